@@ -0,0 +1,139 @@
+use {
+  crate::{
+    cpu,
+    pseudofs,
+    pseudofs::{Read, Write},
+    sysfs,
+    units::Hertz,
+  },
+  log::{debug, info},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+
+  #[error(transparent)] CpuxPseudofs(#[from] crate::pseudofs::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl cpu::NotFound for Error {
+  fn is_not_found(&self) -> bool {
+    matches!(self, Error::CpuxPseudofs(pseudofs::Error::NotFound(_, _)))
+  }
+}
+
+fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Ok(val) => Ok(Some(val)),
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
+  }
+}
+
+pub fn available() -> bool {
+  sysfs::amd_pstate_status().is_file()
+}
+
+pub fn try_status() -> Result<String> {
+  let res = String::read(&sysfs::amd_pstate_status())?;
+  debug!(r#"amd_pstate get_status "{}""#, res);
+  Ok(res)
+}
+
+pub fn status() -> Result<Option<String>> {
+  allow_missing_files(try_status())
+}
+
+// One of active, passive or guided. The cpufreq policies are torn down and recreated by the
+// kernel when the status changes.
+pub fn try_set_status(val: &str) -> Result<()> {
+  info!(r#"amd_pstate set_status "{}""#, val);
+  val.write(&sysfs::amd_pstate_status())?;
+  Ok(())
+}
+
+pub fn set_status(val: &str) -> Result<Option<()>> {
+  allow_missing_files(try_set_status(val))
+}
+
+pub fn try_prefcore() -> Result<String> {
+  let res = String::read(&sysfs::amd_pstate_prefcore())?;
+  debug!(r#"amd_pstate get_prefcore "{}""#, res);
+  Ok(res)
+}
+
+pub fn prefcore() -> Result<Option<String>> {
+  allow_missing_files(try_prefcore())
+}
+
+pub fn try_epp(cpu_id: u64) -> Result<String> {
+  let res = String::read(&sysfs::amd_pstate_epp(cpu_id))?;
+  debug!(r#"amd_pstate get_epp cpu{} "{}""#, cpu_id, res);
+  Ok(res)
+}
+
+pub fn epp(cpu_id: u64) -> Result<Option<String>> {
+  cpu::allow_missing_if_exists(cpu_id, try_epp(cpu_id), allow_missing_files)
+}
+
+// Only available with the amd-pstate-epp driver, i.e. status active.
+pub fn try_set_epp(cpu_id: u64, val: &str) -> Result<()> {
+  info!(r#"amd_pstate set_epp cpu{} "{}""#, cpu_id, val);
+  val.write(&sysfs::amd_pstate_epp(cpu_id))?;
+  Ok(())
+}
+
+pub fn set_epp(cpu_id: u64, val: &str) -> Result<Option<()>> {
+  cpu::allow_missing_if_exists(cpu_id, try_set_epp(cpu_id, val), allow_missing_files)
+}
+
+pub fn try_epps(cpu_id: u64) -> Result<Vec<String>> {
+  let res = Vec::read(&sysfs::amd_pstate_epps(cpu_id))?;
+  debug!(r#"amd_pstate get_epps cpu{} "{}""#, cpu_id, res.join(","));
+  Ok(res)
+}
+
+pub fn epps(cpu_id: u64) -> Result<Option<Vec<String>>> {
+  cpu::allow_missing_if_exists(cpu_id, try_epps(cpu_id), allow_missing_files)
+}
+
+pub fn try_highest_perf(cpu_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::amd_pstate_highest_perf(cpu_id))?;
+  debug!("amd_pstate get_highest_perf cpu{} {}", cpu_id, res);
+  Ok(res)
+}
+
+pub fn highest_perf(cpu_id: u64) -> Result<Option<u64>> {
+  cpu::allow_missing_if_exists(cpu_id, try_highest_perf(cpu_id), allow_missing_files)
+}
+
+pub fn try_lowest_nonlinear_freq(cpu_id: u64) -> Result<Hertz> {
+  let khz = u64::read(&sysfs::amd_pstate_lowest_nonlinear_freq(cpu_id))?;
+  debug!("amd_pstate get_lowest_nonlinear_freq_khz cpu{} {}", cpu_id, khz);
+  Ok(Hertz::from_khz(khz as f64))
+}
+
+pub fn lowest_nonlinear_freq(cpu_id: u64) -> Result<Option<Hertz>> {
+  cpu::allow_missing_if_exists(cpu_id, try_lowest_nonlinear_freq(cpu_id), allow_missing_files)
+}
+
+pub fn try_max_freq(cpu_id: u64) -> Result<Hertz> {
+  let khz = u64::read(&sysfs::amd_pstate_max_freq(cpu_id))?;
+  debug!("amd_pstate get_max_freq_khz cpu{} {}", cpu_id, khz);
+  Ok(Hertz::from_khz(khz as f64))
+}
+
+pub fn max_freq(cpu_id: u64) -> Result<Option<Hertz>> {
+  cpu::allow_missing_if_exists(cpu_id, try_max_freq(cpu_id), allow_missing_files)
+}
+
+// Higher ranked cores are preferred by the scheduler, requires prefcore.
+pub fn try_prefcore_ranking(cpu_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::amd_pstate_prefcore_ranking(cpu_id))?;
+  debug!("amd_pstate get_prefcore_ranking cpu{} {}", cpu_id, res);
+  Ok(res)
+}
+
+pub fn prefcore_ranking(cpu_id: u64) -> Result<Option<u64>> {
+  cpu::allow_missing_if_exists(cpu_id, try_prefcore_ranking(cpu_id), allow_missing_files)
+}
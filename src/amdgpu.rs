@@ -0,0 +1,532 @@
+use {
+  crate::{
+    drm,
+    pseudofs,
+    pseudofs::{Read, Write},
+    sysfs,
+    units::{Hertz, Volts},
+  },
+  log::{debug, info},
+  serde::Serialize,
+  std::path::Path,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+
+  #[error(transparent)] CpuxDrm(#[from] crate::drm::Error),
+  #[error(transparent)] CpuxPseudofs(#[from] crate::pseudofs::Error),
+  #[error(transparent)] CpuxUnits(#[from] crate::units::Error),
+
+  #[error("Error parsing overdrive edit string: {0}")]
+  ParseOdEdit(String),
+
+  #[error("Overdrive {0} has no point {1}")]
+  OdPointNotFound(String, u64),
+
+  #[error("Overdrive range {0} not found")]
+  OdRangeNotFound(String),
+
+  #[error("Overdrive {0} value {1} is outside {2} - {3}")]
+  OdRange(String, String, String, String),
+
+  #[error("Overdrive {0} point {1} requires a voltage")]
+  OdVoltageRequired(String, u64),
+
+  #[error("Overdrive {0} point {1} does not accept a voltage")]
+  OdVoltageUnsupported(String, u64),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Ok(ok) => Ok(Some(ok)),
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
+    Err(err) => Err(err),
+  }
+}
+
+// One line of `pp_dpm_sclk` or `pp_dpm_mclk`, e.g. `1: 1200Mhz *`.
+#[derive(Clone, Debug, Serialize)]
+pub struct DpmLevel {
+  pub id: u64,
+  pub freq: Hertz,
+  pub active: bool,
+}
+
+// One profile of `pp_power_profile_mode`, e.g. ` 1 3D_FULL_SCREEN*:`.
+#[derive(Clone, Debug, Serialize)]
+pub struct PowerProfile {
+  pub id: u64,
+  pub name: String,
+  pub active: bool,
+}
+
+// Levels are numbered, except the deep sleep level `S:` shown by some parts. It is only
+// returned as the current clock. Lines which are not levels are skipped.
+fn parse_dpm(path: &Path, text: &str) -> (Vec<DpmLevel>, Option<Hertz>) {
+  let mut levels = vec![];
+  let mut cur = None;
+  for line in text.lines() {
+    let line = line.trim();
+    let (id, freq) = if let Some(i) = line.find(':') { (&line[..i], line[i+1..].trim()) } else { continue; };
+    let active = freq.ends_with('*');
+    let freq = freq.trim_end_matches('*').trim();
+    let freq = freq.parse::<Hertz>().ok();
+    let freq =
+      if let Some(freq) = freq { freq }
+      else {
+        debug!(r#"amdgpu skip {} "{}""#, path.display(), line);
+        continue;
+      };
+    if active { cur = Some(freq.clone()); }
+    if let Ok(id) = id.trim().parse::<u64>() { levels.push(DpmLevel { id, freq, active }); }
+  }
+  (levels, cur)
+}
+
+// The layout of the profile table differs between generations, but each profile starts on a
+// line with its index and name, and the active profile is marked with `*` before the `:`.
+fn parse_power_profiles(text: &str) -> Vec<PowerProfile> {
+  let mut profiles = vec![];
+  for line in text.lines() {
+    let head = if let Some(i) = line.find(':') { &line[..i] } else { line };
+    let mut parts = head.split_whitespace();
+    let id = if let Some(Ok(id)) = parts.next().map(|p| p.parse::<u64>()) { id } else { continue; };
+    let name = if let Some(name) = parts.next() { name.trim_end_matches('*') } else { continue; };
+    if name.is_empty() || ! name.starts_with(|c: char| c.is_ascii_alphanumeric()) { continue; }
+    profiles.push(PowerProfile { id, name: name.to_string(), active: head.contains('*') });
+  }
+  profiles
+}
+
+// One point of an `OD_SCLK`, `OD_MCLK` or `OD_VDDC_CURVE` section, e.g. `1: 1750Mhz 900mV`.
+#[derive(Clone, Debug, Serialize)]
+pub struct OdPoint {
+  pub id: u64,
+  pub freq: Hertz,
+  pub voltage: Option<Volts>,
+}
+
+// One frequency line of `OD_RANGE`, e.g. `SCLK: 300MHz 2000MHz`.
+#[derive(Clone, Debug, Serialize)]
+pub struct OdFreqRange {
+  pub name: String,
+  pub min: Hertz,
+  pub max: Hertz,
+}
+
+// One voltage line of `OD_RANGE`, e.g. `VDDC: 800mV 1200mV`.
+#[derive(Clone, Debug, Serialize)]
+pub struct OdVoltageRange {
+  pub name: String,
+  pub min: Volts,
+  pub max: Volts,
+}
+
+// The contents of `pp_od_clk_voltage`. Sections absent on a generation are left empty.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct OdTable {
+  pub sclk: Vec<OdPoint>,
+  pub mclk: Vec<OdPoint>,
+  pub vddc_curve: Vec<OdPoint>,
+  pub freq_ranges: Vec<OdFreqRange>,
+  pub voltage_ranges: Vec<OdVoltageRange>,
+}
+
+impl OdTable {
+
+  fn points(&self, section: OdSection) -> &[OdPoint] {
+    match section {
+      OdSection::Sclk => &self.sclk,
+      OdSection::Mclk => &self.mclk,
+      OdSection::VddcCurve => &self.vddc_curve,
+    }
+  }
+
+  pub fn freq_range(&self, name: &str) -> Option<&OdFreqRange> {
+    self.freq_ranges.iter().find(|r| r.name == name)
+  }
+
+  pub fn voltage_range(&self, name: &str) -> Option<&OdVoltageRange> {
+    self.voltage_ranges.iter().find(|r| r.name == name)
+  }
+
+  // The ranges which apply to a point: SCLK/MCLK and VDDC for the clock sections, and the
+  // per-point VDDC_CURVE_SCLK[n]/VDDC_CURVE_VOLT[n] for the voltage curve.
+  pub fn ranges(&self, section: OdSection, point_id: u64) -> (Option<&OdFreqRange>, Option<&OdVoltageRange>) {
+    match section {
+      OdSection::Sclk => (self.freq_range("SCLK"), self.voltage_range("VDDC")),
+      OdSection::Mclk => (self.freq_range("MCLK"), self.voltage_range("VDDC")),
+      OdSection::VddcCurve => (
+        self.freq_range(&format!("VDDC_CURVE_SCLK[{}]", point_id)),
+        self.voltage_range(&format!("VDDC_CURVE_VOLT[{}]", point_id)),
+      ),
+    }
+  }
+
+  // Rejects edits of unknown points and values outside of `OD_RANGE`. A value without a range
+  // cannot be checked, and is rejected too.
+  pub fn validate(&self, edit: &OdEdit) -> Result<()> {
+    let section = edit.section;
+    let point = self.points(section).iter().find(|p| p.id == edit.id)
+      .ok_or_else(|| Error::OdPointNotFound(section.to_string(), edit.id))?;
+    match (&point.voltage, &edit.voltage) {
+      (Some(_), None) => return Err(Error::OdVoltageRequired(section.to_string(), edit.id)),
+      (None, Some(_)) => return Err(Error::OdVoltageUnsupported(section.to_string(), edit.id)),
+      _ => (),
+    }
+    let (freq_range, voltage_range) = self.ranges(section, edit.id);
+    let freq_range = freq_range.ok_or_else(|| Error::OdRangeNotFound(format!("{} {}", section, edit.id)))?;
+    if edit.freq.hz() < freq_range.min.hz() || edit.freq.hz() > freq_range.max.hz() {
+      return Err(Error::OdRange(
+        freq_range.name.clone(), edit.freq.to_string(), freq_range.min.to_string(), freq_range.max.to_string(),
+      ));
+    }
+    if let Some(voltage) = &edit.voltage {
+      let voltage_range = voltage_range.ok_or_else(|| Error::OdRangeNotFound(format!("{} {} voltage", section, edit.id)))?;
+      if voltage.millivolts() < voltage_range.min.millivolts() || voltage.millivolts() > voltage_range.max.millivolts() {
+        return Err(Error::OdRange(
+          voltage_range.name.clone(), voltage.to_string(), voltage_range.min.to_string(), voltage_range.max.to_string(),
+        ));
+      }
+    }
+    Ok(())
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OdSection {
+  Sclk,
+  Mclk,
+  VddcCurve,
+}
+
+impl OdSection {
+
+  // The command prefix accepted by `pp_od_clk_voltage`.
+  fn command(&self) -> &'static str {
+    match self {
+      Self::Sclk => "s",
+      Self::Mclk => "m",
+      Self::VddcCurve => "vc",
+    }
+  }
+}
+
+impl std::fmt::Display for OdSection {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let s = match self {
+      Self::Sclk => "sclk",
+      Self::Mclk => "mclk",
+      Self::VddcCurve => "vc",
+    };
+    write!(f, "{}", s)
+  }
+}
+
+// A single staged edit of `pp_od_clk_voltage`, e.g. `s 1 2000 1100` or `vc 2 2000 1100`.
+#[derive(Clone, Debug)]
+pub struct OdEdit {
+  pub section: OdSection,
+  pub id: u64,
+  pub freq: Hertz,
+  pub voltage: Option<Volts>,
+}
+
+impl OdEdit {
+
+  pub fn command(&self) -> String {
+    let mut res = format!("{} {} {}", self.section.command(), self.id, self.freq.mhz().round() as u64);
+    if let Some(voltage) = &self.voltage { res.push_str(&format!(" {}", voltage.millivolts())); }
+    res
+  }
+}
+
+impl std::str::FromStr for OdEdit {
+  type Err = Error;
+
+  // `<sclk|mclk|vc>:<point>:<freq>[:<voltage>]`, e.g. `sclk:1:2000mhz`, `vc:2:2000mhz:1100mv`.
+  fn from_str(s: &str) -> Result<Self> {
+    let parts: Vec<&str> = s.trim().split(':').collect();
+    let err = || Error::ParseOdEdit(s.to_string());
+    let (section, id, freq, voltage) =
+      match parts[..] {
+        [section, id, freq] => (section, id, freq, None),
+        [section, id, freq, voltage] => (section, id, freq, Some(voltage)),
+        _ => return Err(err()),
+      };
+    let section =
+      match &section.to_lowercase()[..] {
+        "s" | "sclk" => OdSection::Sclk,
+        "m" | "mclk" => OdSection::Mclk,
+        "vc" | "curve" => OdSection::VddcCurve,
+        _ => return Err(err()),
+      };
+    let id = id.parse::<u64>().map_err(|_| err())?;
+    if freq.len() <= 3 { return Err(err()); }
+    let freq = freq.parse::<Hertz>()?;
+    let voltage = if let Some(voltage) = voltage { Some(voltage.parse::<Volts>()?) } else { None };
+    if section == OdSection::VddcCurve && voltage.is_none() { return Err(err()); }
+    Ok(Self { section, id, freq, voltage })
+  }
+}
+
+// The file is split in sections, each starting with a line `OD_<NAME>:`. Points are
+// `<id>: <freq> [<voltage>]` and ranges are `<name>: <min> <max>`. Unknown sections, such as
+// `OD_VDDGFX_OFFSET`, and lines which cannot be parsed are skipped.
+fn parse_od(path: &Path, text: &str) -> OdTable {
+  enum Section { Points(OdSection), Range, Other }
+  let mut table = OdTable::default();
+  let mut cur = Section::Other;
+  let parse_freq = |s: &str| s.parse::<Hertz>().ok();
+  for line in text.lines() {
+    let line = line.trim();
+    if line.starts_with("OD_") && line.ends_with(':') {
+      cur =
+        match line {
+          "OD_SCLK:" => Section::Points(OdSection::Sclk),
+          "OD_MCLK:" => Section::Points(OdSection::Mclk),
+          "OD_VDDC_CURVE:" => Section::Points(OdSection::VddcCurve),
+          "OD_RANGE:" => Section::Range,
+          _ => Section::Other,
+        };
+      continue;
+    }
+    let (head, tail) = if let Some(i) = line.find(':') { (line[..i].trim(), &line[i+1..]) } else { continue; };
+    let vals: Vec<&str> = tail.split_whitespace().collect();
+    let parsed =
+      match &cur {
+        Section::Points(section) => {
+          let point =
+            match (head.parse::<u64>(), &vals[..]) {
+              (Ok(id), [freq]) => parse_freq(freq).map(|freq| OdPoint { id, freq, voltage: None }),
+              (Ok(id), [freq, voltage]) =>
+                parse_freq(freq).zip(voltage.parse::<Volts>().ok())
+                  .map(|(freq, voltage)| OdPoint { id, freq, voltage: Some(voltage) }),
+              _ => None,
+            };
+          let points =
+            match section {
+              OdSection::Sclk => &mut table.sclk,
+              OdSection::Mclk => &mut table.mclk,
+              OdSection::VddcCurve => &mut table.vddc_curve,
+            };
+          point.map(|p| points.push(p)).is_some()
+        },
+        Section::Range =>
+          match &vals[..] {
+            [min, max] if min.to_lowercase().ends_with("hz") =>
+              parse_freq(min).zip(parse_freq(max))
+                .map(|(min, max)| table.freq_ranges.push(OdFreqRange { name: head.to_string(), min, max }))
+                .is_some(),
+            [min, max] =>
+              min.parse::<Volts>().ok().zip(max.parse::<Volts>().ok())
+                .map(|(min, max)| table.voltage_ranges.push(OdVoltageRange { name: head.to_string(), min, max }))
+                .is_some(),
+            _ => false,
+          },
+        Section::Other => true,
+      };
+    if ! parsed { debug!(r#"amdgpu skip {} "{}""#, path.display(), line); }
+  }
+  table
+}
+
+pub fn available() -> bool {
+  sysfs::amdgpu_module().is_dir()
+}
+
+pub fn try_cards() -> Result<Vec<u64>> {
+  let mut cards: Vec<u64> = vec![];
+  for card_id in drm::try_cards()? {
+    if drm::try_card_driver(card_id)?.eq("amdgpu") {
+      cards.push(card_id);
+    }
+  }
+  cards.sort_unstable();
+  Ok(cards)
+}
+
+pub fn cards() -> Result<Option<Vec<u64>>> {
+  match try_cards() {
+    Ok(cards) => Ok(Some(cards)),
+    Err(Error::CpuxDrm(drm::Error::StdIo(err))) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+    Err(err) => Err(err),
+  }
+}
+
+pub fn try_performance_level(card_id: u64) -> Result<String> {
+  let res = String::read(&sysfs::amdgpu_performance_level(card_id))?;
+  debug!(r#"amdgpu get_performance_level card{} "{}""#, card_id, res);
+  Ok(res)
+}
+
+pub fn performance_level(card_id: u64) -> Result<Option<String>> {
+  allow_missing_files(try_performance_level(card_id))
+}
+
+// e.g. auto, low, high, manual, profile_standard, profile_peak.
+pub fn try_set_performance_level(card_id: u64, val: &str) -> Result<()> {
+  info!(r#"amdgpu set_performance_level card{} "{}""#, card_id, val);
+  val.write(&sysfs::amdgpu_performance_level(card_id))?;
+  Ok(())
+}
+
+pub fn set_performance_level(card_id: u64, val: &str) -> Result<Option<()>> {
+  allow_missing_files(try_set_performance_level(card_id, val))
+}
+
+pub fn try_power_profiles(card_id: u64) -> Result<Vec<PowerProfile>> {
+  let text = String::read(&sysfs::amdgpu_power_profile_mode(card_id))?;
+  let res = parse_power_profiles(&text);
+  debug!(r#"amdgpu get_power_profiles card{} "{}""#, card_id, res.iter().map(|p| p.name.clone()).collect::<Vec<String>>().join(","));
+  Ok(res)
+}
+
+pub fn power_profiles(card_id: u64) -> Result<Option<Vec<PowerProfile>>> {
+  allow_missing_files(try_power_profiles(card_id))
+}
+
+pub fn power_profile(card_id: u64) -> Result<Option<PowerProfile>> {
+  Ok(power_profiles(card_id)?.and_then(|p| p.into_iter().find(|p| p.active)))
+}
+
+pub fn try_set_power_profile(card_id: u64, profile_id: u64) -> Result<()> {
+  info!("amdgpu set_power_profile card{} {}", card_id, profile_id);
+  profile_id.write(&sysfs::amdgpu_power_profile_mode(card_id))?;
+  Ok(())
+}
+
+pub fn set_power_profile(card_id: u64, profile_id: u64) -> Result<Option<()>> {
+  allow_missing_files(try_set_power_profile(card_id, profile_id))
+}
+
+pub fn try_sclk_levels(card_id: u64) -> Result<Vec<DpmLevel>> {
+  let path = sysfs::amdgpu_dpm_sclk(card_id);
+  let (res, _) = parse_dpm(&path, &String::read(&path)?);
+  debug!("amdgpu get_sclk_levels card{} {}", card_id, res.len());
+  Ok(res)
+}
+
+pub fn sclk_levels(card_id: u64) -> Result<Option<Vec<DpmLevel>>> {
+  allow_missing_files(try_sclk_levels(card_id))
+}
+
+pub fn try_sclk(card_id: u64) -> Result<Option<Hertz>> {
+  let path = sysfs::amdgpu_dpm_sclk(card_id);
+  let (_, res) = parse_dpm(&path, &String::read(&path)?);
+  debug!("amdgpu get_sclk card{} {:?}", card_id, res.as_ref().map(Hertz::hz));
+  Ok(res)
+}
+
+pub fn sclk(card_id: u64) -> Result<Option<Hertz>> {
+  Ok(allow_missing_files(try_sclk(card_id))?.flatten())
+}
+
+// Restricts the shader clock to the given levels, requires performance level manual.
+pub fn try_set_sclk_levels(card_id: u64, levels: &[u64]) -> Result<()> {
+  let val = levels.iter().map(u64::to_string).collect::<Vec<String>>().join(" ");
+  info!(r#"amdgpu set_sclk_levels card{} "{}""#, card_id, val);
+  val.as_str().write(&sysfs::amdgpu_dpm_sclk(card_id))?;
+  Ok(())
+}
+
+pub fn set_sclk_levels(card_id: u64, levels: &[u64]) -> Result<Option<()>> {
+  allow_missing_files(try_set_sclk_levels(card_id, levels))
+}
+
+pub fn try_mclk_levels(card_id: u64) -> Result<Vec<DpmLevel>> {
+  let path = sysfs::amdgpu_dpm_mclk(card_id);
+  let (res, _) = parse_dpm(&path, &String::read(&path)?);
+  debug!("amdgpu get_mclk_levels card{} {}", card_id, res.len());
+  Ok(res)
+}
+
+pub fn mclk_levels(card_id: u64) -> Result<Option<Vec<DpmLevel>>> {
+  allow_missing_files(try_mclk_levels(card_id))
+}
+
+pub fn try_mclk(card_id: u64) -> Result<Option<Hertz>> {
+  let path = sysfs::amdgpu_dpm_mclk(card_id);
+  let (_, res) = parse_dpm(&path, &String::read(&path)?);
+  debug!("amdgpu get_mclk card{} {:?}", card_id, res.as_ref().map(Hertz::hz));
+  Ok(res)
+}
+
+pub fn mclk(card_id: u64) -> Result<Option<Hertz>> {
+  Ok(allow_missing_files(try_mclk(card_id))?.flatten())
+}
+
+// Restricts the memory clock to the given levels, requires performance level manual.
+pub fn try_set_mclk_levels(card_id: u64, levels: &[u64]) -> Result<()> {
+  let val = levels.iter().map(u64::to_string).collect::<Vec<String>>().join(" ");
+  info!(r#"amdgpu set_mclk_levels card{} "{}""#, card_id, val);
+  val.as_str().write(&sysfs::amdgpu_dpm_mclk(card_id))?;
+  Ok(())
+}
+
+pub fn set_mclk_levels(card_id: u64, levels: &[u64]) -> Result<Option<()>> {
+  allow_missing_files(try_set_mclk_levels(card_id, levels))
+}
+
+pub fn try_od_table(card_id: u64) -> Result<OdTable> {
+  let path = sysfs::amdgpu_od_clk_voltage(card_id);
+  let res = parse_od(&path, &String::read(&path)?);
+  debug!(
+    "amdgpu get_od_table card{} sclk={} mclk={} vc={} ranges={}",
+    card_id, res.sclk.len(), res.mclk.len(), res.vddc_curve.len(), res.freq_ranges.len() + res.voltage_ranges.len(),
+  );
+  Ok(res)
+}
+
+pub fn od_table(card_id: u64) -> Result<Option<OdTable>> {
+  allow_missing_files(try_od_table(card_id))
+}
+
+// Stages an edit, which takes effect on commit. The edit is not validated, see `try_set_od`.
+pub fn try_stage_od(card_id: u64, edit: &OdEdit) -> Result<()> {
+  let val = edit.command();
+  info!(r#"amdgpu stage_od card{} "{}""#, card_id, val);
+  val.as_str().write(&sysfs::amdgpu_od_clk_voltage(card_id))?;
+  Ok(())
+}
+
+pub fn stage_od(card_id: u64, edit: &OdEdit) -> Result<Option<()>> {
+  allow_missing_files(try_stage_od(card_id, edit))
+}
+
+pub fn try_commit_od(card_id: u64) -> Result<()> {
+  info!(r#"amdgpu commit_od card{} "c""#, card_id);
+  "c".write(&sysfs::amdgpu_od_clk_voltage(card_id))?;
+  Ok(())
+}
+
+pub fn commit_od(card_id: u64) -> Result<Option<()>> {
+  allow_missing_files(try_commit_od(card_id))
+}
+
+// Restores the default clocks and voltages.
+pub fn try_reset_od(card_id: u64) -> Result<()> {
+  info!(r#"amdgpu reset_od card{} "r""#, card_id);
+  "r".write(&sysfs::amdgpu_od_clk_voltage(card_id))?;
+  Ok(())
+}
+
+pub fn reset_od(card_id: u64) -> Result<Option<()>> {
+  allow_missing_files(try_reset_od(card_id))
+}
+
+// Validates every edit against the current table before anything is written, then stages the
+// edits and commits them. Requires performance level manual.
+pub fn try_set_od(card_id: u64, edits: &[OdEdit]) -> Result<()> {
+  let table = try_od_table(card_id)?;
+  for edit in edits { table.validate(edit)?; }
+  for edit in edits { try_stage_od(card_id, edit)?; }
+  try_commit_od(card_id)
+}
+
+pub fn set_od(card_id: u64, edits: &[OdEdit]) -> Result<Option<()>> {
+  allow_missing_files(try_set_od(card_id, edits))
+}
@@ -0,0 +1,291 @@
+use {
+  crate::sysfs,
+  log::{debug, info, warn},
+  std::{
+    collections::BTreeMap,
+    fs,
+    os::unix::fs::{PermissionsExt, symlink},
+    path::{Component, Path, PathBuf},
+  },
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+
+  #[error("{0}: destination already exists")]
+  Exists(PathBuf),
+
+  #[error("{0}: {1}")]
+  Io(PathBuf, std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// Attributes read by cpux, relative to the sysfs root. `*` matches within one path component.
+// Symlinks met along the way are recorded and followed, a symlink at the end of a pattern is
+// recorded but not followed.
+const ATTRIBUTES: &[&str] = &[
+  "sys/devices/system/cpu/present",
+  "sys/devices/system/cpu/possible",
+  "sys/devices/system/cpu/online",
+  "sys/devices/system/cpu/cpu*",
+  "sys/devices/system/cpu/cpu*/online",
+  "sys/devices/system/cpu/cpu*/power/energy_perf_bias",
+  "sys/devices/system/cpu/cpu*/topology/core_id",
+  "sys/devices/system/cpu/cpu*/topology/physical_package_id",
+  "sys/devices/system/cpu/cpu*/cpufreq",
+  "sys/devices/system/cpu/cpu*/cpufreq/*",
+  "sys/devices/system/cpu/cpufreq/*",
+  "sys/devices/system/cpu/cpufreq/policy*/*",
+  "sys/devices/system/cpu/cpuidle",
+  "sys/devices/system/cpu/cpuidle/*",
+  "sys/devices/system/cpu/cpu*/cpuidle",
+  "sys/devices/system/cpu/cpu*/cpuidle/state*",
+  "sys/devices/system/cpu/cpu*/cpuidle/state*/*",
+  "sys/devices/system/cpu/intel_pstate/*",
+  "sys/devices/system/cpu/amd_pstate/*",
+  "sys/class/drm",
+  "sys/class/drm/card*",
+  "sys/class/drm/card*/device",
+  "sys/class/drm/card*/device/driver",
+  "sys/class/drm/card*/device/vendor",
+  "sys/class/drm/card*/device/device",
+  "sys/class/drm/card*/device/subsystem_vendor",
+  "sys/class/drm/card*/device/subsystem_device",
+  "sys/class/drm/card*/device/boot_vga",
+  "sys/class/drm/card*/device/drm",
+  "sys/class/drm/card*/device/drm/renderD*",
+  "sys/class/drm/card*/gt_*",
+  "sys/class/drm/card*/gt",
+  "sys/class/drm/card*/gt/gt*",
+  "sys/class/drm/card*/gt/gt*/rps_*",
+  "sys/class/drm/card*/gt/gt*/rc6_*",
+  "sys/class/drm/card*/gt/gt*/throttle_reason_*",
+  "sys/class/drm/card*/device/power_dpm_force_performance_level",
+  "sys/class/drm/card*/device/pp_power_profile_mode",
+  "sys/class/drm/card*/device/pp_dpm_*",
+  "sys/class/drm/card*/device/pp_od_clk_voltage",
+  "sys/class/drm/card*/device/hwmon",
+  "sys/class/drm/card*/device/hwmon/hwmon*",
+  "sys/class/drm/card*/device/tile*",
+  "sys/class/drm/card*/device/tile*/gt*",
+  "sys/class/drm/card*/device/tile*/gt*/freq0/*_freq",
+  "sys/module/i915",
+  "sys/module/amdgpu",
+  "sys/module/xe",
+  "sys/class/hwmon",
+  "sys/class/hwmon/hwmon*",
+  "sys/class/hwmon/hwmon*/name",
+  "sys/class/hwmon/hwmon*/temp*_input",
+  "sys/class/hwmon/hwmon*/temp*_label",
+  "sys/class/hwmon/hwmon*/temp*_crit",
+  "sys/class/hwmon/hwmon*/temp*_max",
+  "sys/class/hwmon/hwmon*/fan*_input",
+  "sys/class/hwmon/hwmon*/in*_input",
+  "sys/class/hwmon/hwmon*/pwm*",
+  "sys/class/hwmon/hwmon*/power1_*",
+  "sys/class/devfreq",
+  "sys/class/devfreq/*",
+  "sys/class/devfreq/*/cur_freq",
+  "sys/class/devfreq/*/min_freq",
+  "sys/class/devfreq/*/max_freq",
+  "sys/class/devfreq/*/available_frequencies",
+  "sys/class/devfreq/*/governor",
+  "sys/class/devfreq/*/available_governors",
+  "sys/class/devfreq/*/trans_stat",
+  "sys/class/powercap",
+  "sys/class/powercap/intel-rapl:*",
+  "sys/class/powercap/intel-rapl:*/name",
+  "sys/class/powercap/intel-rapl:*/enabled",
+  "sys/class/powercap/intel-rapl:*/energy_uj",
+  "sys/class/powercap/intel-rapl:*/max_energy_range_uj",
+  "sys/class/powercap/intel-rapl:*/constraint_*",
+];
+
+// Upper bound on symlinks followed while resolving one path.
+const MAX_LINKS: usize = 8;
+
+#[derive(Debug)]
+enum Entry {
+  Dir(u32),
+  File(u32, Vec<u8>),
+  Symlink(PathBuf),
+}
+
+fn io<T>(path: &Path, result: std::io::Result<T>) -> Result<T> {
+  result.map_err(|e| Error::Io(path.to_path_buf(), e))
+}
+
+fn matches(pattern: &str, name: &str) -> bool {
+  match pattern.find('*') {
+    Some(i) => {
+      let (prefix, suffix) = (&pattern[..i], &pattern[i+1..]);
+      name.len() >= prefix.len() + suffix.len() && name.starts_with(prefix) && name.ends_with(suffix)
+    },
+    None => pattern == name,
+  }
+}
+
+// Resolve `target` relative to `dir` without touching the filesystem. Returns `None` if the
+// result would escape the root.
+fn resolve(dir: &Path, target: &Path) -> Option<PathBuf> {
+  let mut res = if target.is_absolute() { PathBuf::new() } else { dir.to_path_buf() };
+  for c in target.components() {
+    match c {
+      Component::Normal(c) => res.push(c),
+      Component::ParentDir => if ! res.pop() { return None; },
+      Component::CurDir | Component::RootDir | Component::Prefix(_) => continue,
+    }
+  }
+  Some(res)
+}
+
+struct Capture {
+  root: PathBuf,
+  entries: BTreeMap<PathBuf, Entry>,
+}
+
+impl Capture {
+
+  fn new(root: PathBuf) -> Self { Self { root, entries: BTreeMap::new() } }
+
+  fn glob(&self, pattern: &str) -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::new()];
+    for part in pattern.split('/') {
+      let mut next = vec![];
+      for path in paths {
+        if part.contains('*') {
+          let ents = if let Ok(ents) = fs::read_dir(self.root.join(&path)) { ents } else { continue; };
+          for ent in ents.flatten() {
+            let name = ent.file_name();
+            let name = if let Some(name) = name.to_str() { name } else { continue; };
+            if matches(part, name) { next.push(path.join(name)); }
+          }
+        } else {
+          let path = path.join(part);
+          if fs::symlink_metadata(self.root.join(&path)).is_ok() { next.push(path); }
+        }
+      }
+      paths = next;
+    }
+    paths.sort();
+    paths
+  }
+
+  fn record(&mut self, rel: &Path) -> Result<()> {
+    let mut parts: Vec<Component> = rel.components().rev().collect();
+    let mut cur = PathBuf::new();
+    let mut links = 0;
+    while let Some(part) = parts.pop() {
+      cur.push(part);
+      let path = self.root.join(&cur);
+      let meta = io(&path, fs::symlink_metadata(&path))?;
+      if meta.file_type().is_symlink() {
+        let target = io(&path, fs::read_link(&path))?;
+        self.entries.insert(cur.clone(), Entry::Symlink(target.clone()));
+        if parts.is_empty() { return Ok(()); }
+        links += 1;
+        if links > MAX_LINKS {
+          warn!("capture too many symlinks {}", path.display());
+          return Ok(());
+        }
+        cur = match resolve(cur.parent().unwrap_or_else(|| Path::new("")), &target) {
+          Some(cur) => cur,
+          None => {
+            debug!("capture symlink leaves root {} -> {}", path.display(), target.display());
+            return Ok(());
+          },
+        };
+        continue;
+      }
+      let mode = meta.permissions().mode() & 0o7777;
+      if meta.is_dir() {
+        self.entries.entry(cur.clone()).or_insert(Entry::Dir(mode));
+      } else if parts.is_empty() {
+        match fs::read(&path) {
+          Ok(data) => { self.entries.insert(cur.clone(), Entry::File(mode, data)); },
+          Err(err) => debug!("capture skip {} {}", path.display(), err),
+        }
+      }
+    }
+    Ok(())
+  }
+
+  fn collect(&mut self) -> Result<()> {
+    for pattern in ATTRIBUTES {
+      for path in self.glob(pattern) {
+        self.record(&path)?;
+      }
+    }
+    Ok(())
+  }
+
+  fn write_dir(&self, dest: &Path) -> Result<()> {
+    io(dest, fs::create_dir_all(dest))?;
+    for (rel, ent) in &self.entries {
+      let path = dest.join(rel);
+      if let Some(parent) = path.parent() { io(parent, fs::create_dir_all(parent))?; }
+      match ent {
+        Entry::Dir(_) => io(&path, fs::create_dir_all(&path))?,
+        Entry::File(mode, data) => {
+          io(&path, fs::write(&path, data))?;
+          io(&path, fs::set_permissions(&path, fs::Permissions::from_mode(*mode)))?;
+        },
+        Entry::Symlink(target) => io(&path, symlink(target, &path))?,
+      }
+    }
+    // Directory modes last, so read-only directories can still be filled. The owner keeps
+    // write access so the fixture can be removed again.
+    for (rel, ent) in self.entries.iter().rev() {
+      if let Entry::Dir(mode) = ent {
+        let path = dest.join(rel);
+        io(&path, fs::set_permissions(&path, fs::Permissions::from_mode(*mode | 0o700)))?;
+      }
+    }
+    Ok(())
+  }
+
+  fn write_tar(&self, dest: &Path) -> Result<()> {
+    let file = io(dest, fs::File::create(dest))?;
+    let mut tar = tar::Builder::new(file);
+    for (rel, ent) in &self.entries {
+      let mut header = tar::Header::new_gnu();
+      header.set_mtime(0);
+      match ent {
+        Entry::Dir(mode) => {
+          header.set_entry_type(tar::EntryType::Directory);
+          header.set_mode(*mode);
+          header.set_size(0);
+          io(dest, tar.append_data(&mut header, rel, std::io::empty()))?;
+        },
+        Entry::File(mode, data) => {
+          header.set_entry_type(tar::EntryType::Regular);
+          header.set_mode(*mode);
+          header.set_size(data.len() as u64);
+          io(dest, tar.append_data(&mut header, rel, &data[..]))?;
+        },
+        Entry::Symlink(target) => {
+          header.set_entry_type(tar::EntryType::Symlink);
+          header.set_mode(0o777);
+          header.set_size(0);
+          io(dest, tar.append_link(&mut header, rel, target))?;
+        },
+      }
+    }
+    io(dest, tar.finish())?;
+    Ok(())
+  }
+}
+
+pub fn is_tar<P: AsRef<Path>>(dest: P) -> bool {
+  dest.as_ref().extension().map(|e| e == "tar").unwrap_or(false)
+}
+
+pub fn capture<P: AsRef<Path>>(dest: P) -> Result<()> {
+  let dest = dest.as_ref();
+  if dest.exists() { return Err(Error::Exists(dest.to_path_buf())); }
+  let mut capture = Capture::new(sysfs::root());
+  capture.collect()?;
+  info!("capture {} entries from {} to {}", capture.entries.len(), capture.root.display(), dest.display());
+  if is_tar(dest) { capture.write_tar(dest) } else { capture.write_dir(dest) }
+}
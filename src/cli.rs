@@ -1,34 +1,267 @@
 use {
   crate::{
+    amd_pstate,
+    amdgpu::{self, OdEdit, OdSection},
+    capture,
     cpu,
     cpufreq,
+    cpuidle,
+    devfreq,
+    drm,
+    hwmon::{self, PwmMode},
     i915,
     intel_pstate as pstate,
-    units::{Hertz, HertzUnit},
-    utils::{Indices, Toggles},
+    powercap::{self, ZoneId},
+    summary::{self, Select, Summary},
+    sysfs,
+    turbo,
+    units::{Celsius, Hertz, HertzUnit, Watts},
+    utils::{Card, Cards, Indices, Toggles},
+    xe,
   },
   fern,
-  log::{LevelFilter, error},
+  log::{LevelFilter, debug, error},
   tabular::{Row, Table},
+  std::{
+    io::Write,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+  },
   structopt::StructOpt,
 };
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
 
+  #[error(transparent)] CpuxAmdPstate(#[from] crate::amd_pstate::Error),
+  #[error(transparent)] CpuxAmdgpu(#[from] crate::amdgpu::Error),
+  #[error(transparent)] CpuxCapture(#[from] crate::capture::Error),
   #[error(transparent)] CpuxCpu(#[from] crate::cpu::Error),
   #[error(transparent)] CpuxCpufreq(#[from] crate::cpufreq::Error),
+  #[error(transparent)] CpuxCpuidle(#[from] crate::cpuidle::Error),
+  #[error(transparent)] CpuxDevfreq(#[from] crate::devfreq::Error),
+  #[error(transparent)] CpuxDrm(#[from] crate::drm::Error),
+  #[error(transparent)] CpuxHwmon(#[from] crate::hwmon::Error),
   #[error(transparent)] CpuxI915(#[from] crate::i915::Error),
   #[error(transparent)] CpuxIntelPstate(#[from] crate::intel_pstate::Error),
+  #[error(transparent)] CpuxPowercap(#[from] crate::powercap::Error),
+  #[error(transparent)] CpuxSummary(#[from] crate::summary::Error),
+  #[error(transparent)] CpuxTurbo(#[from] crate::turbo::Error),
+  #[error(transparent)] CpuxXe(#[from] crate::xe::Error),
   #[error(transparent)] LogSetLogger(#[from] log::SetLoggerError),
+  #[error(transparent)] SerdeJson(#[from] serde_json::Error),
+
+  #[error("Unknown output format: {0}")]
+  ParseFormat(String),
+
+  #[error("csv and tsv output print one kind of row, --{0} cannot be combined with --{1}")]
+  RowsMixed(&'static str, &'static str),
+
+  #[error("csv and tsv output print one kind of row, --cards selects card{0} ({1}) which has no {2} rows")]
+  RowsCardMixed(u64, String, &'static str),
+
+  #[error("--cards: no such card: {0}")]
+  CardNotFound(Card),
+
+  #[error("--i915-gts matches no i915 GT")]
+  I915GtNotFound,
+
+  #[error("--xe-tiles and --xe-gts match no xe GT")]
+  XeGtNotFound,
+
+  #[error("idle state not found on any target CPU: {0}")]
+  IdleStateNotFound(String),
+
+  #[error("intel_pstate performance {0}% is outside the allowed range 0-100")]
+  PstatePerfRange(u64),
+
+  #[error("--pstate-perf-min {0}% is above --pstate-perf-max {1}%")]
+  PstatePerfMinAboveMax(u64, u64),
+
+  #[error("cpufreq governor not available: {0}, available governors: {1}")]
+  GovernorNotAvailable(String, String),
+
+  #[error("cpuidle governor not available: {0}, available governors: {1}")]
+  IdleGovernorNotAvailable(String, String),
+
+  #[error("policy{0} has no online cpu, bring one online or use --cpu-on true to change it")]
+  PolicyOffline(u64),
+
+  #[error("policy{0} has no energy/performance preference in the current driver mode")]
+  EppNotSupported(u64),
+
+  #[error("energy/performance preference not available: {0}, available preferences: {1}")]
+  EppNotAvailable(String, String),
+
+  #[error("no turbo control found, tried intel_pstate/no_turbo, cpufreq/boost and policy boost")]
+  TurboNotAvailable,
+
+  #[error("devfreq device not found: {0}")]
+  DevfreqDeviceNotFound(String),
+
+  #[error(transparent)] StdIo(#[from] std::io::Error),
+  #[error(transparent)] Ctrlc(#[from] ctrlc::Error),
+
+  #[error("hwmon chip not found: {0}")]
+  HwmonChipNotFound(String),
+
+  #[error("hwmon chip name matches more than one chip, use the id instead: {0}")]
+  HwmonChipAmbiguous(String),
+
+  #[error("--hwmon-chip is required for fan control")]
+  HwmonPwmNoChip,
+
+  #[error("hwmon{0} has no pwm{1}")]
+  HwmonPwmNotFound(u64, u64),
+
+  #[error("pwm value {0} is outside the allowed range {1}-255, see --hwmon-pwm-min")]
+  HwmonPwmRange(u64, u64),
+
+  #[error("manual fan control requires a value, see --hwmon-pwm")]
+  HwmonPwmManualNoValue,
+
+  #[error("--hwmon-pwm requires manual fan control, not {0}")]
+  HwmonPwmModeConflict(PwmMode),
+
+  #[error("card{0} has no power profile {1}")]
+  AmdgpuProfileNotFound(u64, String),
+
+  #[error("card{0} has no {1} level {2}")]
+  AmdgpuLevelNotFound(u64, &'static str, u64),
+
+  #[error("clock levels and overdrive require performance level manual, not {0}")]
+  AmdgpuLevelsNotManual(String),
+
+  #[error("rapl zone not found: {0}")]
+  RaplZoneNotFound(ZoneId),
+
+  #[error("{0} has no {1} power limit")]
+  RaplConstraintNotFound(ZoneId, &'static str),
+
+  #[error("{0} {1} power limit {2} is above the maximum {3}")]
+  RaplLimitRange(ZoneId, &'static str, Watts, Watts),
+
+  #[error("card{0} power cap {1} is outside {2} - {3}")]
+  GpuPowerCapRange(u64, Watts, String, String),
 }
 
 type Result<T> = std::result::Result<T, Error>;
 
+// pwm channels to put back when cpux exits, see --hwmon-pwm-hold.
+static PWM_RESTORE: Mutex<Vec<hwmon::PwmState>> = Mutex::new(Vec::new());
+
+fn restore_pwms() {
+  let states = std::mem::take(&mut *PWM_RESTORE.lock().unwrap_or_else(|e| e.into_inner()));
+  for state in states.iter().rev() {
+    if let Err(err) = state.restore() { error!("{}", err); }
+  }
+}
+
+// Restores held pwm channels when dropped, also while unwinding from a panic.
+struct PwmRestoreGuard;
+
+impl Drop for PwmRestoreGuard {
+  fn drop(&mut self) { restore_pwms(); }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Format {
+  Table,
+  Json,
+  Csv,
+  Tsv,
+}
+
+impl std::str::FromStr for Format {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match &s.to_lowercase()[..] {
+      "table" => Ok(Self::Table),
+      "json" => Ok(Self::Json),
+      "csv" => Ok(Self::Csv),
+      "tsv" => Ok(Self::Tsv),
+      _ => Err(Error::ParseFormat(s.to_string())),
+    }
+  }
+}
+
+// The kind of CSV/TSV row, CPU rows unless a GPU or devfreq summary is given.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Rows {
+  Cpu,
+  I915,
+  Xe,
+  Amdgpu,
+  Devfreq,
+}
+
+impl Rows {
+
+  // The argument which selects the rows.
+  fn arg(self) -> &'static str {
+    match self {
+      Self::Cpu => "cpu",
+      Self::I915 => "i915",
+      Self::Xe => "xe",
+      Self::Amdgpu => "amdgpu",
+      Self::Devfreq => "devfreq",
+    }
+  }
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Command {
+
+  #[structopt(about="Copy the sysfs attributes read by cpux into a fixture directory or .tar archive")]
+  Capture {
+
+    #[structopt(parse(from_os_str), help="Destination directory, or archive if the name ends in .tar")]
+    dest: PathBuf,
+  },
+}
+
 #[derive(Debug, StructOpt)]
 #[structopt(about="View and set CPU and related parameters.")]
 pub struct Cli {
 
+  #[structopt(subcommand)]
+  command: Option<Command>,
+
+  #[structopt(long, takes_value=false, help="Prints AMD pstate driver summary, default if detected")]
+  amd_pstate: bool,
+
+  #[structopt(long, value_name="pref", env="CPUX_AMD_PSTATE_EPP", help="AMD pstate energy/performance preference (per --cpus)")]
+  amd_pstate_epp: Option<String>,
+
+  #[structopt(long, value_name="status", possible_values=&["active", "passive", "guided"], env="CPUX_AMD_PSTATE_STATUS", help="AMD pstate driver mode, active, passive or guided")]
+  amd_pstate_status: Option<String>,
+
+  #[structopt(long, takes_value=false, help="Prints AMD GPU driver summary, default if detected")]
+  amdgpu: bool,
+
+  #[structopt(long, value_name="indices", env="CPUX_AMDGPU_MCLK_LEVELS", help="AMD GPU enabled memory clock levels, implies manual, e.g. 2-3")]
+  amdgpu_mclk_levels: Option<Indices>,
+
+  #[structopt(long, value_name="edits", use_delimiter=true, env="CPUX_AMDGPU_OD", help="AMD GPU overdrive edits, implies manual, e.g. sclk:1:2000mhz,vc:2:2000mhz:1100mv")]
+  amdgpu_od: Option<Vec<OdEdit>>,
+
+  #[structopt(long, takes_value=false, help="Resets AMD GPU overdrive clocks and voltages to defaults")]
+  amdgpu_od_reset: bool,
+
+  #[structopt(long, value_name="level", env="CPUX_AMDGPU_PERF_LEVEL", help="AMD GPU performance level, e.g. auto, low, high, manual, profile_peak")]
+  amdgpu_perf_level: Option<String>,
+
+  #[structopt(long, value_name="profile", env="CPUX_AMDGPU_PROFILE", help="AMD GPU power profile, by index or name, e.g. 1, compute")]
+  amdgpu_profile: Option<String>,
+
+  #[structopt(long, value_name="indices", env="CPUX_AMDGPU_SCLK_LEVELS", help="AMD GPU enabled shader clock levels, implies manual, e.g. 1-2")]
+  amdgpu_sclk_levels: Option<Indices>,
+
+  #[structopt(long, value_name="cards", env="CPUX_CARDS", help="Target GPUs by index, PCI slot or driver, default all, e.g. 1,0000:03:00.0,amdgpu")]
+  cards: Option<Cards>,
+
   #[structopt(short, long, value_name="indices", env="CPUX_CPUS", help="Target CPUs, default all, e.g. 0,1,2-5,9,12-15")]
   cpus: Option<Indices>,
 
@@ -41,7 +274,25 @@ pub struct Cli {
   #[structopt(short="O", long, value_name="list", env="CPUX_CPU_ON_EACH", help="CPU online status, e.g. 10-1 ⇒ 0=on 1=off 2=skip 3=on")]
   cpu_on_each: Option<Toggles>,
 
-  #[structopt(long, takes_value=false, help="Prints CPU frequency governor summary, default if detected")]
+  #[structopt(long, takes_value=false, help="Prints devfreq device summary, default if detected")]
+  devfreq: bool,
+
+  #[structopt(long, value_name="names", use_delimiter=true, env="CPUX_DEVFREQ_DEVICES", help="Target devfreq devices, default all, e.g. ff9a0000.gpu,dmc")]
+  devfreq_devices: Option<Vec<String>>,
+
+  #[structopt(long, value_name="gov", env="CPUX_DEVFREQ_GOV", help="devfreq governor, e.g. simple_ondemand (per --devfreq-devices)")]
+  devfreq_gov: Option<String>,
+
+  #[structopt(long, value_name="hz", env="CPUX_DEVFREQ_MAX", help="devfreq max frequency, e.g. 800mhz (per --devfreq-devices)")]
+  devfreq_max: Option<Hertz>,
+
+  #[structopt(long, value_name="hz", env="CPUX_DEVFREQ_MIN", help="devfreq min frequency, e.g. 200mhz (per --devfreq-devices)")]
+  devfreq_min: Option<Hertz>,
+
+  #[structopt(long, value_name="format", default_value="table", env="CPUX_FORMAT", help="Output format, table, json, csv or tsv")]
+  format: Format,
+
+  #[structopt(long, takes_value=false, help="Prints cpufreq policy summary, default if detected")]
   freq: bool,
 
   #[structopt(short="g", long, value_name="gov", env="CPUX_FREQ_GOV", help="Frequency governor (per --cpus)")]
@@ -53,6 +304,30 @@ pub struct Cli {
   #[structopt(short="n", long, value_name="hz", env="CPUX_FREQ_MIN", help="Min frequency, e.g. 800mhz, 0.8ghz (per --cpus)")]
   freq_min: Option<Hertz>,
 
+  #[structopt(long, value_name="watts", env="CPUX_GPU_POWER_CAP", help="GPU power cap of amdgpu, i915 and xe cards, e.g. 150w")]
+  gpu_power_cap: Option<Watts>,
+
+  #[structopt(long, takes_value=false, help="Prints hwmon sensor summary, default if detected")]
+  hwmon: bool,
+
+  #[structopt(long, value_name="chip", env="CPUX_HWMON_CHIP", help="Target hwmon chip for fan control, by id or name, e.g. 2, hwmon2, nct6775")]
+  hwmon_chip: Option<String>,
+
+  #[structopt(long, value_name="0-255", env="CPUX_HWMON_PWM", help="Fan pwm value, implies manual mode (per --hwmon-pwms)")]
+  hwmon_pwm: Option<u64>,
+
+  #[structopt(long, takes_value=false, help="Restore previous fan control when cpux exits, wait for ctrl-c if REFRESH is not given")]
+  hwmon_pwm_hold: bool,
+
+  #[structopt(long, value_name="0-255", default_value="64", env="CPUX_HWMON_PWM_MIN", help="Lowest allowed --hwmon-pwm value")]
+  hwmon_pwm_min: u64,
+
+  #[structopt(long, value_name="mode", env="CPUX_HWMON_PWM_MODE", help="Fan control mode, full, manual or auto (per --hwmon-pwms)")]
+  hwmon_pwm_mode: Option<PwmMode>,
+
+  #[structopt(long, value_name="indices", env="CPUX_HWMON_PWMS", help="Target pwm channels of --hwmon-chip, default all, e.g. 1,2")]
+  hwmon_pwms: Option<Indices>,
+
   #[structopt(long, takes_value=false, help="Prints Intel GPU driver summary, default if detected")]
   i915: bool,
 
@@ -65,21 +340,90 @@ pub struct Cli {
   #[structopt(long, value_name="hz", env="CPUX_I915_FREQ_MIN", help="Intel GPU minimum frequency, e.g. 350mhz, 0.35ghz")]
   i915_freq_min: Option<Hertz>,
 
+  #[structopt(long, value_name="indices", env="CPUX_I915_GTS", help="Target i915 GTs, default all, e.g. 0,1")]
+  i915_gts: Option<Indices>,
+
+  #[structopt(long, takes_value=false, help="Prints cpuidle state summary, default if detected")]
+  idle: bool,
+
+  #[structopt(long, value_name="states", use_delimiter=true, env="CPUX_IDLE_DISABLE", help="Disable idle states by name or index, e.g. C6,C8 (per --cpus)")]
+  idle_disable: Option<Vec<String>>,
+
+  #[structopt(long, value_name="states", use_delimiter=true, env="CPUX_IDLE_ENABLE", help="Enable idle states by name or index, e.g. C6,C8 (per --cpus)")]
+  idle_enable: Option<Vec<String>>,
+
+  #[structopt(long, value_name="gov", env="CPUX_IDLE_GOV", help="cpuidle governor, e.g. menu, teo")]
+  idle_gov: Option<String>,
+
+  #[structopt(long, value_name="us", env="CPUX_IDLE_LATENCY_MAX", help="Disable idle states with exit latency above, enable the others (per --cpus)")]
+  idle_latency_max: Option<u64>,
+
   #[structopt(long, value_name="level", env="CPUX_LOG_LEVEL", help="Log level, default warn, e.g. error|warn|info|debug|trace")]
   log_level: Option<LevelFilter>,
 
   #[structopt(long, takes_value=false, help="Prints Intel pstate driver summary, default if detected")]
   pstate: bool,
   
+  #[structopt(long, value_name="bool", env="CPUX_PSTATE_DYNAMIC_BOOST", help="Intel pstate HWP dynamic boost, true or false")]
+  pstate_dynamic_boost: Option<bool>,
+
   #[structopt(long, value_name="0-15", env="CPUX_PSTATE_EPB", help="Intel pstate energy/performance bias hint (per --cpus)")]
   pstate_epb: Option<u64>,
 
   #[structopt(long, value_name="pref", env="CPUX_PSTATE_EPP", help="Intel pstate energy/performance preference (per --cpus)")]
   pstate_epp: Option<String>,
 
+  #[structopt(long, value_name="pct", env="CPUX_PSTATE_PERF_MAX", help="Intel pstate maximum performance, percent of max turbo, e.g. 80")]
+  pstate_perf_max: Option<u64>,
+
+  #[structopt(long, value_name="pct", env="CPUX_PSTATE_PERF_MIN", help="Intel pstate minimum performance, percent of max turbo, e.g. 20")]
+  pstate_perf_min: Option<u64>,
+
+  #[structopt(long, value_name="status", possible_values=&["active", "passive", "off"], env="CPUX_PSTATE_STATUS", help="Intel pstate driver mode, active, passive or off")]
+  pstate_status: Option<String>,
+
+  #[structopt(long, value_name="bool", env="CPUX_PSTATE_TURBO", help="Intel pstate turbo, true or false")]
+  pstate_turbo: Option<bool>,
+
   #[structopt(short, long, takes_value=false, env="CPUX_QUIET", help="Do not print the default summaries")]
   quiet: Option<bool>,
 
+  #[structopt(long, takes_value=false, help="Prints Intel RAPL power limit summary, default if detected")]
+  rapl: bool,
+
+  #[structopt(long, value_name="bool", env="CPUX_RAPL_ENABLED", help="Intel RAPL power limiting, true or false (per --rapl-zones)")]
+  rapl_enabled: Option<bool>,
+
+  #[structopt(long, value_name="watts", env="CPUX_RAPL_LIMIT_LONG", help="Intel RAPL long term power limit, e.g. 15w, 28.5w (per --rapl-zones)")]
+  rapl_limit_long: Option<Watts>,
+
+  #[structopt(long, value_name="watts", env="CPUX_RAPL_LIMIT_SHORT", help="Intel RAPL short term power limit, e.g. 25w, 44.5w (per --rapl-zones)")]
+  rapl_limit_short: Option<Watts>,
+
+  #[structopt(long, value_name="zones", use_delimiter=true, env="CPUX_RAPL_ZONES", help="Target RAPL zones, default package zones, e.g. 0,0:1,intel-rapl:1")]
+  rapl_zones: Option<Vec<ZoneId>>,
+
+  #[structopt(long, value_name="path", env="CPUX_ROOT", parse(from_os_str), help="Root directory for sysfs, default /")]
+  root: Option<PathBuf>,
+
+  #[structopt(long, value_name="state", possible_values=&["on", "off"], env="CPUX_TURBO", help="CPU turbo/boost, on or off, for any driver")]
+  turbo: Option<String>,
+
+  #[structopt(long, takes_value=false, help="Prints Intel xe GPU driver summary, default if detected")]
+  xe: bool,
+
+  #[structopt(long, value_name="hz", env="CPUX_XE_FREQ_MAX", help="Intel xe GPU maximum frequency, e.g. 1200mhz (per --xe-tiles, --xe-gts)")]
+  xe_freq_max: Option<Hertz>,
+
+  #[structopt(long, value_name="hz", env="CPUX_XE_FREQ_MIN", help="Intel xe GPU minimum frequency, e.g. 350mhz (per --xe-tiles, --xe-gts)")]
+  xe_freq_min: Option<Hertz>,
+
+  #[structopt(long, value_name="indices", env="CPUX_XE_GTS", help="Target xe GTs, default all, e.g. 0,1")]
+  xe_gts: Option<Indices>,
+
+  #[structopt(long, value_name="indices", env="CPUX_XE_TILES", help="Target xe tiles, default all, e.g. 0")]
+  xe_tiles: Option<Indices>,
+
   #[structopt(name = "REFRESH", help="Refresh summaries every REFRESH seconds")]
   refresh: Option<u64>,
 }
@@ -92,38 +436,134 @@ impl Cli {
       .format(|out, message, record| {
         out.finish(format_args!("{0: >5} {1}", record.level(), message))
       })
-      .level(self.log_level.clone().unwrap_or(LevelFilter::Warn))
+      .level(self.log_level.unwrap_or(LevelFilter::Warn))
       .filter(|m| m.target().starts_with("cpux"))
       .chain(std::io::stderr())
       .apply()?)
   }
 
+  // Switching the amd_pstate or intel_pstate mode recreates the cpufreq policies, so it goes
+  // first. The governor and EPP checks depend on the mode and run after the switch, the
+  // previous mode is restored when they fail.
+  fn apply_controls_mode(&self) -> Result<()> {
+    if ! self.has_control_args_mode() { return Ok(()); }
+    let prev_amd_pstate = amd_pstate::status()?;
+    let prev_pstate = pstate::status()?;
+    if let Some(ref amd_pstate_status) = self.amd_pstate_status {
+      if prev_amd_pstate.as_ref() != Some(amd_pstate_status) { amd_pstate::try_set_status(amd_pstate_status)?; }
+    }
+    if let Some(ref pstate_status) = self.pstate_status {
+      if prev_pstate.as_ref() != Some(pstate_status) { pstate::try_set_status(pstate_status)?; }
+    }
+    if let Err(err) = self.check_cpufreq_targets() {
+      if let (Some(_), Some(prev)) = (&self.amd_pstate_status, prev_amd_pstate) { amd_pstate::try_set_status(&prev)?; }
+      if let (Some(_), Some(prev)) = (&self.pstate_status, prev_pstate) { pstate::try_set_status(&prev)?; }
+      return Err(err);
+    }
+    Ok(())
+  }
+
+  fn apply_controls_pstate(&self) -> Result<()> {
+    if let Some(pstate_turbo) = self.pstate_turbo { pstate::try_set_no_turbo(! pstate_turbo)?; }
+    // The driver raises max_perf_pct to min_perf_pct, so lower the minimum first when the new
+    // maximum is below the current minimum.
+    let min_first =
+      match (self.pstate_perf_max, pstate::min_perf_pct()?) {
+        (Some(max), Some(cur_min)) => max < cur_min,
+        _ => false,
+      };
+    if min_first {
+      if let Some(pstate_perf_min) = self.pstate_perf_min { pstate::try_set_min_perf_pct(pstate_perf_min)?; }
+    }
+    if let Some(pstate_perf_max) = self.pstate_perf_max { pstate::try_set_max_perf_pct(pstate_perf_max)?; }
+    if ! min_first {
+      if let Some(pstate_perf_min) = self.pstate_perf_min { pstate::try_set_min_perf_pct(pstate_perf_min)?; }
+    }
+    if let Some(pstate_dynamic_boost) = self.pstate_dynamic_boost { pstate::try_set_hwp_dynamic_boost(pstate_dynamic_boost)?; }
+    Ok(())
+  }
+
+  fn apply_controls_turbo(&self) -> Result<()> {
+    if let Some(ref state) = self.turbo {
+      if turbo::set_turbo(state == "on")?.is_none() { return Err(Error::TurboNotAvailable); }
+    }
+    Ok(())
+  }
+
   fn has_control_args_cpu(&self) -> bool {
-    return
-      self.cpu_on.is_some() ||
-      self.cpu_on_each.is_some() ||
-      self.freq_gov.is_some() ||
-      self.freq_max.is_some() ||
-      self.freq_min.is_some() ||
-      self.pstate_epb.is_some() ||
-      self.pstate_epp.is_some();
+    self.amd_pstate_epp.is_some() ||
+    self.cpu_on.is_some() ||
+    self.cpu_on_each.is_some() ||
+    self.freq_gov.is_some() ||
+    self.freq_max.is_some() ||
+    self.freq_min.is_some() ||
+    self.idle_disable.is_some() ||
+    self.idle_enable.is_some() ||
+    self.idle_latency_max.is_some() ||
+    self.pstate_epb.is_some() ||
+    self.pstate_epp.is_some()
   }
 
-  fn apply_controls_cpu(&self) -> Result<()> {
-    if ! self.has_control_args_cpu() { return Ok(()); }
+  fn has_control_args_policy(&self) -> bool {
+    self.amd_pstate_epp.is_some() ||
+    self.freq_gov.is_some() ||
+    self.freq_max.is_some() ||
+    self.freq_min.is_some() ||
+    self.pstate_epp.is_some()
+  }
+
+  // The cpufreq policies of the target cpus, each with the first target cpu which belongs to it.
+  fn target_policies(cpu_ids: &Indices) -> Result<Vec<(u64, u64)>> {
+    let mut policies: Vec<(u64, u64)> = vec![];
+    for cpu_id in cpu_ids.iter() {
+      let policy_id = if let Some(policy_id) = cpufreq::policy(*cpu_id)? { policy_id } else { continue; };
+      if ! policies.iter().any(|(p, _)| *p == policy_id) { policies.push((policy_id, *cpu_id)); }
+    }
+    Ok(policies)
+  }
+
+  fn has_control_args_mode(&self) -> bool {
+    self.amd_pstate_status.is_some() ||
+    self.pstate_status.is_some()
+  }
+
+  // --cpus, or every present cpu.
+  fn target_cpus(&self) -> Result<Indices> {
     let mut cpu_ids = if let Some(cpus) = self.cpus.clone() { cpus } else { Indices::from_vec(cpu::cpus()?) };
     cpu_ids.sort();
     cpu_ids.dedup();
-    for cpu_id in cpu_ids {
-      let mut cpu_online = cpu::online(cpu_id)?.unwrap_or(true);
-      if ! cpu_online { cpu::try_set_online(cpu_id, true)?; }
-      if let Some(ref cpu_on) = self.cpu_on { cpu_online = *cpu_on; }
-      if let Some(ref freq_gov) = self.freq_gov { cpufreq::set_governor(cpu_id, freq_gov)?; }
-      if let Some(ref freq_max) = self.freq_max { cpufreq::set_max(cpu_id, freq_max)?; }
-      if let Some(ref freq_min) = self.freq_min { cpufreq::set_min(cpu_id, freq_min)?; }
-      if let Some(ref pstate_epb) = self.pstate_epb { pstate::set_epb(cpu_id, *pstate_epb)?; }
+    Ok(cpu_ids)
+  }
+
+  fn apply_controls_cpu(&self) -> Result<()> {
+    if ! self.has_control_args_cpu() { return Ok(()); }
+    let cpu_ids = self.target_cpus()?;
+    // Cpus are brought online before their settings are written, and taken offline after.
+    if self.cpu_on == Some(true) {
+      for cpu_id in cpu_ids.iter() {
+        if cpu::online(*cpu_id)? == Some(false) { cpu::try_set_online(*cpu_id, true)?; }
+      }
+    }
+    // Policies are written through `policyN`, which stays writable while one of its cpus is
+    // online. Policies with no online cpu are refused by `check_policies_active`.
+    let policies = if self.has_control_args_policy() { Self::target_policies(&cpu_ids)? } else { vec![] };
+    for (policy_id, cpu_id) in policies {
+      if let Some(ref freq_gov) = self.freq_gov { cpufreq::set_policy_governor(policy_id, freq_gov)?; }
+      if let Some(ref freq_max) = self.freq_max { cpufreq::set_policy_max(policy_id, freq_max)?; }
+      if let Some(ref freq_min) = self.freq_min { cpufreq::set_policy_min(policy_id, freq_min)?; }
+      // EPP is a policy attribute, written through the cpu's link to its policy.
       if let Some(ref pstate_epp) = self.pstate_epp { pstate::set_epp(cpu_id, pstate_epp)?; }
-      if ! cpu_online { cpu::set_online(cpu_id, false)?; }
+      if let Some(ref amd_pstate_epp) = self.amd_pstate_epp { amd_pstate::set_epp(cpu_id, amd_pstate_epp)?; }
+    }
+    for cpu_id in cpu_ids.iter() {
+      // The epb and cpuidle attributes of a cpu are removed while it is offline.
+      if cpu::online(*cpu_id)?.unwrap_or(true) {
+        if let Some(ref pstate_epb) = self.pstate_epb { pstate::set_epb(*cpu_id, *pstate_epb)?; }
+        for (state_id, disable) in self.idle_state_writes(*cpu_id)? { cpuidle::try_set_disable(*cpu_id, state_id, disable)?; }
+      } else if self.pstate_epb.is_some() || self.has_control_args_idle_states() {
+        debug!("cpu{} is offline, epb and idle states not changed", cpu_id);
+      }
+      if self.cpu_on == Some(false) { cpu::set_online(*cpu_id, false)?; }
     }
     if let Some(ref cpu_on_each) = self.cpu_on_each {
       for (cpu_id, status) in cpu_on_each.iter().enumerate() {
@@ -133,128 +573,976 @@ impl Cli {
     Ok(())
   }
 
+  // The kernel refuses writes to a policy whose cpus are all offline, and cpus are not brought
+  // online to write it unless --cpu-on is given.
+  fn check_policies_active(&self, cpu_ids: &Indices) -> Result<()> {
+    if ! self.has_control_args_policy() || self.cpu_on == Some(true) { return Ok(()); }
+    for (policy_id, _) in Self::target_policies(cpu_ids)? {
+      if ! cpufreq::policy_active(policy_id)?.unwrap_or(false) { return Err(Error::PolicyOffline(policy_id)); }
+    }
+    Ok(())
+  }
+
+  // Refuses governors and preferences which the target policies do not list, and preferences
+  // for policies without EPP, e.g. in passive mode. The lists depend on the driver mode, so
+  // with --pstate-status or --amd-pstate-status they are checked after the switch.
+  fn check_cpufreq_args(&self, policies: &[(u64, u64)]) -> Result<()> {
+    for (policy_id, cpu_id) in policies {
+      // Attributes of inactive policies cannot be read, see `check_policies_active`.
+      if ! cpufreq::policy_active(*policy_id)?.unwrap_or(false) { continue; }
+      if let Some(ref freq_gov) = self.freq_gov {
+        if let Some(governors) = cpufreq::policy_governors(*policy_id)? {
+          if ! governors.contains(freq_gov) { return Err(Error::GovernorNotAvailable(freq_gov.clone(), governors.join(","))); }
+        }
+      }
+      if self.pstate_epp.is_some() && pstate::epp(*cpu_id)?.is_none() { return Err(Error::EppNotSupported(*policy_id)); }
+      if self.amd_pstate_epp.is_some() && amd_pstate::epp(*cpu_id)?.is_none() { return Err(Error::EppNotSupported(*policy_id)); }
+      // The drivers also accept a raw EPP value, 0-255.
+      if let Some(ref pstate_epp) = self.pstate_epp.as_ref().filter(|v| v.parse::<u8>().is_err()) {
+        if let Some(epps) = pstate::epps(*cpu_id)? {
+          if ! epps.contains(pstate_epp) { return Err(Error::EppNotAvailable(pstate_epp.to_string(), epps.join(","))); }
+        }
+      }
+      if let Some(ref amd_pstate_epp) = self.amd_pstate_epp.as_ref().filter(|v| v.parse::<u8>().is_err()) {
+        if let Some(epps) = amd_pstate::epps(*cpu_id)? {
+          if ! epps.contains(amd_pstate_epp) { return Err(Error::EppNotAvailable(amd_pstate_epp.to_string(), epps.join(","))); }
+        }
+      }
+    }
+    Ok(())
+  }
+
+  // Whether `state`, a name or an index from --idle-disable or --idle-enable, is `state_id`.
+  fn is_idle_state(cpu_id: u64, state_id: u64, state: &str) -> Result<bool> {
+    if let Ok(id) = state.parse::<u64>() { return Ok(id == state_id); }
+    Ok(cpuidle::name(cpu_id, state_id)?.map(|n| n.eq_ignore_ascii_case(state)).unwrap_or(false))
+  }
+
+  // Refuses idle state names or indices which exist on none of the target cpus, before
+  // anything is written.
+  fn check_idle_states(&self, cpu_ids: &Indices) -> Result<()> {
+    let states = self.idle_disable.iter().chain(self.idle_enable.iter()).flatten();
+    for state in states {
+      let mut found = false;
+      for cpu_id in cpu_ids.iter() {
+        for state_id in cpuidle::states(*cpu_id)?.unwrap_or_default() {
+          if Self::is_idle_state(*cpu_id, state_id, state)? { found = true; }
+        }
+      }
+      if ! found { return Err(Error::IdleStateNotFound(state.clone())); }
+    }
+    Ok(())
+  }
+
+  fn has_control_args_idle_states(&self) -> bool {
+    self.idle_disable.is_some() ||
+    self.idle_enable.is_some() ||
+    self.idle_latency_max.is_some()
+  }
+
+  // Idle states of the cpu to disable (true) or enable (false). --idle-latency-max sets
+  // every state, --idle-enable and then --idle-disable override it.
+  fn idle_state_writes(&self, cpu_id: u64) -> Result<Vec<(u64, bool)>> {
+    if ! self.has_control_args_idle_states() { return Ok(vec![]); }
+    let mut writes = vec![];
+    for state_id in cpuidle::states(cpu_id)?.unwrap_or_default() {
+      let mut disable = None;
+      if let Some(max) = self.idle_latency_max {
+        if let Some(latency) = cpuidle::latency(cpu_id, state_id)? { disable = Some(latency > max); }
+      }
+      for state in self.idle_enable.iter().flatten() {
+        if Self::is_idle_state(cpu_id, state_id, state)? { disable = Some(false); }
+      }
+      for state in self.idle_disable.iter().flatten() {
+        if Self::is_idle_state(cpu_id, state_id, state)? { disable = Some(true); }
+      }
+      if let Some(disable) = disable { writes.push((state_id, disable)); }
+    }
+    Ok(writes)
+  }
+
+  fn apply_controls_cpuidle(&self) -> Result<()> {
+    if let Some(ref idle_gov) = self.idle_gov { cpuidle::try_set_governor(idle_gov)?; }
+    Ok(())
+  }
+
+  // Cards matched by --cards, None when it is not given.
+  fn selected_cards(&self) -> Result<Option<Vec<u64>>> {
+    let cards = if let Some(ref cards) = self.cards { cards } else { return Ok(None); };
+    let mut all = drm::cards()?.unwrap_or_default();
+    all.sort_unstable();
+    let mut res = vec![];
+    for card in cards.iter() {
+      let mut found = false;
+      for card_id in &all {
+        let matched =
+          match card {
+            Card::Index(id) => card_id == id,
+            Card::Slot(slot) => drm::card_slot(*card_id)?.as_deref() == Some(slot.as_str()),
+            Card::Driver(driver) => drm::card_driver(*card_id)?.as_deref() == Some(driver.as_str()),
+          };
+        if matched { found = true; res.push(*card_id); }
+      }
+      if ! found { return Err(Error::CardNotFound(card.clone())); }
+    }
+    res.sort_unstable();
+    res.dedup();
+    Ok(Some(res))
+  }
+
+  fn is_card_selected(selected: &Option<Vec<u64>>, card_id: u64) -> bool {
+    selected.as_ref().map(|s| s.contains(&card_id)).unwrap_or(true)
+  }
+
   fn has_control_args_i915(&self) -> bool {
-    return
-      self.i915_freq_boost.is_some() ||
-      self.i915_freq_max.is_some() ||
-      self.i915_freq_min.is_some();
+    self.i915_freq_boost.is_some() ||
+    self.i915_freq_max.is_some() ||
+    self.i915_freq_min.is_some()
   }
 
   fn apply_controls_i915(&self) -> Result<()> {
     if ! self.has_control_args_i915() { return Ok(()); }
+    let selected = self.selected_cards()?;
     let cards = if let Ok(Some(cards)) = i915::cards() { cards } else { return Ok(()) };
+    let mut found = false;
+    for card_id in cards {
+      if ! Self::is_card_selected(&selected, card_id) { continue; }
+      let gts = i915::gts(card_id)?.unwrap_or_default();
+      // The legacy files only control the first GT, write each GT when they are listed.
+      if gts.is_empty() {
+        if self.i915_gts.is_some() { continue; }
+        found = true;
+        if let Some(ref i915_freq_boost) = self.i915_freq_boost { i915::set_boost(card_id, i915_freq_boost)?; }
+        if let Some(ref i915_freq_max) = self.i915_freq_max { i915::set_max(card_id, i915_freq_max)?; }
+        if let Some(ref i915_freq_min) = self.i915_freq_min { i915::set_min(card_id, i915_freq_min)?; }
+        continue;
+      }
+      for gt_id in gts {
+        if let Some(ref i915_gts) = self.i915_gts { if ! i915_gts.iter().any(|i| *i == gt_id) { continue; } }
+        found = true;
+        if let Some(ref i915_freq_boost) = self.i915_freq_boost { i915::set_gt_boost(card_id, gt_id, i915_freq_boost)?; }
+        if let Some(ref i915_freq_max) = self.i915_freq_max { i915::set_gt_max(card_id, gt_id, i915_freq_max)?; }
+        if let Some(ref i915_freq_min) = self.i915_freq_min { i915::set_gt_min(card_id, gt_id, i915_freq_min)?; }
+      }
+    }
+    if ! found && self.i915_gts.is_some() { return Err(Error::I915GtNotFound); }
+    Ok(())
+  }
+
+  fn has_control_args_xe(&self) -> bool {
+    self.xe_freq_max.is_some() ||
+    self.xe_freq_min.is_some()
+  }
+
+  fn apply_controls_xe(&self) -> Result<()> {
+    if ! self.has_control_args_xe() { return Ok(()); }
+    let selected = |ids: &Option<Indices>, id: u64| ids.as_ref().map(|ids| ids.iter().any(|i| *i == id)).unwrap_or(true);
+    let cards = self.selected_cards()?;
+    let mut found = false;
+    for card_id in xe::cards()?.unwrap_or_default() {
+      if ! Self::is_card_selected(&cards, card_id) { continue; }
+      for tile_id in xe::tiles(card_id)?.unwrap_or_default() {
+        if ! selected(&self.xe_tiles, tile_id) { continue; }
+        for gt_id in xe::gts(card_id, tile_id)?.unwrap_or_default() {
+          if ! selected(&self.xe_gts, gt_id) { continue; }
+          found = true;
+          if let Some(ref xe_freq_max) = self.xe_freq_max { xe::set_max(card_id, tile_id, gt_id, xe_freq_max)?; }
+          if let Some(ref xe_freq_min) = self.xe_freq_min { xe::set_min(card_id, tile_id, gt_id, xe_freq_min)?; }
+        }
+      }
+    }
+    if ! found && (self.xe_tiles.is_some() || self.xe_gts.is_some()) { return Err(Error::XeGtNotFound); }
+    Ok(())
+  }
+
+  fn has_control_args_amdgpu(&self) -> bool {
+    self.amdgpu_mclk_levels.is_some() ||
+    self.amdgpu_od.is_some() ||
+    self.amdgpu_od_reset ||
+    self.amdgpu_perf_level.is_some() ||
+    self.amdgpu_profile.is_some() ||
+    self.amdgpu_sclk_levels.is_some()
+  }
+
+  fn amdgpu_profile_id(card_id: u64, profile: &str) -> Result<u64> {
+    let profiles = amdgpu::power_profiles(card_id)?.unwrap_or_default();
+    let found =
+      if let Ok(profile_id) = profile.parse::<u64>() { profiles.iter().find(|p| p.id == profile_id) }
+      else { profiles.iter().find(|p| p.name.eq_ignore_ascii_case(profile)) };
+    if let Some(found) = found { Ok(found.id) }
+    else { Err(Error::AmdgpuProfileNotFound(card_id, profile.to_string())) }
+  }
+
+  fn amdgpu_levels(card_id: u64, clk: &'static str, levels: &Indices, available: Option<Vec<amdgpu::DpmLevel>>) -> Result<Vec<u64>> {
+    let available = available.unwrap_or_default();
+    let mut levels = levels.clone();
+    levels.sort();
+    levels.dedup();
+    let levels = levels.to_vec();
+    for level in &levels {
+      if ! available.iter().any(|l| l.id == *level) { return Err(Error::AmdgpuLevelNotFound(card_id, clk, *level)); }
+    }
+    Ok(levels)
+  }
+
+  fn apply_controls_amdgpu(&self) -> Result<()> {
+    if ! self.has_control_args_amdgpu() { return Ok(()); }
+    let has_levels = self.amdgpu_sclk_levels.is_some() || self.amdgpu_mclk_levels.is_some() || self.amdgpu_od.is_some();
+    let perf_level =
+      match (self.amdgpu_perf_level.as_deref(), has_levels) {
+        (Some(level), true) if level != "manual" => return Err(Error::AmdgpuLevelsNotManual(level.to_string())),
+        (Some(level), _) => Some(level),
+        (None, true) => Some("manual"),
+        (None, false) => None,
+      };
+    let selected = self.selected_cards()?;
+    for card_id in amdgpu::cards()?.unwrap_or_default() {
+      if ! Self::is_card_selected(&selected, card_id) { continue; }
+      if let Some(perf_level) = perf_level { amdgpu::set_performance_level(card_id, perf_level)?; }
+      if let Some(ref profile) = self.amdgpu_profile {
+        amdgpu::set_power_profile(card_id, Self::amdgpu_profile_id(card_id, profile)?)?;
+      }
+      if let Some(ref levels) = self.amdgpu_sclk_levels {
+        let levels = Self::amdgpu_levels(card_id, "sclk", levels, amdgpu::sclk_levels(card_id)?)?;
+        amdgpu::set_sclk_levels(card_id, &levels)?;
+      }
+      if let Some(ref levels) = self.amdgpu_mclk_levels {
+        let levels = Self::amdgpu_levels(card_id, "mclk", levels, amdgpu::mclk_levels(card_id)?)?;
+        amdgpu::set_mclk_levels(card_id, &levels)?;
+      }
+      if self.amdgpu_od_reset { amdgpu::reset_od(card_id)?; }
+      if let Some(ref edits) = self.amdgpu_od { amdgpu::set_od(card_id, edits)?; }
+    }
+    Ok(())
+  }
+
+  fn has_control_args_devfreq(&self) -> bool {
+    self.devfreq_gov.is_some() ||
+    self.devfreq_max.is_some() ||
+    self.devfreq_min.is_some()
+  }
+
+  fn apply_controls_devfreq(&self) -> Result<()> {
+    if ! self.has_control_args_devfreq() { return Ok(()); }
+    let devices = devfreq::devices()?.unwrap_or_default();
+    let mut targets = if let Some(ref names) = self.devfreq_devices { names.clone() } else { devices.clone() };
+    targets.sort_unstable();
+    targets.dedup();
+    for device in targets {
+      if ! devices.contains(&device) { return Err(Error::DevfreqDeviceNotFound(device)); }
+      if let Some(ref devfreq_gov) = self.devfreq_gov { devfreq::try_set_governor(&device, devfreq_gov)?; }
+      if let Some(ref devfreq_max) = self.devfreq_max { devfreq::set_max(&device, devfreq_max)?; }
+      if let Some(ref devfreq_min) = self.devfreq_min { devfreq::set_min(&device, devfreq_min)?; }
+    }
+    Ok(())
+  }
+
+  fn has_control_args_hwmon(&self) -> bool {
+    self.hwmon_pwm.is_some() ||
+    self.hwmon_pwm_mode.is_some()
+  }
+
+  fn hwmon_chip_id(chip: &str) -> Result<u64> {
+    if let Ok(chip_id) = chip.trim_start_matches("hwmon").parse::<u64>() {
+      if hwmon::chips()?.unwrap_or_default().contains(&chip_id) { return Ok(chip_id); }
+      return Err(Error::HwmonChipNotFound(chip.to_string()));
+    }
+    match &hwmon::chips_named(chip)?[..] {
+      [chip_id] => Ok(*chip_id),
+      [] => Err(Error::HwmonChipNotFound(chip.to_string())),
+      _ => Err(Error::HwmonChipAmbiguous(chip.to_string())),
+    }
+  }
+
+  fn apply_controls_hwmon(&self) -> Result<()> {
+    if ! self.has_control_args_hwmon() { return Ok(()); }
+    let mode =
+      match (self.hwmon_pwm_mode, self.hwmon_pwm) {
+        (Some(PwmMode::Manual), None) => return Err(Error::HwmonPwmManualNoValue),
+        (Some(mode), Some(_)) if mode != PwmMode::Manual => return Err(Error::HwmonPwmModeConflict(mode)),
+        (Some(mode), _) => mode,
+        (None, _) => PwmMode::Manual,
+      };
+    if let Some(pwm) = self.hwmon_pwm {
+      if pwm < self.hwmon_pwm_min || pwm > 255 { return Err(Error::HwmonPwmRange(pwm, self.hwmon_pwm_min)); }
+    }
+    let chip_id = if let Some(ref chip) = self.hwmon_chip { Self::hwmon_chip_id(chip)? } else { return Err(Error::HwmonPwmNoChip); };
+    let pwm_ids = hwmon::pwms(chip_id)?.unwrap_or_default();
+    let mut targets = if let Some(pwms) = self.hwmon_pwms.clone() { pwms } else { Indices::from_vec(pwm_ids.clone()) };
+    targets.sort();
+    targets.dedup();
+    for pwm_id in targets {
+      if ! pwm_ids.contains(&pwm_id) { return Err(Error::HwmonPwmNotFound(chip_id, pwm_id)); }
+      if self.hwmon_pwm_hold {
+        let state = hwmon::PwmState::save(chip_id, pwm_id)?;
+        let first = {
+          let mut states = PWM_RESTORE.lock().unwrap_or_else(|e| e.into_inner());
+          states.push(state);
+          states.len() == 1
+        };
+        // Installed before the first write, so an interrupt between writes restores the
+        // channels written so far.
+        if first {
+          ctrlc::set_handler(|| {
+            restore_pwms();
+            std::process::exit(130);
+          })?;
+        }
+      }
+      hwmon::try_set_pwm_mode(chip_id, pwm_id, mode)?;
+      if let Some(pwm) = self.hwmon_pwm { hwmon::try_set_pwm(chip_id, pwm_id, pwm)?; }
+    }
+    Ok(())
+  }
+
+  fn has_control_args_rapl(&self) -> bool {
+    self.rapl_enabled.is_some() ||
+    self.rapl_limit_long.is_some() ||
+    self.rapl_limit_short.is_some()
+  }
+
+  fn apply_rapl_limit(zone_id: ZoneId, name: &'static str, limit: &Watts) -> Result<()> {
+    let constraint_id =
+      if let Some(constraint_id) = powercap::constraint_named(zone_id, name)? { constraint_id }
+      else { return Err(Error::RaplConstraintNotFound(zone_id, name)); };
+    // max_power_uw is not reported by every zone, and 0 where the limit is unbounded.
+    if let Some(max) = powercap::max_power(zone_id, constraint_id)? {
+      if max.microwatts() > 0 && limit.microwatts() > max.microwatts() {
+        return Err(Error::RaplLimitRange(zone_id, name, limit.clone(), max));
+      }
+    }
+    powercap::try_set_power_limit(zone_id, constraint_id, limit)?;
+    Ok(())
+  }
+
+  fn apply_controls_rapl(&self) -> Result<()> {
+    if ! self.has_control_args_rapl() { return Ok(()); }
+    let zones = powercap::zones()?.unwrap_or_default();
+    let mut zone_ids =
+      if let Some(ref rapl_zones) = self.rapl_zones { rapl_zones.clone() }
+      else {
+        let mut zone_ids = vec![];
+        for zone_id in &zones {
+          if powercap::name(*zone_id)?.map(|n| n.starts_with("package")).unwrap_or(false) { zone_ids.push(*zone_id); }
+        }
+        zone_ids
+      };
+    zone_ids.sort_unstable();
+    zone_ids.dedup();
+    for zone_id in zone_ids {
+      if ! zones.contains(&zone_id) { return Err(Error::RaplZoneNotFound(zone_id)); }
+      if let Some(ref limit) = self.rapl_limit_long { Self::apply_rapl_limit(zone_id, "long_term", limit)?; }
+      if let Some(ref limit) = self.rapl_limit_short { Self::apply_rapl_limit(zone_id, "short_term", limit)?; }
+      if let Some(rapl_enabled) = self.rapl_enabled { powercap::try_set_enabled(zone_id, rapl_enabled)?; }
+    }
+    Ok(())
+  }
+
+  fn apply_controls_gpu_power(&self) -> Result<()> {
+    let cap = if let Some(ref cap) = self.gpu_power_cap { cap } else { return Ok(()); };
+    let selected = self.selected_cards()?;
+    let mut cards = drm::cards()?.unwrap_or_default();
+    cards.sort_unstable();
     for card_id in cards {
-      if let Some(ref i915_freq_boost) = self.i915_freq_boost { i915::set_boost(card_id, i915_freq_boost)?; }
-      if let Some(ref i915_freq_max) = self.i915_freq_max { i915::set_max(card_id, i915_freq_max)?; }
-      if let Some(ref i915_freq_min) = self.i915_freq_min { i915::set_min(card_id, i915_freq_min)?; }
+      if ! Self::is_card_selected(&selected, card_id) { continue; }
+      let power = if let Some(power) = summary::GpuPower::read(card_id)? { power } else { continue; };
+      if power.cap.is_none() { continue; }
+      // The bounds are not reported by i915 and xe, and 0 where the cap is unbounded.
+      let min = power.cap_min.filter(|w| w.microwatts() > 0);
+      let max = power.cap_max.filter(|w| w.microwatts() > 0);
+      let below = min.as_ref().map(|min| cap.microwatts() < min.microwatts()).unwrap_or(false);
+      let above = max.as_ref().map(|max| cap.microwatts() > max.microwatts()).unwrap_or(false);
+      if below || above {
+        return Err(Error::GpuPowerCapRange(
+          card_id, cap.clone(),
+          min.map(String::from).unwrap_or("n/a".to_string()),
+          max.map(String::from).unwrap_or("n/a".to_string()),
+        ));
+      }
+      hwmon::try_set_power_cap(power.chip_id, cap)?;
     }
     Ok(())
   }
 
+  // Refuses arguments which do not depend on the driver mode before anything is written, the
+  // mode-dependent checks run after the switch in `apply_controls_mode`.
+  fn check_control_args(&self) -> Result<()> {
+    for pct in [self.pstate_perf_min, self.pstate_perf_max].iter().flatten() {
+      if *pct > 100 { return Err(Error::PstatePerfRange(*pct)); }
+    }
+    if let (Some(min), Some(max)) = (self.pstate_perf_min, self.pstate_perf_max) {
+      if min > max { return Err(Error::PstatePerfMinAboveMax(min, max)); }
+    }
+    if let Some(ref idle_gov) = self.idle_gov {
+      if let Some(governors) = cpuidle::governors()? {
+        if ! governors.contains(idle_gov) { return Err(Error::IdleGovernorNotAvailable(idle_gov.clone(), governors.join(","))); }
+      }
+    }
+    if self.has_control_args_cpu() {
+      let cpu_ids = self.target_cpus()?;
+      self.check_idle_states(&cpu_ids)?;
+      self.check_policies_active(&cpu_ids)?;
+    }
+    if ! self.has_control_args_mode() { self.check_cpufreq_targets()?; }
+    Ok(())
+  }
+
+  fn check_cpufreq_targets(&self) -> Result<()> {
+    if ! self.has_control_args_policy() { return Ok(()); }
+    self.check_cpufreq_args(&Self::target_policies(&self.target_cpus()?)?)
+  }
+
   fn apply_controls(&self) -> Result<()> {
+    self.check_control_args()?;
+    self.apply_controls_mode()?;
+    self.apply_controls_pstate()?;
+    self.apply_controls_turbo()?;
+    self.apply_controls_cpuidle()?;
     self.apply_controls_cpu()?;
     self.apply_controls_i915()?;
+    self.apply_controls_xe()?;
+    self.apply_controls_amdgpu()?;
+    self.apply_controls_gpu_power()?;
+    self.apply_controls_devfreq()?;
+    self.apply_controls_hwmon()?;
+    self.apply_controls_rapl()?;
     Ok(())
   }
 
-  fn format_table_cpu(cpu_ids: Vec<u64>) -> Result<String> {
-    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<}");
+  fn format_table_cpu(cpus: &[summary::Cpu]) -> Result<String> {
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<}");
     tab.add_row(Row::new()
       .with_cell("CPU")
       .with_cell("Online")
+      .with_cell("Policy")
       .with_cell("Cur")
+      .with_cell("Turbo"));
+    tab.add_row(Row::new()
+      .with_cell("-------")
+      .with_cell("-------")
+      .with_cell("--------")
+      .with_cell("-----------")
+      .with_cell("-----"));
+    for cpu in cpus {
+      tab.add_row(Row::new()
+        .with_cell(format!("cpu{}", cpu.id))
+        .with_cell(cpu.online)
+        .with_cell(cpu.policy.map(|p| format!("policy{}", p)).unwrap_or("n/a".to_string()))
+        .with_cell(cpu.cur.clone().map(String::from).unwrap_or("n/a".to_string()))
+        .with_cell(cpu.turbo.map(|v| if v { "on" } else { "off" }).unwrap_or("n/a")));
+    }
+    let mut buf = tab.to_string();
+    buf.push('\n');
+    Ok(buf)
+  }
+  
+  fn format_table_freq(policies: Option<&[summary::Policy]>) -> Result<String> {
+    let policies = if let Some(policies) = policies { policies } else { return Ok("".to_string()); };
+    if policies.is_empty() { return Ok("".to_string()); }
+    // Driver-specific values get a column only if some policy reports them.
+    let base = policies.iter().any(|p| p.base.is_some());
+    let bios_limit = policies.iter().any(|p| p.bios_limit.is_some());
+    let cpuinfo_cur = policies.iter().any(|p| p.cpuinfo_cur.is_some());
+    let latency = policies.iter().any(|p| p.transition_latency.is_some());
+    let columns = 9 + [base, bios_limit, cpuinfo_cur, latency].iter().filter(|c| **c).count();
+    let mut tab = Table::new(&vec!["{:<}"; columns].join(" "));
+    let mut header = Row::new()
+      .with_cell("Policy")
+      .with_cell("CPUs")
+      .with_cell("Related")
+      .with_cell("Governor")
       .with_cell("Min")
       .with_cell("Max")
       .with_cell("Min limit")
-      .with_cell("Max limit"));
-    tab.add_row(Row::new()
+      .with_cell("Max limit");
+    let mut dashes = Row::new()
+      .with_cell("--------")
       .with_cell("-------")
       .with_cell("-------")
+      .with_cell("----------------")
       .with_cell("-----------")
       .with_cell("-----------")
       .with_cell("-----------")
-      .with_cell("-----------")
-      .with_cell("-----------"));
-    for cpu_id in cpu_ids {
-      tab.add_row(Row::new()
-        .with_cell(format!("cpu{}", cpu_id))
-        .with_cell(cpu::online(cpu_id)?.unwrap_or(true))
-        .with_cell(cpufreq::cur(cpu_id)?.map(String::from).unwrap_or("n/a".to_string()))
-        .with_cell(cpufreq::min(cpu_id)?.map(String::from).unwrap_or("n/a".to_string()))
-        .with_cell(cpufreq::max(cpu_id)?.map(String::from).unwrap_or("n/a".to_string()))
-        .with_cell(cpufreq::min_limit(cpu_id)?.map(String::from).unwrap_or("n/a".to_string()))
-        .with_cell(cpufreq::max_limit(cpu_id)?.map(String::from).unwrap_or("n/a".to_string())));
+      .with_cell("-----------");
+    if base { header.add_cell("Base"); dashes.add_cell("-----------"); }
+    if bios_limit { header.add_cell("BIOS limit"); dashes.add_cell("-----------"); }
+    if cpuinfo_cur { header.add_cell("HW cur"); dashes.add_cell("-----------"); }
+    if latency { header.add_cell("Latency"); dashes.add_cell("----------"); }
+    tab.add_row(header.with_cell("Governors"));
+    tab.add_row(dashes.with_cell("----------------"));
+    let hertz = |v: &Option<Hertz>| v.clone().map(String::from).unwrap_or("n/a".to_string());
+    let cpus = |v: &Option<Vec<u64>>| v.clone().map(|c| Indices::from_vec(c).to_string()).unwrap_or("n/a".to_string());
+    let mut drivers: Vec<String> = vec![];
+    for policy in policies {
+      if let Some(ref driver) = policy.driver {
+        if ! drivers.contains(driver) { drivers.push(driver.clone()); }
+      }
+      let mut row = Row::new()
+        .with_cell(format!("policy{}", policy.id))
+        .with_cell(if policy.active { cpus(&policy.affected_cpus) } else { "offline".to_string() })
+        .with_cell(cpus(&policy.related_cpus))
+        .with_cell(policy.governor.clone().unwrap_or("n/a".to_string()))
+        .with_cell(hertz(&policy.min))
+        .with_cell(hertz(&policy.max))
+        .with_cell(hertz(&policy.min_limit))
+        .with_cell(hertz(&policy.max_limit));
+      if base { row.add_cell(hertz(&policy.base)); }
+      if bios_limit { row.add_cell(hertz(&policy.bios_limit)); }
+      if cpuinfo_cur { row.add_cell(hertz(&policy.cpuinfo_cur)); }
+      if latency { row.add_cell(policy.transition_latency.map(|v| format!("{} ns", v)).unwrap_or("n/a".to_string())); }
+      tab.add_row(row.with_cell(policy.governors.as_ref().map(|v| v.join(",")).unwrap_or("n/a".to_string())));
     }
-    let mut buf = tab.to_string();
+    let drivers = if drivers.is_empty() { "n/a".to_string() } else { drivers.join(", ") };
+    let mut buf = String::new();
+    buf.push_str(&format!("cpufreq: {}\n\n", drivers));
+    buf.push_str(&tab.to_string());
     buf.push('\n');
     Ok(buf)
   }
   
-  fn format_table_freq(cpu_ids: Vec<u64>) -> Result<String> {
-    if cpu_ids.len() == 0 { return Ok("".to_string()); }
-    let mut tab = Table::new("{:<} {:<} {:<}");
+  fn format_table_gpu(gpus: Option<&[summary::Gpu]>) -> Result<String> {
+    let gpus = if let Some(gpus) = gpus { gpus } else { return Ok("".to_string()); };
+    if gpus.is_empty() { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<}");
     tab.add_row(Row::new()
-      .with_cell("CPU")
-      .with_cell("Governor")
-      .with_cell("Governors"));
+      .with_cell("Card")
+      .with_cell("Driver")
+      .with_cell("Slot")
+      .with_cell("PCI id")
+      .with_cell("Subsystem")
+      .with_cell("Boot VGA")
+      .with_cell("Render")
+      .with_cell("Name"));
     tab.add_row(Row::new()
+      .with_cell("------")
       .with_cell("-------")
-      .with_cell("----------------")
-      .with_cell("----------------"));
-    for cpu_id in cpu_ids {
+      .with_cell("------------")
+      .with_cell("---------")
+      .with_cell("---------")
+      .with_cell("--------")
+      .with_cell("----------")
+      .with_cell("------"));
+    for gpu in gpus {
+      let id = |vendor_id: Option<u16>, device_id: Option<u16>|
+        match (vendor_id, device_id) {
+          (Some(v), Some(d)) => format!("{:04x}:{:04x}", v, d),
+          _ => "n/a".to_string(),
+        };
+      let name =
+        match (&gpu.vendor, &gpu.device) {
+          (Some(vendor), Some(device)) => format!("{} {}", vendor, device),
+          (Some(vendor), None) => vendor.clone(),
+          _ => "n/a".to_string(),
+        };
       tab.add_row(Row::new()
-        .with_cell(format!("cpu{}", cpu_id))
-        .with_cell(cpufreq::governor(cpu_id)?.unwrap_or("n/a".to_string()))
-        .with_cell(cpufreq::governors(cpu_id)?.map(|v| v.join(",")).unwrap_or("n/a".to_string())));
+        .with_cell(format!("card{}", gpu.id))
+        .with_cell(gpu.driver.as_deref().unwrap_or("n/a"))
+        .with_cell(gpu.slot.as_deref().unwrap_or("n/a"))
+        .with_cell(id(gpu.vendor_id, gpu.device_id))
+        .with_cell(id(gpu.subsystem_vendor_id, gpu.subsystem_device_id))
+        .with_cell(gpu.boot_vga.map(|b| if b { "yes" } else { "no" }).unwrap_or("n/a"))
+        .with_cell(gpu.render_node.as_deref().unwrap_or("n/a"))
+        .with_cell(name));
     }
     let mut buf = tab.to_string();
     buf.push('\n');
     Ok(buf)
   }
-  
-  fn format_table_i915(card_ids: Option<Vec<u64>>) -> Result<String> {
-    let card_ids = if let Some(card_ids) = card_ids { card_ids } else { return Ok("".to_string()); };
-    if card_ids.len() == 0 { return Ok("".to_string()); }
-    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<}");
+
+  // The card's GTs when the kernel lists them, else one GT read from the legacy card files.
+  fn i915_gts(card: &summary::I915Card) -> Vec<summary::I915Gt> {
+    if ! card.gts.is_empty() { return card.gts.clone(); }
+    vec![summary::I915Gt {
+      id: 0,
+      actual: card.actual.clone(),
+      requested: card.requested.clone(),
+      min: card.min.clone(),
+      max: card.max.clone(),
+      boost: card.boost.clone(),
+      min_limit: card.min_limit.clone(),
+      max_limit: card.max_limit.clone(),
+      optimum_limit: card.optimum_limit.clone(),
+      rc6_enable: None,
+      rc6_residency_ms: None,
+      rc6: None,
+      throttle_reasons: None,
+    }]
+  }
+
+  fn format_table_i915(cards: Option<&[summary::I915Card]>) -> Result<String> {
+    let cards = if let Some(cards) = cards { cards } else { return Ok("".to_string()); };
+    if cards.is_empty() { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<}");
     tab.add_row(Row::new()
       .with_cell("Card")
       .with_cell("Driver")
+      .with_cell("GT")
       .with_cell("Actual")
       .with_cell("Req'd")
       .with_cell("Min")
       .with_cell("Max")
       .with_cell("Boost")
       .with_cell("Min limit")
-      .with_cell("Max limit"));
+      .with_cell("Max limit")
+      .with_cell("Power")
+      .with_cell("Power cap"));
     tab.add_row(Row::new()
       .with_cell("------")
       .with_cell("-------")
+      .with_cell("---")
       .with_cell("--------")
       .with_cell("--------")
       .with_cell("--------")
       .with_cell("--------")
       .with_cell("--------")
       .with_cell("---------")
+      .with_cell("---------")
+      .with_cell("--------")
       .with_cell("---------"));
-    for card_id in card_ids {
+    for card in cards {
+      // Power is reported per card, it is shown on the card's first row.
+      let gts = Self::i915_gts(card);
+      for (i, gt) in gts.iter().enumerate() {
+        let (power, power_cap) =
+          if i == 0 { (Self::format_gpu_power(card.power.as_ref()), Self::format_gpu_power_cap(card.power.as_ref())) }
+          else { ("".to_string(), "".to_string()) };
+        tab.add_row(Row::new()
+          .with_cell(format!("card{}", card.id))
+          .with_cell(&card.driver)
+          .with_cell(if card.gts.is_empty() { "n/a".to_string() } else { format!("gt{}", gt.id) })
+          .with_cell(gt.actual.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.requested.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.min.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.max.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.boost.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.min_limit.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.max_limit.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(power)
+          .with_cell(power_cap));
+      }
+    }
+    let mut buf = tab.to_string();
+    buf.push('\n');
+    buf.push_str(&Self::format_table_i915_rc6(cards)?);
+    Ok(buf)
+  }
+
+  // RC6 residency is only known in refresh mode, as the share of the interval spent in RC6.
+  fn format_table_i915_rc6(cards: &[summary::I915Card]) -> Result<String> {
+    let has_rc6 = |gt: &summary::I915Gt| gt.rc6_enable.is_some() || gt.throttle_reasons.is_some();
+    if ! cards.iter().any(|c| c.gts.iter().any(has_rc6)) { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<}");
+    tab.add_row(Row::new()
+      .with_cell("Card")
+      .with_cell("GT")
+      .with_cell("RC6")
+      .with_cell("RC6 residency")
+      .with_cell("Throttle reasons"));
+    tab.add_row(Row::new()
+      .with_cell("------")
+      .with_cell("---")
+      .with_cell("-----")
+      .with_cell("-------------")
+      .with_cell("----------------"));
+    for card in cards {
+      for gt in card.gts.iter().filter(|gt| has_rc6(gt)) {
+        tab.add_row(Row::new()
+          .with_cell(format!("card{}", card.id))
+          .with_cell(format!("gt{}", gt.id))
+          .with_cell(gt.rc6_enable.map(|e| e.to_string()).unwrap_or("n/a".to_string()))
+          .with_cell(gt.rc6.map(|r| format!("{:.1}%", r)).unwrap_or("n/a".to_string()))
+          .with_cell(
+            match &gt.throttle_reasons {
+              Some(reasons) if reasons.is_empty() => "none".to_string(),
+              Some(reasons) => reasons.join(","),
+              None => "n/a".to_string(),
+            }
+          ));
+      }
+    }
+    let mut buf = tab.to_string();
+    buf.push('\n');
+    Ok(buf)
+  }
+
+  fn format_table_xe(cards: Option<&[summary::XeCard]>) -> Result<String> {
+    let cards = if let Some(cards) = cards { cards } else { return Ok("".to_string()); };
+    if cards.is_empty() { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<}");
+    tab.add_row(Row::new()
+      .with_cell("Card")
+      .with_cell("Driver")
+      .with_cell("Tile")
+      .with_cell("GT")
+      .with_cell("Actual")
+      .with_cell("Req'd")
+      .with_cell("Min")
+      .with_cell("Max")
+      .with_cell("Min limit")
+      .with_cell("Max limit")
+      .with_cell("Power")
+      .with_cell("Power cap"));
+    tab.add_row(Row::new()
+      .with_cell("------")
+      .with_cell("-------")
+      .with_cell("-----")
+      .with_cell("---")
+      .with_cell("--------")
+      .with_cell("--------")
+      .with_cell("--------")
+      .with_cell("--------")
+      .with_cell("---------")
+      .with_cell("---------")
+      .with_cell("--------")
+      .with_cell("---------"));
+    for card in cards {
+      // Power is reported per card, it is shown on the card's first GT.
+      for (i, gt) in card.gts.iter().enumerate() {
+        let (power, power_cap) =
+          if i == 0 { (Self::format_gpu_power(card.power.as_ref()), Self::format_gpu_power_cap(card.power.as_ref())) }
+          else { ("".to_string(), "".to_string()) };
+        tab.add_row(Row::new()
+          .with_cell(format!("card{}", card.id))
+          .with_cell(&card.driver)
+          .with_cell(format!("tile{}", gt.tile_id))
+          .with_cell(format!("gt{}", gt.id))
+          .with_cell(gt.actual.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.requested.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.min.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.max.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.min_limit.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(gt.max_limit.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(power)
+          .with_cell(power_cap));
+      }
+    }
+    let mut buf = tab.to_string();
+    buf.push('\n');
+    Ok(buf)
+  }
+
+  fn format_gpu_power(power: Option<&summary::GpuPower>) -> String {
+    power.and_then(|p| p.average.clone()).map(String::from).unwrap_or("n/a".to_string())
+  }
+
+  // The cap followed by its bounds when reported, e.g. `150.0 W (100.0 W - 250.0 W)`.
+  fn format_gpu_power_cap(power: Option<&summary::GpuPower>) -> String {
+    let power = if let Some(power) = power { power } else { return "n/a".to_string(); };
+    let cap = if let Some(cap) = &power.cap { cap.to_string() } else { return "n/a".to_string(); };
+    match (&power.cap_min, &power.cap_max) {
+      (Some(min), Some(max)) if max.microwatts() > 0 => format!("{} ({} - {})", cap, min, max),
+      _ => cap,
+    }
+  }
+
+  fn format_table_amdgpu(cards: Option<&[summary::AmdgpuCard]>) -> Result<String> {
+
+    fn levels(levels: &Option<Vec<amdgpu::DpmLevel>>) -> String {
+      match levels {
+        Some(levels) if ! levels.is_empty() =>
+          levels.iter().map(|l| format!("{}:{}", l.id, l.freq.mhz() as u64)).collect::<Vec<String>>().join(" "),
+        _ => "n/a".to_string(),
+      }
+    }
+
+    let cards = if let Some(cards) = cards { cards } else { return Ok("".to_string()); };
+    if cards.is_empty() { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<}");
+    tab.add_row(Row::new()
+      .with_cell("Card")
+      .with_cell("Driver")
+      .with_cell("Perf level")
+      .with_cell("Profile")
+      .with_cell("Sclk")
+      .with_cell("Mclk")
+      .with_cell("Power")
+      .with_cell("Power cap")
+      .with_cell("Sclk levels (MHz)")
+      .with_cell("Mclk levels (MHz)"));
+    tab.add_row(Row::new()
+      .with_cell("------")
+      .with_cell("-------")
+      .with_cell("----------")
+      .with_cell("--------------")
+      .with_cell("--------")
+      .with_cell("--------")
+      .with_cell("--------")
+      .with_cell("---------")
+      .with_cell("-----------------")
+      .with_cell("-----------------"));
+    for card in cards {
       tab.add_row(Row::new()
-        .with_cell(format!("card{}", card_id))
-        .with_cell(format!("i915"))
-        .with_cell(i915::actual(card_id)?.map(String::from).unwrap_or("n/a".to_string()))
-        .with_cell(i915::requested(card_id)?.map(String::from).unwrap_or("n/a".to_string()))
-        .with_cell(i915::min(card_id)?.map(String::from).unwrap_or("n/a".to_string()))
-        .with_cell(i915::max(card_id)?.map(String::from).unwrap_or("n/a".to_string()))
-        .with_cell(i915::boost(card_id)?.map(String::from).unwrap_or("n/a".to_string()))
-        .with_cell(i915::min_limit(card_id)?.map(String::from).unwrap_or("n/a".to_string()))
-        .with_cell(i915::max_limit(card_id)?.map(String::from).unwrap_or("n/a".to_string())));
+        .with_cell(format!("card{}", card.id))
+        .with_cell(&card.driver)
+        .with_cell(card.performance_level.clone().unwrap_or("n/a".to_string()))
+        .with_cell(card.power_profile.clone().unwrap_or("n/a".to_string()))
+        .with_cell(card.sclk.clone().map(String::from).unwrap_or("n/a".to_string()))
+        .with_cell(card.mclk.clone().map(String::from).unwrap_or("n/a".to_string()))
+        .with_cell(Self::format_gpu_power(card.power.as_ref()))
+        .with_cell(Self::format_gpu_power_cap(card.power.as_ref()))
+        .with_cell(levels(&card.sclk_levels))
+        .with_cell(levels(&card.mclk_levels)));
     }
     let mut buf = tab.to_string();
     buf.push('\n');
+    buf.push_str(&Self::format_table_amdgpu_od(cards)?);
     Ok(buf)
   }
 
-  fn format_table_pstate(cpu_ids: Vec<u64>) -> Result<String> {
-    if cpu_ids.len() == 0 { return Ok("".to_string()); }
+  fn format_table_amdgpu_od(cards: &[summary::AmdgpuCard]) -> Result<String> {
+    let has_points = |od: &amdgpu::OdTable| ! (od.sclk.is_empty() && od.mclk.is_empty() && od.vddc_curve.is_empty());
+    if ! cards.iter().any(|c| c.od.as_ref().map(has_points).unwrap_or(false)) { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<}");
+    tab.add_row(Row::new()
+      .with_cell("Card")
+      .with_cell("OD")
+      .with_cell("Point")
+      .with_cell("Clock")
+      .with_cell("Voltage")
+      .with_cell("Clock range")
+      .with_cell("Voltage range"));
+    tab.add_row(Row::new()
+      .with_cell("------")
+      .with_cell("----")
+      .with_cell("-----")
+      .with_cell("--------")
+      .with_cell("-------")
+      .with_cell("-------------")
+      .with_cell("-------------"));
+    for card in cards {
+      let od = if let Some(od) = &card.od { od } else { continue; };
+      for (section, points) in &[(OdSection::Sclk, &od.sclk), (OdSection::Mclk, &od.mclk), (OdSection::VddcCurve, &od.vddc_curve)] {
+        for point in points.iter() {
+          let (freq_range, voltage_range) = od.ranges(*section, point.id);
+          tab.add_row(Row::new()
+            .with_cell(format!("card{}", card.id))
+            .with_cell(section)
+            .with_cell(point.id)
+            .with_cell(format!("{} MHz", point.freq.mhz() as u64))
+            .with_cell(point.voltage.clone().map(|v| format!("{} mV", v.millivolts())).unwrap_or("n/a".to_string()))
+            .with_cell(freq_range.map(|r| format!("{} - {} MHz", r.min.mhz() as u64, r.max.mhz() as u64)).unwrap_or("n/a".to_string()))
+            .with_cell(voltage_range.map(|r| format!("{} - {} mV", r.min.millivolts(), r.max.millivolts())).unwrap_or("n/a".to_string())));
+        }
+      }
+    }
+    let mut buf = tab.to_string();
+    buf.push('\n');
+    Ok(buf)
+  }
+
+  fn format_table_idle(idle: Option<&summary::Cpuidle>, cpus: &[summary::Cpu]) -> Result<String> {
+    // States are listed by name in the order of their first appearance, cpus may not all
+    // have the same states.
+    let mut names: Vec<String> = vec![];
+    let mut states: Vec<&summary::IdleState> = vec![];
+    for state in cpus.iter().filter_map(|c| c.idle_states.as_ref()).flatten() {
+      let name = if let Some(ref name) = state.name { name } else { continue; };
+      if names.contains(name) { continue; }
+      names.push(name.clone());
+      states.push(state);
+    }
+    if states.is_empty() { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<}");
+    tab.add_row(Row::new()
+      .with_cell("State")
+      .with_cell("Name")
+      .with_cell("Latency")
+      .with_cell("Residency")
+      .with_cell("Description"));
+    tab.add_row(Row::new()
+      .with_cell("-------")
+      .with_cell("--------")
+      .with_cell("--------")
+      .with_cell("---------")
+      .with_cell("----------------"));
+    for state in &states {
+      tab.add_row(Row::new()
+        .with_cell(format!("state{}", state.id))
+        .with_cell(state.name.clone().unwrap_or("n/a".to_string()))
+        .with_cell(state.latency.map(|v| format!("{} µs", v)).unwrap_or("n/a".to_string()))
+        .with_cell(state.residency.map(|v| format!("{} µs", v)).unwrap_or("n/a".to_string()))
+        .with_cell(state.desc.clone().unwrap_or("n/a".to_string())));
+    }
+    let mut tab_cpus = Table::new(&vec!["{:<}"; names.len() + 1].join(" "));
+    let mut header = Row::new().with_cell("CPU");
+    let mut dashes = Row::new().with_cell("-------");
+    for name in &names {
+      header.add_cell(name);
+      dashes.add_cell("-".repeat(name.len().max(4)));
+    }
+    tab_cpus.add_row(header);
+    tab_cpus.add_row(dashes);
+    for cpu in cpus {
+      let mut row = Row::new().with_cell(format!("cpu{}", cpu.id));
+      for name in &names {
+        let state = cpu.idle_states.iter().flatten().find(|s| s.name.as_ref() == Some(name));
+        row.add_cell(
+          match state.and_then(|s| s.disabled) {
+            Some(true) => "off",
+            Some(false) => "on",
+            None => "n/a",
+          });
+      }
+      tab_cpus.add_row(row);
+    }
+    let driver = idle.and_then(|i| i.driver.clone()).unwrap_or("n/a".to_string());
+    let governor = idle.and_then(|i| i.governor.clone()).unwrap_or("n/a".to_string());
+    let governors = idle.and_then(|i| i.governors.as_ref().map(|g| g.join(","))).unwrap_or("n/a".to_string());
+    let mut res = String::new();
+    res.push_str(&format!("cpuidle: {}, governor {} ({})\n\n", driver, governor, governors));
+    res.push_str(&tab.to_string());
+    res.push('\n');
+    res.push_str(&tab_cpus.to_string());
+    res.push('\n');
+    Ok(res)
+  }
+
+  fn format_table_devfreq(devices: Option<&[summary::DevfreqDevice]>) -> Result<String> {
+    let devices = if let Some(devices) = devices { devices } else { return Ok("".to_string()); };
+    if devices.is_empty() { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<}");
+    tab.add_row(Row::new()
+      .with_cell("Device")
+      .with_cell("Governor")
+      .with_cell("Cur")
+      .with_cell("Min")
+      .with_cell("Max")
+      .with_cell("Transitions")
+      .with_cell("Frequencies (MHz)")
+      .with_cell("Governors"));
+    tab.add_row(Row::new()
+      .with_cell("------------")
+      .with_cell("----------------")
+      .with_cell("---------")
+      .with_cell("---------")
+      .with_cell("---------")
+      .with_cell("-----------")
+      .with_cell("-----------------")
+      .with_cell("----------------"));
+    for device in devices {
+      let frequencies =
+        match device.frequencies {
+          Some(ref freqs) if ! freqs.is_empty() =>
+            freqs.iter().map(|f| (f.mhz() as u64).to_string()).collect::<Vec<String>>().join(" "),
+          _ => "n/a".to_string(),
+        };
+      tab.add_row(Row::new()
+        .with_cell(&device.name)
+        .with_cell(device.governor.clone().unwrap_or("n/a".to_string()))
+        .with_cell(device.cur.clone().map(String::from).unwrap_or("n/a".to_string()))
+        .with_cell(device.min.clone().map(String::from).unwrap_or("n/a".to_string()))
+        .with_cell(device.max.clone().map(String::from).unwrap_or("n/a".to_string()))
+        .with_cell(device.trans_stat.as_ref().and_then(|t| t.total_transitions).map(|t| t.to_string()).unwrap_or("n/a".to_string()))
+        .with_cell(frequencies)
+        .with_cell(device.governors.as_ref().map(|v| v.join(",")).unwrap_or("n/a".to_string())));
+    }
+    let mut buf = tab.to_string();
+    buf.push('\n');
+    Ok(buf)
+  }
+
+  fn format_table_pstate(pstate: Option<&summary::IntelPstate>, cpus: &[summary::Cpu]) -> Result<String> {
+    if cpus.is_empty() { return Ok("".to_string()); }
     let mut tab = Table::new("{:<} {:<} {:<} {:<}");
     tab.add_row(Row::new()
       .with_cell("CPU")
@@ -266,32 +1554,233 @@ impl Cli {
       .with_cell("----")
       .with_cell("--------------------")
       .with_cell("--------------------"));
-    for cpu_id in cpu_ids {
+    for cpu in cpus {
       tab.add_row(Row::new()
-        .with_cell(format!("cpu{}", cpu_id))
-        .with_cell(pstate::epb(cpu_id)?.map(|v| v.to_string()).unwrap_or("n/a".to_string()))
-        .with_cell(pstate::epp(cpu_id)?.unwrap_or("n/a".to_string()))
-        .with_cell(pstate::epps(cpu_id)?.map(|v| v.join(",")).unwrap_or("n/a".to_string())));
+        .with_cell(format!("cpu{}", cpu.id))
+        .with_cell(cpu.epb.map(|v| v.to_string()).unwrap_or("n/a".to_string()))
+        .with_cell(cpu.epp.clone().unwrap_or("n/a".to_string()))
+        .with_cell(cpu.epps.as_ref().map(|v| v.join(",")).unwrap_or("n/a".to_string())));
+    }
+    let mut header = vec![format!("intel_pstate: {}", pstate.and_then(|p| p.status.clone()).unwrap_or("n/a".to_string()))];
+    if let Some(pstate) = pstate {
+      if let Some(no_turbo) = pstate.no_turbo { header.push(format!("turbo: {}", if no_turbo { "off" } else { "on" })); }
+      if let (Some(min), Some(max)) = (pstate.min_perf_pct, pstate.max_perf_pct) { header.push(format!("perf: {}% - {}%", min, max)); }
+      if let Some(num_pstates) = pstate.num_pstates { header.push(format!("pstates: {}", num_pstates)); }
+      if let Some(turbo_pct) = pstate.turbo_pct { header.push(format!("turbo pstates: {}%", turbo_pct)); }
+      if let Some(boost) = pstate.hwp_dynamic_boost { header.push(format!("dynamic boost: {}", if boost { "on" } else { "off" })); }
     }
     let mut res = String::new();
-    res.push_str(&format!("intel_pstate: {}\n\n", pstate::status()?.unwrap_or("n/a".to_string())));
+    res.push_str(&header.join("  "));
+    res.push_str("\n\n");
     res.push_str(&tab.to_string());
     res.push('\n');
     Ok(res)
   }
   
+  fn format_table_amd_pstate(amd: Option<&summary::AmdPstate>, cpus: &[summary::Cpu]) -> Result<String> {
+    if cpus.is_empty() { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<}");
+    tab.add_row(Row::new()
+      .with_cell("CPU")
+      .with_cell("EP Pref")
+      .with_cell("EP Prefs")
+      .with_cell("Highest perf")
+      .with_cell("Ranking")
+      .with_cell("Lowest nonlinear")
+      .with_cell("Max freq"));
+    tab.add_row(Row::new()
+      .with_cell("--------")
+      .with_cell("--------------------")
+      .with_cell("--------------------")
+      .with_cell("------------")
+      .with_cell("-------")
+      .with_cell("----------------")
+      .with_cell("---------"));
+    for cpu in cpus {
+      let amd = cpu.amd_pstate.as_ref();
+      tab.add_row(Row::new()
+        .with_cell(format!("cpu{}", cpu.id))
+        .with_cell(cpu.epp.clone().unwrap_or("n/a".to_string()))
+        .with_cell(cpu.epps.as_ref().map(|v| v.join(",")).unwrap_or("n/a".to_string()))
+        .with_cell(amd.and_then(|a| a.highest_perf).map(|v| v.to_string()).unwrap_or("n/a".to_string()))
+        .with_cell(amd.and_then(|a| a.prefcore_ranking).map(|v| v.to_string()).unwrap_or("n/a".to_string()))
+        .with_cell(amd.and_then(|a| a.lowest_nonlinear_freq.clone()).map(String::from).unwrap_or("n/a".to_string()))
+        .with_cell(amd.and_then(|a| a.max_freq.clone()).map(String::from).unwrap_or("n/a".to_string())));
+    }
+    let status = amd.and_then(|a| a.status.clone());
+    let prefcore = amd.and_then(|a| a.prefcore.clone());
+    let mut res = String::new();
+    res.push_str(&format!("amd_pstate: {}  prefcore: {}\n\n", status.unwrap_or("n/a".to_string()), prefcore.unwrap_or("n/a".to_string())));
+    res.push_str(&tab.to_string());
+    res.push('\n');
+    Ok(res)
+  }
+
+  fn format_table_hwmon(chips: Option<&[summary::HwmonChip]>, cpus: &[summary::Cpu]) -> Result<String> {
+    let chips = if let Some(chips) = chips { chips } else { return Ok("".to_string()); };
+    if chips.is_empty() { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<}");
+    tab.add_row(Row::new()
+      .with_cell("Chip")
+      .with_cell("Name")
+      .with_cell("Sensor")
+      .with_cell("Label")
+      .with_cell("Input")
+      .with_cell("Max")
+      .with_cell("Crit"));
+    tab.add_row(Row::new()
+      .with_cell("--------")
+      .with_cell("------------")
+      .with_cell("------")
+      .with_cell("------------")
+      .with_cell("---------")
+      .with_cell("---------")
+      .with_cell("---------"));
+    for chip in chips {
+      let chip_name = chip.name.clone().unwrap_or("n/a".to_string());
+      for temp in &chip.temps {
+        tab.add_row(Row::new()
+          .with_cell(format!("hwmon{}", chip.id))
+          .with_cell(&chip_name)
+          .with_cell(format!("temp{}", temp.id))
+          .with_cell(temp.label.clone().unwrap_or("n/a".to_string()))
+          .with_cell(temp.input.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(temp.max.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(temp.crit.clone().map(String::from).unwrap_or("n/a".to_string())));
+      }
+      for fan in &chip.fans {
+        tab.add_row(Row::new()
+          .with_cell(format!("hwmon{}", chip.id))
+          .with_cell(&chip_name)
+          .with_cell(format!("fan{}", fan.id))
+          .with_cell("n/a")
+          .with_cell(fan.input.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell("n/a")
+          .with_cell("n/a"));
+      }
+      for voltage in &chip.voltages {
+        tab.add_row(Row::new()
+          .with_cell(format!("hwmon{}", chip.id))
+          .with_cell(&chip_name)
+          .with_cell(format!("in{}", voltage.id))
+          .with_cell("n/a")
+          .with_cell(voltage.input.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell("n/a")
+          .with_cell("n/a"));
+      }
+      for pwm in &chip.pwms {
+        tab.add_row(Row::new()
+          .with_cell(format!("hwmon{}", chip.id))
+          .with_cell(&chip_name)
+          .with_cell(format!("pwm{}", pwm.id))
+          .with_cell(pwm.mode.map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(pwm.value.map(|v| v.to_string()).unwrap_or("n/a".to_string()))
+          .with_cell("n/a")
+          .with_cell("n/a"));
+      }
+    }
+    let mut buf = tab.to_string();
+    buf.push('\n');
+    if cpus.iter().any(|c| c.package_temp.is_some() || c.core_temp.is_some()) {
+      let mut tab = Table::new("{:<} {:<} {:<}");
+      tab.add_row(Row::new()
+        .with_cell("CPU")
+        .with_cell("Package temp")
+        .with_cell("Core temp"));
+      tab.add_row(Row::new()
+        .with_cell("-------")
+        .with_cell("------------")
+        .with_cell("---------"));
+      for cpu in cpus {
+        tab.add_row(Row::new()
+          .with_cell(format!("cpu{}", cpu.id))
+          .with_cell(cpu.package_temp.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(cpu.core_temp.clone().map(String::from).unwrap_or("n/a".to_string())));
+      }
+      buf.push_str(&tab.to_string());
+      buf.push('\n');
+    }
+    Ok(buf)
+  }
+
+  fn format_time_window(us: u64) -> String {
+    if us >= 1_000_000 { format!("{:.1} s", us as f64 / 1_000_000.) }
+    else if us >= 1_000 { format!("{:.1} ms", us as f64 / 1_000.) }
+    else { format!("{} µs", us) }
+  }
+
+  fn format_table_rapl(zones: Option<&[summary::RaplZone]>) -> Result<String> {
+    let zones = if let Some(zones) = zones { zones } else { return Ok("".to_string()); };
+    if zones.is_empty() { return Ok("".to_string()); }
+    let mut tab = Table::new("{:<} {:<} {:<} {:<} {:<} {:<} {:<} {:<}");
+    tab.add_row(Row::new()
+      .with_cell("Zone")
+      .with_cell("Name")
+      .with_cell("Enabled")
+      .with_cell("Power")
+      .with_cell("Constraint")
+      .with_cell("Limit")
+      .with_cell("Window")
+      .with_cell("Max"));
+    tab.add_row(Row::new()
+      .with_cell("--------------")
+      .with_cell("---------")
+      .with_cell("-------")
+      .with_cell("-------")
+      .with_cell("----------")
+      .with_cell("-------")
+      .with_cell("--------")
+      .with_cell("-------"));
+    for zone in zones {
+      let zone_name = zone.name.clone().unwrap_or("n/a".to_string());
+      let enabled = zone.enabled.map(|v| v.to_string()).unwrap_or("n/a".to_string());
+      let power = zone.power.clone().map(String::from).unwrap_or("n/a".to_string());
+      if zone.constraints.is_empty() {
+        tab.add_row(Row::new()
+          .with_cell(zone.id)
+          .with_cell(&zone_name)
+          .with_cell(&enabled)
+          .with_cell(&power)
+          .with_cell("n/a")
+          .with_cell("n/a")
+          .with_cell("n/a")
+          .with_cell("n/a"));
+      }
+      for constraint in &zone.constraints {
+        tab.add_row(Row::new()
+          .with_cell(zone.id)
+          .with_cell(&zone_name)
+          .with_cell(&enabled)
+          .with_cell(&power)
+          .with_cell(constraint.name.clone().unwrap_or("n/a".to_string()))
+          .with_cell(constraint.power_limit.clone().map(String::from).unwrap_or("n/a".to_string()))
+          .with_cell(constraint.time_window_us.map(Self::format_time_window).unwrap_or("n/a".to_string()))
+          .with_cell(constraint.max_power.clone().map(String::from).unwrap_or("n/a".to_string())));
+      }
+    }
+    let mut buf = tab.to_string();
+    buf.push('\n');
+    Ok(buf)
+  }
+
   fn has_table_args(&self) -> bool {
-    return
-      self.cpu ||
-      self.freq ||
-      self.i915 ||
-      self.pstate
+    self.amd_pstate ||
+    self.amdgpu ||
+    self.cpu ||
+    self.devfreq ||
+    self.freq ||
+    self.hwmon ||
+    self.i915 ||
+    self.idle ||
+    self.pstate ||
+    self.rapl ||
+    self.xe
   }
 
-  fn format_tables(&self) -> Result<String> {
+  fn format_tables(&self, summary: &Summary) -> Result<String> {
 
     fn indent(text: &str, level: usize) -> String {
-      let i = std::iter::repeat(" ").take(level).collect::<String>();
+      let i = " ".repeat(level);
       text
         .split('\n')
         .map(|s| format!("{}{}", i, s))
@@ -300,44 +1789,305 @@ impl Cli {
       // FIXME
     }
 
-    let cpu_ids = cpu::cpus()?;
     let has_table_args = self.has_table_args();
     let mut buf = String::new();
     buf.push('\n');
-    if self.pstate || (! has_table_args && pstate::available()) 
-      { buf.push_str(&Self::format_table_pstate(cpu_ids.clone())?); }
+    if self.pstate || (! has_table_args && summary.intel_pstate.is_some())
+      { buf.push_str(&Self::format_table_pstate(summary.intel_pstate.as_ref(), &summary.cpus)?); }
+    if self.amd_pstate || (! has_table_args && summary.amd_pstate.is_some())
+      { buf.push_str(&Self::format_table_amd_pstate(summary.amd_pstate.as_ref(), &summary.cpus)?); }
     if self.freq || (! has_table_args && cpufreq::available())
-      { buf.push_str(&Self::format_table_freq(cpu_ids.clone())?); }
+      { buf.push_str(&Self::format_table_freq(summary.policies.as_deref())?); }
     if self.cpu || ! has_table_args
-      { buf.push_str(&Self::format_table_cpu(cpu_ids.clone())?); }
-    if self.i915 || (! has_table_args && i915::available())
-      { buf.push_str(&Self::format_table_i915(i915::cards()?)?); }
+      { buf.push_str(&Self::format_table_cpu(&summary.cpus)?); }
+    if self.idle || (! has_table_args && summary.cpuidle.is_some())
+      { buf.push_str(&Self::format_table_idle(summary.cpuidle.as_ref(), &summary.cpus)?); }
+    let i915 = self.i915 || (! has_table_args && summary.i915.is_some());
+    let xe = self.xe || (! has_table_args && summary.xe.is_some());
+    let amdgpu = self.amdgpu || (! has_table_args && summary.amdgpu.is_some());
+    // Identifies the cards of the GPU tables which follow.
+    if i915 || xe || amdgpu
+      { buf.push_str(&Self::format_table_gpu(summary.gpus.as_deref())?); }
+    if i915
+      { buf.push_str(&Self::format_table_i915(summary.i915.as_deref())?); }
+    if xe
+      { buf.push_str(&Self::format_table_xe(summary.xe.as_deref())?); }
+    if amdgpu
+      { buf.push_str(&Self::format_table_amdgpu(summary.amdgpu.as_deref())?); }
+    if self.devfreq || (! has_table_args && summary.devfreq.is_some())
+      { buf.push_str(&Self::format_table_devfreq(summary.devfreq.as_deref())?); }
+    if self.hwmon || (! has_table_args && summary.hwmon.is_some())
+      { buf.push_str(&Self::format_table_hwmon(summary.hwmon.as_deref(), &summary.cpus)?); }
+    if self.rapl || (! has_table_args && summary.rapl.is_some())
+      { buf.push_str(&Self::format_table_rapl(summary.rapl.as_deref())?); }
     let mut buf = indent(&buf, 2).trim_end().to_string();
     buf.push_str("\n\n");
     Ok(buf)
   }
 
-  fn print_tables(&self) -> Result<()> {
-    print!("{}", self.format_tables()?);
-    Ok(())
+  fn format_json(summary: &Summary, pretty: bool) -> Result<String> {
+    let mut buf =
+      if pretty { serde_json::to_string_pretty(summary)? }
+      else { serde_json::to_string(summary)? };
+    buf.push('\n');
+    Ok(buf)
+  }
+
+  fn rows(&self) -> Result<Rows> {
+    let args = [
+      ("i915", self.i915, Rows::I915),
+      ("xe", self.xe, Rows::Xe),
+      ("amdgpu", self.amdgpu, Rows::Amdgpu),
+      ("devfreq", self.devfreq, Rows::Devfreq),
+      ("amd-pstate", self.amd_pstate, Rows::Cpu),
+      ("cpu", self.cpu, Rows::Cpu),
+      ("freq", self.freq, Rows::Cpu),
+      ("hwmon", self.hwmon, Rows::Cpu),
+      ("idle", self.idle, Rows::Cpu),
+      ("pstate", self.pstate, Rows::Cpu),
+      ("rapl", self.rapl, Rows::Cpu),
+    ];
+    let mut res: Option<(&'static str, Rows)> = None;
+    for (arg, _, rows) in args.iter().filter(|(_, given, _)| *given) {
+      match res {
+        Some((prev, prev_rows)) if prev_rows != *rows => return Err(Error::RowsMixed(prev, arg)),
+        Some(_) => (),
+        None => res = Some((arg, *rows)),
+      }
+    }
+    // Cards selected by --cards print their driver's rows, unless CPU or devfreq rows are
+    // asked for.
+    if let (None | Some((_, Rows::I915 | Rows::Xe | Rows::Amdgpu)), Some(cards)) = (res, self.selected_cards()?) {
+      for card_id in cards {
+        let driver = drm::card_driver(card_id)?.unwrap_or_default();
+        let rows =
+          match driver.as_str() {
+            "i915" => Rows::I915,
+            "xe" => Rows::Xe,
+            "amdgpu" => Rows::Amdgpu,
+            _ => return Err(Error::RowsCardMixed(card_id, driver, res.map(|(arg, _)| arg).unwrap_or("gpu"))),
+          };
+        match res {
+          Some((arg, prev_rows)) if prev_rows != rows => return Err(Error::RowsCardMixed(card_id, driver, arg)),
+          Some(_) => (),
+          None => res = Some((rows.arg(), rows)),
+        }
+      }
+    }
+    Ok(res.map(|(_, rows)| rows).unwrap_or(Rows::Cpu))
+  }
+
+  fn format_row(fields: &[String], sep: char) -> String {
+    let mut buf = fields
+      .iter()
+      .map(|f|
+        if sep == '\t' { f.replace(&['\t', '\n'][..], " ") }
+        else if f.contains(&[',', '"', '\n'][..]) { format!("\"{}\"", f.replace('"', "\"\"")) }
+        else { f.clone() })
+      .collect::<Vec<String>>()
+      .join(&sep.to_string());
+    buf.push('\n');
+    buf
+  }
+
+  fn format_rows_header(&self, sep: char) -> Result<String> {
+    let header: &[&str] =
+      match self.rows()? {
+        Rows::Cpu => &["time", "cpu", "online", "cur", "min", "max", "min_limit", "max_limit", "governor", "epb", "epp", "package_temp", "core_temp"],
+        Rows::I915 => &["time", "card", "driver", "gt", "actual", "requested", "min", "max", "boost", "min_limit", "max_limit"],
+        Rows::Xe => &["time", "card", "driver", "tile", "gt", "actual", "requested", "min", "max", "min_limit", "max_limit"],
+        Rows::Amdgpu => &["time", "card", "driver", "performance_level", "power_profile", "sclk", "mclk", "power", "power_cap"],
+        Rows::Devfreq => &["time", "device", "governor", "cur", "min", "max"],
+      };
+    Ok(Self::format_row(&header.iter().map(|h| h.to_string()).collect::<Vec<String>>(), sep))
+  }
+
+  fn format_rows(&self, summary: &Summary, time: Duration, sep: char) -> Result<String> {
+
+    fn opt<T: ToString>(val: &Option<T>) -> String {
+      val.as_ref().map(|v| v.to_string()).unwrap_or_default()
+    }
+
+    fn hz(val: &Option<Hertz>) -> String {
+      val.as_ref().map(|v| v.hz().to_string()).unwrap_or_default()
+    }
+
+    fn celsius(val: &Option<Celsius>) -> String {
+      val.as_ref().map(|v| v.celsius().to_string()).unwrap_or_default()
+    }
+
+    fn watts(val: Option<&Watts>) -> String {
+      val.map(|v| v.watts().to_string()).unwrap_or_default()
+    }
+
+    let time = format!("{}.{:03}", time.as_secs(), time.subsec_millis());
+    let cards = self.selected_cards()?;
+    let mut buf = String::new();
+    match self.rows()? {
+      Rows::Cpu =>
+        for cpu in &summary.cpus {
+          buf.push_str(&Self::format_row(&[
+            time.clone(),
+            format!("cpu{}", cpu.id),
+            cpu.online.to_string(),
+            hz(&cpu.cur),
+            hz(&cpu.min),
+            hz(&cpu.max),
+            hz(&cpu.min_limit),
+            hz(&cpu.max_limit),
+            opt(&cpu.governor),
+            opt(&cpu.epb),
+            opt(&cpu.epp),
+            celsius(&cpu.package_temp),
+            celsius(&cpu.core_temp),
+          ], sep));
+        },
+      Rows::I915 =>
+        for card in summary.i915.as_deref().unwrap_or_default() {
+          if ! Self::is_card_selected(&cards, card.id) { continue; }
+          for gt in Self::i915_gts(card) {
+            buf.push_str(&Self::format_row(&[
+              time.clone(),
+              format!("card{}", card.id),
+              card.driver.clone(),
+              if card.gts.is_empty() { "".to_string() } else { format!("gt{}", gt.id) },
+              hz(&gt.actual),
+              hz(&gt.requested),
+              hz(&gt.min),
+              hz(&gt.max),
+              hz(&gt.boost),
+              hz(&gt.min_limit),
+              hz(&gt.max_limit),
+            ], sep));
+          }
+        },
+      Rows::Xe =>
+        for card in summary.xe.as_deref().unwrap_or_default() {
+          if ! Self::is_card_selected(&cards, card.id) { continue; }
+          for gt in &card.gts {
+            buf.push_str(&Self::format_row(&[
+              time.clone(),
+              format!("card{}", card.id),
+              card.driver.clone(),
+              format!("tile{}", gt.tile_id),
+              format!("gt{}", gt.id),
+              hz(&gt.actual),
+              hz(&gt.requested),
+              hz(&gt.min),
+              hz(&gt.max),
+              hz(&gt.min_limit),
+              hz(&gt.max_limit),
+            ], sep));
+          }
+        },
+      Rows::Amdgpu =>
+        for card in summary.amdgpu.as_deref().unwrap_or_default() {
+          if ! Self::is_card_selected(&cards, card.id) { continue; }
+          buf.push_str(&Self::format_row(&[
+            time.clone(),
+            format!("card{}", card.id),
+            card.driver.clone(),
+            opt(&card.performance_level),
+            opt(&card.power_profile),
+            hz(&card.sclk),
+            hz(&card.mclk),
+            watts(card.power.as_ref().and_then(|p| p.average.as_ref())),
+            watts(card.power.as_ref().and_then(|p| p.cap.as_ref())),
+          ], sep));
+        },
+      Rows::Devfreq =>
+        for device in summary.devfreq.as_deref().unwrap_or_default() {
+          buf.push_str(&Self::format_row(&[
+            time.clone(),
+            device.name.clone(),
+            opt(&device.governor),
+            hz(&device.cur),
+            hz(&device.min),
+            hz(&device.max),
+          ], sep));
+        },
+    }
+    Ok(buf)
+  }
+
+  // Parts of the summary which the output needs. JSON and the default tables show everything
+  // which is detected.
+  fn summary_select(&self) -> Result<Select> {
+    match self.format {
+      Format::Json => Ok(Select::all()),
+      Format::Csv | Format::Tsv =>
+        match self.rows()? {
+          Rows::Cpu => Ok(Select { cpufreq: true, intel_pstate: true, hwmon: true, ..Select::default() }),
+          Rows::I915 => Ok(Select { i915: true, ..Select::default() }),
+          Rows::Xe => Ok(Select { xe: true, ..Select::default() }),
+          Rows::Amdgpu => Ok(Select { amdgpu: true, ..Select::default() }),
+          Rows::Devfreq => Ok(Select { devfreq: true, ..Select::default() }),
+        },
+      Format::Table if ! self.has_table_args() => Ok(Select::all()),
+      Format::Table =>
+        Ok(Select {
+          cpufreq: self.cpu || self.freq,
+          intel_pstate: self.pstate,
+          amd_pstate: self.amd_pstate,
+          cpuidle: self.idle,
+          gpus: self.i915 || self.xe || self.amdgpu,
+          i915: self.i915,
+          xe: self.xe,
+          amdgpu: self.amdgpu,
+          devfreq: self.devfreq,
+          hwmon: self.hwmon,
+          rapl: self.rapl,
+        }),
+    }
+  }
+
+  // Prints a summary, with values computed against `prev` if given. Returns the sample for the
+  // next call.
+  fn print_summary(&self, prev: Option<&(Summary, Instant)>) -> Result<(Summary, Instant)> {
+    let mut summary = Summary::read_selected(&self.summary_select()?)?;
+    let now = Instant::now();
+    if let Some((prev, then)) = prev { summary.set_deltas(prev, now.duration_since(*then)); }
+    match self.format {
+      Format::Table => print!("{}", self.format_tables(&summary)?),
+      Format::Json => print!("{}", Self::format_json(&summary, self.refresh.is_none())?),
+      Format::Csv | Format::Tsv => {
+        let sep = if let Format::Csv = self.format { ',' } else { '\t' };
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        if prev.is_none() { print!("{}", self.format_rows_header(sep)?); }
+        print!("{}", self.format_rows(&summary, time, sep)?);
+      },
+    }
+    std::io::stdout().flush()?;
+    Ok((summary, now))
   }
 
   fn refresh(&self) -> Result<()> {
     let refresh = if let Some(refresh) = self.refresh { refresh } else { return Ok(()); };
     let refresh = if refresh == 0 { 1 } else { refresh };
-    let refresh = std::time::Duration::from_secs(refresh);
+    let refresh = Duration::from_secs(refresh);
+    let mut prev = None;
     loop {
-      print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
-      self.print_tables()?;
+      if let Format::Table = self.format { print!("{esc}[2J{esc}[1;1H", esc = 27 as char); }
+      prev = Some(self.print_summary(prev.as_ref())?);
       std::thread::sleep(refresh);
     }
   }
 
+  fn hold(&self) {
+    loop { std::thread::sleep(Duration::from_secs(3600)); }
+  }
+
   pub fn run(&self) -> Result<()> {
     self.setup_logging()?;
+    if let Some(ref root) = self.root { sysfs::set_root(root); }
+    if let Some(Command::Capture { ref dest }) = self.command { return Ok(capture::capture(dest)?); }
+    let _pwm_restore = PwmRestoreGuard;
     self.apply_controls()?;
     if self.refresh.is_some() { self.refresh()?; }
-    else { self.print_tables()?; }
+    else {
+      self.print_summary(None)?;
+      if self.hwmon_pwm_hold && self.has_control_args_hwmon() { self.hold(); }
+    }
     Ok(())
   }
 }
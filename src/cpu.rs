@@ -23,15 +23,34 @@ pub enum Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-fn allow_missing_if_cpu_exists<T>(cpu_id: u64, result: Result<T>) -> Result<Option<T>> {
+// Module errors which tell whether a file was not found.
+pub(crate) trait NotFound {
+  fn is_not_found(&self) -> bool;
+}
+
+impl NotFound for Error {
+  fn is_not_found(&self) -> bool {
+    matches!(self, Error::CpuxPseudofs(pseudofs::Error::NotFound(_, _)))
+  }
+}
+
+// A missing file of an existing cpu is a missing attribute, e.g. while the cpu is offline, and
+// is passed to the module's `allow_missing_files`. A missing cpu is an error.
+pub(crate) fn allow_missing_if_exists<T, E: NotFound>(
+  cpu_id: u64,
+  result: std::result::Result<T, E>,
+  allow_missing_files: impl FnOnce(std::result::Result<T, E>) -> std::result::Result<Option<T>, E>,
+) -> std::result::Result<Option<T>, E> {
+  if let Err(err) = &result {
+    if err.is_not_found() && ! exists(cpu_id) { return result.map(Some); }
+  }
+  allow_missing_files(result)
+}
+
+fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
   match result {
     Ok(val) => Ok(Some(val)),
-    Err(Error::CpuxPseudofs(err)) => {
-      if let pseudofs::Error::NotFound(_, _) = err {
-        if ! exists(cpu_id) { return Err(Error::CpuxPseudofs(err)); }
-      }
-      Ok(pseudofs::allow_missing_files(Err(err))? )
-    },
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
     Err(err) => Err(err),
   }
 }
@@ -54,7 +73,7 @@ pub fn try_online(cpu_id: u64) -> Result<bool> {
 }
 
 pub fn online(cpu_id: u64) -> Result<Option<bool>> {
-  Ok(allow_missing_if_cpu_exists(cpu_id, try_online(cpu_id))?)
+  allow_missing_if_exists(cpu_id, try_online(cpu_id), allow_missing_files)
 }
 
 pub fn try_set_online(cpu_id: u64, val: bool) -> Result<()> {
@@ -64,5 +83,25 @@ pub fn try_set_online(cpu_id: u64, val: bool) -> Result<()> {
 }
 
 pub fn set_online(cpu_id: u64, val: bool) -> Result<Option<()>> {
-  Ok(allow_missing_if_cpu_exists(cpu_id, try_set_online(cpu_id, val))?)
+  allow_missing_if_exists(cpu_id, try_set_online(cpu_id, val), allow_missing_files)
+}
+
+pub fn try_core_id(cpu_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::cpu_core_id(cpu_id))?;
+  debug!("cpu get_core_id cpu{} {}", cpu_id, res);
+  Ok(res)
+}
+
+pub fn core_id(cpu_id: u64) -> Result<Option<u64>> {
+  allow_missing_if_exists(cpu_id, try_core_id(cpu_id), allow_missing_files)
+}
+
+pub fn try_package_id(cpu_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::cpu_package_id(cpu_id))?;
+  debug!("cpu get_package_id cpu{} {}", cpu_id, res);
+  Ok(res)
+}
+
+pub fn package_id(cpu_id: u64) -> Result<Option<u64>> {
+  allow_missing_if_exists(cpu_id, try_package_id(cpu_id), allow_missing_files)
 }
@@ -1,33 +1,83 @@
 use {
   crate::{
-    cpu,
+    cpu::{self, NotFound},
     pseudofs,
     pseudofs::{Read, Write},
     sysfs,
     units::{Hertz, HertzUnit}
   },
   log::{debug, info},
+  std::{fs, path::PathBuf},
 };
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
 
+  #[error("Error parsing value `{1}` in file {0}")]
+  Parse(PathBuf, String),
+
+  #[error(transparent)] CpuxCpu(#[from] crate::cpu::Error),
   #[error(transparent)] CpuxPseudofs(#[from] crate::pseudofs::Error),
+  #[error(transparent)] StdIo(#[from] std::io::Error),
 }
 pub type Result<T> = std::result::Result<T, Error>;
 
-fn allow_missing_if_cpu_exists<T>(cpu_id: u64, result: Result<T>) -> Result<Option<T>> {
+impl cpu::NotFound for Error {
+  fn is_not_found(&self) -> bool {
+    match self {
+      Error::CpuxPseudofs(pseudofs::Error::NotFound(_, _)) => true,
+      Error::StdIo(err) => err.kind() == std::io::ErrorKind::NotFound,
+      _ => false,
+    }
+  }
+}
+
+fn allow_missing_if_policy_exists<T>(policy_id: u64, result: Result<T>) -> Result<Option<T>> {
+  if let Err(err) = &result {
+    if err.is_not_found() && ! sysfs::cpufreq_policy(policy_id).is_dir() { return result.map(Some); }
+  }
+  allow_missing_files(result)
+}
+
+// `cpuinfo_cur_freq` is readable by root only, treat it as missing otherwise.
+fn allow_unreadable_if_policy_exists<T>(policy_id: u64, result: Result<T>) -> Result<Option<T>> {
   match result {
-    Ok(val) => Ok(Some(val)),
-    Err(Error::CpuxPseudofs(err)) => {
-      if let pseudofs::Error::NotFound(_, _) = err {
-        if ! cpu::exists(cpu_id) { return Err(Error::CpuxPseudofs(err)); }
-      }
-      Ok(pseudofs::allow_missing_files(Err(err))? )
+    Err(Error::CpuxPseudofs(pseudofs::Error::NoPermission(path, err))) => {
+      debug!("cpufreq NoPermission {} {}", path.display(), err);
+      Ok(None)
     },
+    result => allow_missing_if_policy_exists(policy_id, result),
+  }
+}
+
+fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Ok(val) => Ok(Some(val)),
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
+    Err(Error::StdIo(err)) =>
+      match err.kind() {
+        std::io::ErrorKind::NotFound => Ok(None),
+        _ => Err(Error::StdIo(err)),
+      },
+    Err(err) => Err(err),
   }
 }
 
+// A list of cpu ids like `affected_cpus`, e.g. `0 1 2 3`.
+fn try_read_cpus(path: PathBuf) -> Result<Vec<u64>> {
+  let val = String::read(&path)?;
+  let mut res = vec![];
+  for id in val.split_whitespace() {
+    res.push(id.parse::<u64>().map_err(|_| Error::Parse(path.clone(), val.clone()))?);
+  }
+  Ok(res)
+}
+
+fn try_read_khz(path: PathBuf) -> Result<Hertz> {
+  let khz = u64::read(&path)?;
+  Ok(Hertz::from_khz(khz as f64))
+}
+
 pub fn available() -> bool {
   sysfs::cpu_cpufreq().is_dir()
 }
@@ -39,7 +89,7 @@ pub fn try_governor(cpu_id: u64) -> Result<String> {
 }
 
 pub fn governor(cpu_id: u64) -> Result<Option<String>> {
-  allow_missing_if_cpu_exists(cpu_id, try_governor(cpu_id))
+  cpu::allow_missing_if_exists(cpu_id, try_governor(cpu_id), allow_missing_files)
 }
 
 pub fn try_set_governor(cpu_id: u64, val: &str) -> Result<()> {
@@ -49,7 +99,7 @@ pub fn try_set_governor(cpu_id: u64, val: &str) -> Result<()> {
 }
 
 pub fn set_governor(cpu_id: u64, val: &str) -> Result<Option<()>> {
-  allow_missing_if_cpu_exists(cpu_id, try_set_governor(cpu_id, val))
+  cpu::allow_missing_if_exists(cpu_id, try_set_governor(cpu_id, val), allow_missing_files)
 }
 
 pub fn try_governors(cpu_id: u64) -> Result<Vec<String>> {
@@ -59,7 +109,7 @@ pub fn try_governors(cpu_id: u64) -> Result<Vec<String>> {
 }
 
 pub fn governors(cpu_id: u64) -> Result<Option<Vec<String>>> {
-  allow_missing_if_cpu_exists(cpu_id, try_governors(cpu_id))
+  cpu::allow_missing_if_exists(cpu_id, try_governors(cpu_id), allow_missing_files)
 }
 
 pub fn try_cur(cpu_id: u64) -> Result<Hertz> {
@@ -69,7 +119,7 @@ pub fn try_cur(cpu_id: u64) -> Result<Hertz> {
 }
 
 pub fn cur(cpu_id: u64) -> Result<Option<Hertz>> {
-  allow_missing_if_cpu_exists(cpu_id, try_cur(cpu_id))
+  cpu::allow_missing_if_exists(cpu_id, try_cur(cpu_id), allow_missing_files)
 }
 
 pub fn try_max(cpu_id: u64) -> Result<Hertz> {
@@ -79,7 +129,7 @@ pub fn try_max(cpu_id: u64) -> Result<Hertz> {
 }
 
 pub fn max(cpu_id: u64) -> Result<Option<Hertz>> {
-  allow_missing_if_cpu_exists(cpu_id, try_max(cpu_id))
+  cpu::allow_missing_if_exists(cpu_id, try_max(cpu_id), allow_missing_files)
 }
 
 pub fn try_max_limit(cpu_id: u64) -> Result<Hertz> {
@@ -89,7 +139,7 @@ pub fn try_max_limit(cpu_id: u64) -> Result<Hertz> {
 }
 
 pub fn max_limit(cpu_id: u64) -> Result<Option<Hertz>> {
-  allow_missing_if_cpu_exists(cpu_id, try_max_limit(cpu_id))
+  cpu::allow_missing_if_exists(cpu_id, try_max_limit(cpu_id), allow_missing_files)
 }
 
 pub fn try_set_max<H: AsRef<Hertz>>(cpu_id: u64, val: H) -> Result<()> {
@@ -100,7 +150,7 @@ pub fn try_set_max<H: AsRef<Hertz>>(cpu_id: u64, val: H) -> Result<()> {
 }
 
 pub fn set_max<H: AsRef<Hertz>>(cpu_id: u64, val: H) -> Result<Option<()>> {
-  allow_missing_if_cpu_exists(cpu_id, try_set_max(cpu_id, val))
+  cpu::allow_missing_if_exists(cpu_id, try_set_max(cpu_id, val), allow_missing_files)
 }
 
 pub fn try_min(cpu_id: u64) -> Result<Hertz> {
@@ -110,7 +160,7 @@ pub fn try_min(cpu_id: u64) -> Result<Hertz> {
 }
 
 pub fn min(cpu_id: u64) -> Result<Option<Hertz>> {
-  allow_missing_if_cpu_exists(cpu_id, try_min(cpu_id))
+  cpu::allow_missing_if_exists(cpu_id, try_min(cpu_id), allow_missing_files)
 }
 
 pub fn try_min_limit(cpu_id: u64) -> Result<Hertz> {
@@ -120,7 +170,7 @@ pub fn try_min_limit(cpu_id: u64) -> Result<Hertz> {
 }
 
 pub fn min_limit(cpu_id: u64) -> Result<Option<Hertz>> {
-  allow_missing_if_cpu_exists(cpu_id, try_min_limit(cpu_id))
+  cpu::allow_missing_if_exists(cpu_id, try_min_limit(cpu_id), allow_missing_files)
 }
 
 pub fn try_set_min<H: AsRef<Hertz>>(cpu_id: u64, val: H) -> Result<()> {
@@ -131,5 +181,278 @@ pub fn try_set_min<H: AsRef<Hertz>>(cpu_id: u64, val: H) -> Result<()> {
 }
 
 pub fn set_min<H: AsRef<Hertz>>(cpu_id: u64, val: H) -> Result<Option<()>> {
-  allow_missing_if_cpu_exists(cpu_id, try_set_min(cpu_id, val))
+  cpu::allow_missing_if_exists(cpu_id, try_set_min(cpu_id, val), allow_missing_files)
+}
+
+// Global `cpufreq/boost`, e.g. acpi-cpufreq and amd-pstate in passive mode.
+pub fn try_boost() -> Result<bool> {
+  let res = bool::read(&sysfs::cpufreq_boost())?;
+  debug!("cpufreq get_boost {}", res);
+  Ok(res)
+}
+
+pub fn boost() -> Result<Option<bool>> {
+  allow_missing_files(try_boost())
+}
+
+pub fn try_set_boost(val: bool) -> Result<()> {
+  info!("cpufreq set_boost {}", val);
+  val.write(&sysfs::cpufreq_boost())?;
+  Ok(())
+}
+
+pub fn set_boost(val: bool) -> Result<Option<()>> {
+  allow_missing_files(try_set_boost(val))
+}
+
+pub fn try_policies() -> Result<Vec<u64>> {
+  let mut policies = vec![];
+  for ent in fs::read_dir(sysfs::cpu_cpufreq())? {
+    let ent = ent?.file_name();
+    let ent = if let Some(ent) = ent.to_str() { ent } else { continue; };
+    if let Some(id) = ent.strip_prefix("policy") {
+      if let Ok(id) = id.parse::<u64>() { policies.push(id); }
+    }
+  }
+  policies.sort_unstable();
+  debug!("cpufreq get_policies {:?}", policies);
+  Ok(policies)
+}
+
+pub fn policies() -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_policies())
+}
+
+// Id of the policy the cpu belongs to, from the `cpuN/cpufreq` link to `policyN`. The link
+// is kept while the cpu is offline.
+pub fn try_policy(cpu_id: u64) -> Result<u64> {
+  let path = sysfs::cpufreq(cpu_id);
+  let link = fs::read_link(&path)?;
+  let res = link
+    .file_name()
+    .and_then(|n| n.to_str())
+    .and_then(|n| n.strip_prefix("policy"))
+    .and_then(|n| n.parse::<u64>().ok())
+    .ok_or_else(|| Error::Parse(path, link.display().to_string()))?;
+  debug!("cpufreq get_policy cpu{} {}", cpu_id, res);
+  Ok(res)
+}
+
+pub fn policy(cpu_id: u64) -> Result<Option<u64>> {
+  cpu::allow_missing_if_exists(cpu_id, try_policy(cpu_id), allow_missing_files)
+}
+
+// Online cpus of the policy.
+pub fn try_affected_cpus(policy_id: u64) -> Result<Vec<u64>> {
+  let res = try_read_cpus(sysfs::cpufreq_policy_affected_cpus(policy_id))?;
+  debug!("cpufreq get_affected_cpus policy{} {:?}", policy_id, res);
+  Ok(res)
+}
+
+pub fn affected_cpus(policy_id: u64) -> Result<Option<Vec<u64>>> {
+  allow_missing_if_policy_exists(policy_id, try_affected_cpus(policy_id))
+}
+
+// All cpus of the policy, online or offline.
+pub fn try_related_cpus(policy_id: u64) -> Result<Vec<u64>> {
+  let res = try_read_cpus(sysfs::cpufreq_policy_related_cpus(policy_id))?;
+  debug!("cpufreq get_related_cpus policy{} {:?}", policy_id, res);
+  Ok(res)
+}
+
+pub fn related_cpus(policy_id: u64) -> Result<Option<Vec<u64>>> {
+  allow_missing_if_policy_exists(policy_id, try_related_cpus(policy_id))
+}
+
+// Whether any cpu of the policy is online, from `affected_cpus`, the online cpus of the
+// policy. The kernel refuses to read it with EBUSY, as every attribute of an inactive policy.
+pub fn try_policy_active(policy_id: u64) -> Result<bool> {
+  match try_affected_cpus(policy_id) {
+    Ok(cpus) => Ok(! cpus.is_empty()),
+    Err(Error::CpuxPseudofs(pseudofs::Error::NotFound(_, err) | pseudofs::Error::Io(_, err)))
+      if err.raw_os_error() == Some(16) => Ok(false),
+    Err(err) => Err(err),
+  }
+}
+
+pub fn policy_active(policy_id: u64) -> Result<Option<bool>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_active(policy_id))
+}
+
+pub fn try_policy_driver(policy_id: u64) -> Result<String> {
+  let res = String::read(&sysfs::cpufreq_policy_driver(policy_id))?;
+  debug!(r#"cpufreq get_scaling_driver policy{} "{}""#, policy_id, res);
+  Ok(res)
+}
+
+pub fn policy_driver(policy_id: u64) -> Result<Option<String>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_driver(policy_id))
+}
+
+pub fn try_policy_governor(policy_id: u64) -> Result<String> {
+  let res = String::read(&sysfs::cpufreq_policy_governor(policy_id))?;
+  debug!(r#"cpufreq get_governor policy{} "{}""#, policy_id, res);
+  Ok(res)
+}
+
+pub fn policy_governor(policy_id: u64) -> Result<Option<String>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_governor(policy_id))
+}
+
+pub fn try_set_policy_governor(policy_id: u64, val: &str) -> Result<()> {
+  info!(r#"cpufreq set_governor policy{} "{}""#, policy_id, val);
+  val.write(&sysfs::cpufreq_policy_governor(policy_id))?;
+  Ok(())
+}
+
+pub fn set_policy_governor(policy_id: u64, val: &str) -> Result<Option<()>> {
+  allow_missing_if_policy_exists(policy_id, try_set_policy_governor(policy_id, val))
+}
+
+pub fn try_policy_governors(policy_id: u64) -> Result<Vec<String>> {
+  let res = Vec::read(&sysfs::cpufreq_policy_governors(policy_id))?;
+  debug!(r#"cpufreq get_governors policy{} "{}""#, policy_id, res.join(","));
+  Ok(res)
+}
+
+pub fn policy_governors(policy_id: u64) -> Result<Option<Vec<String>>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_governors(policy_id))
+}
+
+pub fn try_policy_cur(policy_id: u64) -> Result<Hertz> {
+  let res = try_read_khz(sysfs::cpufreq_policy_cur_khz(policy_id))?;
+  debug!("cpufreq get_cur_khz policy{} {}", policy_id, res.khz());
+  Ok(res)
+}
+
+pub fn policy_cur(policy_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_cur(policy_id))
+}
+
+pub fn try_policy_max(policy_id: u64) -> Result<Hertz> {
+  let res = try_read_khz(sysfs::cpufreq_policy_max_khz(policy_id))?;
+  debug!("cpufreq get_max_khz policy{} {}", policy_id, res.khz());
+  Ok(res)
+}
+
+pub fn policy_max(policy_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_max(policy_id))
+}
+
+pub fn try_set_policy_max<H: AsRef<Hertz>>(policy_id: u64, val: H) -> Result<()> {
+  let khz = val.as_ref().khz() as u64;
+  info!("cpufreq set_max_khz policy{} {}", policy_id, khz);
+  khz.write(&sysfs::cpufreq_policy_max_khz(policy_id))?;
+  Ok(())
+}
+
+pub fn set_policy_max<H: AsRef<Hertz>>(policy_id: u64, val: H) -> Result<Option<()>> {
+  allow_missing_if_policy_exists(policy_id, try_set_policy_max(policy_id, val))
+}
+
+pub fn try_policy_max_limit(policy_id: u64) -> Result<Hertz> {
+  let res = try_read_khz(sysfs::cpufreq_policy_max_khz_limit(policy_id))?;
+  debug!("cpufreq get_max_khz_limit policy{} {}", policy_id, res.khz());
+  Ok(res)
+}
+
+pub fn policy_max_limit(policy_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_max_limit(policy_id))
+}
+
+pub fn try_policy_min(policy_id: u64) -> Result<Hertz> {
+  let res = try_read_khz(sysfs::cpufreq_policy_min_khz(policy_id))?;
+  debug!("cpufreq get_min_khz policy{} {}", policy_id, res.khz());
+  Ok(res)
+}
+
+pub fn policy_min(policy_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_min(policy_id))
+}
+
+pub fn try_set_policy_min<H: AsRef<Hertz>>(policy_id: u64, val: H) -> Result<()> {
+  let khz = val.as_ref().khz() as u64;
+  info!("cpufreq set_min_khz policy{} {}", policy_id, khz);
+  khz.write(&sysfs::cpufreq_policy_min_khz(policy_id))?;
+  Ok(())
+}
+
+pub fn set_policy_min<H: AsRef<Hertz>>(policy_id: u64, val: H) -> Result<Option<()>> {
+  allow_missing_if_policy_exists(policy_id, try_set_policy_min(policy_id, val))
+}
+
+pub fn try_policy_min_limit(policy_id: u64) -> Result<Hertz> {
+  let res = try_read_khz(sysfs::cpufreq_policy_min_khz_limit(policy_id))?;
+  debug!("cpufreq get_min_khz_limit policy{} {}", policy_id, res.khz());
+  Ok(res)
+}
+
+pub fn policy_min_limit(policy_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_min_limit(policy_id))
+}
+
+// Guaranteed frequency, reported by intel_pstate and amd-pstate.
+pub fn try_policy_base(policy_id: u64) -> Result<Hertz> {
+  let res = try_read_khz(sysfs::cpufreq_policy_base_khz(policy_id))?;
+  debug!("cpufreq get_base_frequency policy{} {}", policy_id, res.khz());
+  Ok(res)
+}
+
+pub fn policy_base(policy_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_base(policy_id))
+}
+
+// Frequency limit imposed by the platform firmware through ACPI _PPC, acpi-cpufreq only.
+pub fn try_policy_bios_limit(policy_id: u64) -> Result<Hertz> {
+  let res = try_read_khz(sysfs::cpufreq_policy_bios_limit_khz(policy_id))?;
+  debug!("cpufreq get_bios_limit policy{} {}", policy_id, res.khz());
+  Ok(res)
+}
+
+pub fn policy_bios_limit(policy_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_bios_limit(policy_id))
+}
+
+// Current frequency as read from the hardware, where `scaling_cur_freq` may be the last
+// requested one.
+pub fn try_policy_cpuinfo_cur(policy_id: u64) -> Result<Hertz> {
+  let res = try_read_khz(sysfs::cpufreq_policy_cpuinfo_cur_khz(policy_id))?;
+  debug!("cpufreq get_cpuinfo_cur_freq policy{} {}", policy_id, res.khz());
+  Ok(res)
+}
+
+pub fn policy_cpuinfo_cur(policy_id: u64) -> Result<Option<Hertz>> {
+  allow_unreadable_if_policy_exists(policy_id, try_policy_cpuinfo_cur(policy_id))
+}
+
+// Frequency transition latency in ns. Drivers which do not know it report 4294967295.
+pub fn try_policy_transition_latency(policy_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::cpufreq_policy_transition_latency(policy_id))?;
+  debug!("cpufreq get_cpuinfo_transition_latency policy{} {}", policy_id, res);
+  Ok(res)
+}
+
+pub fn policy_transition_latency(policy_id: u64) -> Result<Option<u64>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_transition_latency(policy_id))
+}
+
+// Per-policy `boost`, e.g. amd-pstate in active mode.
+pub fn try_policy_boost(policy_id: u64) -> Result<bool> {
+  let res = bool::read(&sysfs::cpufreq_policy_boost(policy_id))?;
+  debug!("cpufreq get_boost policy{} {}", policy_id, res);
+  Ok(res)
+}
+
+pub fn policy_boost(policy_id: u64) -> Result<Option<bool>> {
+  allow_missing_if_policy_exists(policy_id, try_policy_boost(policy_id))
+}
+
+pub fn try_set_policy_boost(policy_id: u64, val: bool) -> Result<()> {
+  info!("cpufreq set_boost policy{} {}", policy_id, val);
+  val.write(&sysfs::cpufreq_policy_boost(policy_id))?;
+  Ok(())
+}
+
+pub fn set_policy_boost(policy_id: u64, val: bool) -> Result<Option<()>> {
+  allow_missing_if_policy_exists(policy_id, try_set_policy_boost(policy_id, val))
 }
@@ -0,0 +1,190 @@
+use {
+  crate::{
+    cpu,
+    pseudofs,
+    pseudofs::{Read, Write},
+    sysfs,
+  },
+  log::{debug, info},
+  std::fs,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+
+  #[error(transparent)] CpuxPseudofs(#[from] crate::pseudofs::Error),
+  #[error(transparent)] StdIo(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl cpu::NotFound for Error {
+  fn is_not_found(&self) -> bool {
+    match self {
+      Error::CpuxPseudofs(pseudofs::Error::NotFound(_, _)) => true,
+      Error::StdIo(err) => err.kind() == std::io::ErrorKind::NotFound,
+      _ => false,
+    }
+  }
+}
+
+fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Ok(ok) => Ok(Some(ok)),
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
+    Err(Error::StdIo(err)) =>
+      match err.kind() {
+        std::io::ErrorKind::NotFound => Ok(None),
+        _ => Err(Error::StdIo(err)),
+      },
+  }
+}
+
+pub fn available() -> bool {
+  sysfs::cpu_cpuidle().is_dir()
+}
+
+pub fn try_driver() -> Result<String> {
+  let res = String::read(&sysfs::cpuidle_current_driver())?;
+  debug!(r#"cpuidle get_current_driver "{}""#, res);
+  Ok(res)
+}
+
+pub fn driver() -> Result<Option<String>> {
+  allow_missing_files(try_driver())
+}
+
+// `current_governor` is only present when the kernel allows switching governors at runtime,
+// `current_governor_ro` otherwise.
+pub fn try_governor() -> Result<String> {
+  let path = sysfs::cpuidle_current_governor();
+  let path = if path.is_file() { path } else { sysfs::cpuidle_current_governor_ro() };
+  let res = String::read(&path)?;
+  debug!(r#"cpuidle get_current_governor "{}""#, res);
+  Ok(res)
+}
+
+pub fn governor() -> Result<Option<String>> {
+  allow_missing_files(try_governor())
+}
+
+pub fn try_set_governor(val: &str) -> Result<()> {
+  info!(r#"cpuidle set_current_governor "{}""#, val);
+  val.write(&sysfs::cpuidle_current_governor())?;
+  Ok(())
+}
+
+pub fn set_governor(val: &str) -> Result<Option<()>> {
+  allow_missing_files(try_set_governor(val))
+}
+
+pub fn try_governors() -> Result<Vec<String>> {
+  let res = Vec::<String>::read(&sysfs::cpuidle_available_governors())?;
+  debug!(r#"cpuidle get_available_governors "{}""#, res.join(","));
+  Ok(res)
+}
+
+pub fn governors() -> Result<Option<Vec<String>>> {
+  allow_missing_files(try_governors())
+}
+
+pub fn try_states(cpu_id: u64) -> Result<Vec<u64>> {
+  let mut states = vec![];
+  for ent in fs::read_dir(sysfs::cpuidle(cpu_id))? {
+    let ent = ent?.file_name();
+    let ent = if let Some(ent) = ent.to_str() { ent } else { continue; };
+    if let Some(id) = ent.strip_prefix("state") {
+      if let Ok(id) = id.parse::<u64>() { states.push(id); }
+    }
+  }
+  states.sort_unstable();
+  debug!("cpuidle get_states cpu{} {:?}", cpu_id, states);
+  Ok(states)
+}
+
+pub fn states(cpu_id: u64) -> Result<Option<Vec<u64>>> {
+  cpu::allow_missing_if_exists(cpu_id, try_states(cpu_id), allow_missing_files)
+}
+
+pub fn try_name(cpu_id: u64, state_id: u64) -> Result<String> {
+  let res = String::read(&sysfs::cpuidle_state_name(cpu_id, state_id))?;
+  debug!(r#"cpuidle get_name cpu{} state{} "{}""#, cpu_id, state_id, res);
+  Ok(res)
+}
+
+pub fn name(cpu_id: u64, state_id: u64) -> Result<Option<String>> {
+  cpu::allow_missing_if_exists(cpu_id, try_name(cpu_id, state_id), allow_missing_files)
+}
+
+pub fn try_desc(cpu_id: u64, state_id: u64) -> Result<String> {
+  let res = String::read(&sysfs::cpuidle_state_desc(cpu_id, state_id))?;
+  debug!(r#"cpuidle get_desc cpu{} state{} "{}""#, cpu_id, state_id, res);
+  Ok(res)
+}
+
+pub fn desc(cpu_id: u64, state_id: u64) -> Result<Option<String>> {
+  cpu::allow_missing_if_exists(cpu_id, try_desc(cpu_id, state_id), allow_missing_files)
+}
+
+// Exit latency in µs.
+pub fn try_latency(cpu_id: u64, state_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::cpuidle_state_latency(cpu_id, state_id))?;
+  debug!("cpuidle get_latency cpu{} state{} {}", cpu_id, state_id, res);
+  Ok(res)
+}
+
+pub fn latency(cpu_id: u64, state_id: u64) -> Result<Option<u64>> {
+  cpu::allow_missing_if_exists(cpu_id, try_latency(cpu_id, state_id), allow_missing_files)
+}
+
+// Target residency in µs.
+pub fn try_residency(cpu_id: u64, state_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::cpuidle_state_residency(cpu_id, state_id))?;
+  debug!("cpuidle get_residency cpu{} state{} {}", cpu_id, state_id, res);
+  Ok(res)
+}
+
+pub fn residency(cpu_id: u64, state_id: u64) -> Result<Option<u64>> {
+  cpu::allow_missing_if_exists(cpu_id, try_residency(cpu_id, state_id), allow_missing_files)
+}
+
+pub fn try_usage(cpu_id: u64, state_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::cpuidle_state_usage(cpu_id, state_id))?;
+  debug!("cpuidle get_usage cpu{} state{} {}", cpu_id, state_id, res);
+  Ok(res)
+}
+
+pub fn usage(cpu_id: u64, state_id: u64) -> Result<Option<u64>> {
+  cpu::allow_missing_if_exists(cpu_id, try_usage(cpu_id, state_id), allow_missing_files)
+}
+
+// Total time spent in the state in µs.
+pub fn try_time(cpu_id: u64, state_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::cpuidle_state_time(cpu_id, state_id))?;
+  debug!("cpuidle get_time cpu{} state{} {}", cpu_id, state_id, res);
+  Ok(res)
+}
+
+pub fn time(cpu_id: u64, state_id: u64) -> Result<Option<u64>> {
+  cpu::allow_missing_if_exists(cpu_id, try_time(cpu_id, state_id), allow_missing_files)
+}
+
+pub fn try_disable(cpu_id: u64, state_id: u64) -> Result<bool> {
+  let res = bool::read(&sysfs::cpuidle_state_disable(cpu_id, state_id))?;
+  debug!("cpuidle get_disable cpu{} state{} {}", cpu_id, state_id, res);
+  Ok(res)
+}
+
+pub fn disable(cpu_id: u64, state_id: u64) -> Result<Option<bool>> {
+  cpu::allow_missing_if_exists(cpu_id, try_disable(cpu_id, state_id), allow_missing_files)
+}
+
+pub fn try_set_disable(cpu_id: u64, state_id: u64, val: bool) -> Result<()> {
+  info!("cpuidle set_disable cpu{} state{} {}", cpu_id, state_id, val);
+  val.write(&sysfs::cpuidle_state_disable(cpu_id, state_id))?;
+  Ok(())
+}
+
+pub fn set_disable(cpu_id: u64, state_id: u64, val: bool) -> Result<Option<()>> {
+  cpu::allow_missing_if_exists(cpu_id, try_set_disable(cpu_id, state_id, val), allow_missing_files)
+}
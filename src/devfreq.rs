@@ -0,0 +1,206 @@
+use {
+  crate::{
+    pseudofs,
+    pseudofs::{Read, Write},
+    sysfs,
+    units::Hertz,
+  },
+  log::{debug, info},
+  serde::Serialize,
+  std::{fs, path::Path},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+
+  #[error(transparent)] CpuxPseudofs(#[from] crate::pseudofs::Error),
+  #[error(transparent)] StdIo(#[from] std::io::Error),
+
+  #[error("{0}: value could not be parsed as a list of frequencies: `{1}`")]
+  ParseFrequencies(String, String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Ok(ok) => Ok(Some(ok)),
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
+    Err(Error::StdIo(err)) =>
+      match err.kind() {
+        std::io::ErrorKind::NotFound => Ok(None),
+        _ => Err(Error::StdIo(err)),
+      },
+    Err(err) => Err(err),
+  }
+}
+
+fn try_read_hz(path: &Path) -> Result<Hertz> {
+  Ok(Hertz::new(u64::read(path)?))
+}
+
+// One row of `trans_stat`: the transitions from `freq` to each frequency of the table, in
+// the order of `available_frequencies`, and the time spent at `freq`.
+#[derive(Clone, Debug, Serialize)]
+pub struct TransState {
+  pub freq: Hertz,
+  pub current: bool,
+  pub transitions: Vec<u64>,
+  pub time_ms: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TransStat {
+  pub states: Vec<TransState>,
+  pub total_transitions: Option<u64>,
+}
+
+// Rows look like `*  200000000:  0  5  3  1234`, `*` marking the current frequency, and the
+// table ends with `Total transition : 14`. Header and unrecognized lines are skipped.
+pub fn parse_trans_stat(text: &str) -> TransStat {
+  let mut stat = TransStat::default();
+  for line in text.lines() {
+    let (left, right) = if let Some(split) = line.split_once(':') { split } else { continue; };
+    let left = left.trim();
+    if left.starts_with("Total transition") {
+      stat.total_transitions = right.trim().parse::<u64>().ok();
+      continue;
+    }
+    let current = left.starts_with('*');
+    let freq = if let Ok(freq) = left.trim_start_matches('*').trim().parse::<u64>() { freq } else { continue; };
+    let mut values: Vec<u64> = if let Ok(v) = right.split_whitespace().map(|v| v.parse::<u64>()).collect() { v } else { continue; };
+    let time_ms = if let Some(time_ms) = values.pop() { time_ms } else { continue; };
+    stat.states.push(TransState { freq: Hertz::new(freq), current, transitions: values, time_ms });
+  }
+  stat
+}
+
+pub fn available() -> bool {
+  matches!(devices(), Ok(Some(devices)) if ! devices.is_empty())
+}
+
+// Device names, e.g. `ff9a0000.gpu`, `dmc`.
+pub fn try_devices() -> Result<Vec<String>> {
+  let mut devices = vec![];
+  for ent in fs::read_dir(sysfs::devfreq())? {
+    let ent = ent?.file_name();
+    if let Some(ent) = ent.to_str() { devices.push(ent.to_string()); }
+  }
+  devices.sort_unstable();
+  Ok(devices)
+}
+
+pub fn devices() -> Result<Option<Vec<String>>> {
+  allow_missing_files(try_devices())
+}
+
+pub fn try_cur(device: &str) -> Result<Hertz> {
+  let res = try_read_hz(&sysfs::devfreq_cur_freq(device))?;
+  debug!("devfreq get_cur_freq {} {}", device, res.hz());
+  Ok(res)
+}
+
+pub fn cur(device: &str) -> Result<Option<Hertz>> {
+  allow_missing_files(try_cur(device))
+}
+
+pub fn try_min(device: &str) -> Result<Hertz> {
+  let res = try_read_hz(&sysfs::devfreq_min_freq(device))?;
+  debug!("devfreq get_min_freq {} {}", device, res.hz());
+  Ok(res)
+}
+
+pub fn min(device: &str) -> Result<Option<Hertz>> {
+  allow_missing_files(try_min(device))
+}
+
+pub fn try_set_min<H: AsRef<Hertz>>(device: &str, val: H) -> Result<()> {
+  let hz = val.as_ref().hz();
+  info!("devfreq set_min_freq {} {}", device, hz);
+  hz.write(&sysfs::devfreq_min_freq(device))?;
+  Ok(())
+}
+
+pub fn set_min<H: AsRef<Hertz>>(device: &str, val: H) -> Result<Option<()>> {
+  allow_missing_files(try_set_min(device, val))
+}
+
+pub fn try_max(device: &str) -> Result<Hertz> {
+  let res = try_read_hz(&sysfs::devfreq_max_freq(device))?;
+  debug!("devfreq get_max_freq {} {}", device, res.hz());
+  Ok(res)
+}
+
+pub fn max(device: &str) -> Result<Option<Hertz>> {
+  allow_missing_files(try_max(device))
+}
+
+pub fn try_set_max<H: AsRef<Hertz>>(device: &str, val: H) -> Result<()> {
+  let hz = val.as_ref().hz();
+  info!("devfreq set_max_freq {} {}", device, hz);
+  hz.write(&sysfs::devfreq_max_freq(device))?;
+  Ok(())
+}
+
+pub fn set_max<H: AsRef<Hertz>>(device: &str, val: H) -> Result<Option<()>> {
+  allow_missing_files(try_set_max(device, val))
+}
+
+// Sorted, lowest first.
+pub fn try_available_frequencies(device: &str) -> Result<Vec<Hertz>> {
+  let path = sysfs::devfreq_available_frequencies(device);
+  let text = String::read(&path)?;
+  let mut hz: Vec<u64> = text
+    .split_whitespace()
+    .map(|v| v.parse::<u64>())
+    .collect::<std::result::Result<_, _>>()
+    .map_err(|_| Error::ParseFrequencies(path.display().to_string(), text.clone()))?;
+  hz.sort_unstable();
+  debug!("devfreq get_available_frequencies {} {:?}", device, hz);
+  Ok(hz.into_iter().map(Hertz::new).collect())
+}
+
+pub fn available_frequencies(device: &str) -> Result<Option<Vec<Hertz>>> {
+  allow_missing_files(try_available_frequencies(device))
+}
+
+pub fn try_governor(device: &str) -> Result<String> {
+  let res = String::read(&sysfs::devfreq_governor(device))?;
+  debug!(r#"devfreq get_governor {} "{}""#, device, res);
+  Ok(res)
+}
+
+pub fn governor(device: &str) -> Result<Option<String>> {
+  allow_missing_files(try_governor(device))
+}
+
+pub fn try_set_governor(device: &str, val: &str) -> Result<()> {
+  info!(r#"devfreq set_governor {} "{}""#, device, val);
+  val.write(&sysfs::devfreq_governor(device))?;
+  Ok(())
+}
+
+pub fn set_governor(device: &str, val: &str) -> Result<Option<()>> {
+  allow_missing_files(try_set_governor(device, val))
+}
+
+pub fn try_available_governors(device: &str) -> Result<Vec<String>> {
+  let res = Vec::<String>::read(&sysfs::devfreq_available_governors(device))?;
+  debug!("devfreq get_available_governors {} {:?}", device, res);
+  Ok(res)
+}
+
+pub fn available_governors(device: &str) -> Result<Option<Vec<String>>> {
+  allow_missing_files(try_available_governors(device))
+}
+
+pub fn try_trans_stat(device: &str) -> Result<TransStat> {
+  let text = String::read(&sysfs::devfreq_trans_stat(device))?;
+  let res = parse_trans_stat(&text);
+  debug!("devfreq get_trans_stat {} {} states", device, res.states.len());
+  Ok(res)
+}
+
+pub fn trans_stat(device: &str) -> Result<Option<TransStat>> {
+  allow_missing_files(try_trans_stat(device))
+}
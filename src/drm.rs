@@ -1,6 +1,11 @@
 use {
-  crate::sysfs,
-  std::fs,
+  crate::{
+    pseudofs,
+    pseudofs::Read,
+    sysfs,
+  },
+  log::debug,
+  std::{fs, path::Path},
 };
 
 #[derive(thiserror::Error, Debug)]
@@ -12,6 +17,10 @@ pub enum Error {
   #[error("Bad path")]
   BadPath,
 
+  #[error("{0}: value could not be parsed as a PCI id: `{1}`")]
+  ParsePciId(String, String),
+
+  #[error(transparent)] CpuxPseudofs(#[from] crate::pseudofs::Error),
   #[error(transparent)] StdIo(#[from] std::io::Error),
 }
 
@@ -20,6 +29,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
   match result {
     Ok(ok) => Ok(Some(ok)),
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
     Err(Error::StdIo(err)) =>
       match err.kind() {
         std::io::ErrorKind::NotFound => Ok(None),
@@ -36,13 +46,19 @@ fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
   }
 }
 
+// PCI ids are hex strings, e.g. `0x8086`.
+fn try_read_pci_id(path: &Path) -> Result<u16> {
+  let s = String::read(path)?;
+  u16::from_str_radix(s.trim_start_matches("0x"), 16).map_err(|_| Error::ParsePciId(path.display().to_string(), s))
+}
+
 pub fn try_cards() -> Result<Vec<u64>> {
   let mut cards: Vec<u64> = vec![];
   for ent in fs::read_dir(sysfs::drm())? {
     let ent = ent?.file_name();
     let ent = if let Some(ent) = ent.to_str() { ent } else { return Err(Error::PathCodec); };
-    if ent.starts_with("card") {
-      cards.push(if let Some(i) = (&ent[4..]).parse::<u64>().ok() { i } else { continue; }); // FIXME trace
+    if let Some(i) = ent.strip_prefix("card") {
+      cards.push(if let Ok(i) = i.parse::<u64>() { i } else { continue; }); // FIXME trace
     }
   }
   Ok(cards)
@@ -55,15 +71,15 @@ pub fn cards() -> Result<Option<Vec<u64>>> {
     let ent = if let Ok(ent) = ent { ent } else { continue; }; // FIXME trace
     let ent = ent.file_name();
     let ent = if let Some(ent) = ent.to_str() { ent } else { continue; }; // FIXME trace
-    if ent.starts_with("card") {
-      cards.push(if let Ok(i) = (&ent[4..]).parse::<u64>() { i } else { continue; }); // FIXME trace
+    if let Some(i) = ent.strip_prefix("card") {
+      cards.push(if let Ok(i) = i.parse::<u64>() { i } else { continue; }); // FIXME trace
     }
   }
   Ok(Some(cards))
 }
 
 pub fn try_card_driver(card_id: u64) -> Result<String> {
-  let file_name = fs::read_link(&sysfs::drm_card_driver(card_id))?;
+  let file_name = fs::read_link(sysfs::drm_card_driver(card_id))?;
   let file_name = file_name.file_name();
   let file_name = if let Some(f) = file_name { f.to_str() } else { return Err(Error::BadPath); }; // FIXME details
   if let Some(f) = file_name { Ok(f.to_string()) } else { Err(Error::PathCodec) } // FIXME trace
@@ -72,3 +88,101 @@ pub fn try_card_driver(card_id: u64) -> Result<String> {
 pub fn card_driver(card_id: u64) -> Result<Option<String>> {
   allow_missing_files(try_card_driver(card_id))
 }
+
+// The PCI slot of the card's device, e.g. `0000:03:00.0`.
+pub fn try_card_slot(card_id: u64) -> Result<String> {
+  let file_name = fs::read_link(sysfs::drm_card_device(card_id))?;
+  let file_name = if let Some(f) = file_name.file_name() { f } else { return Err(Error::BadPath); };
+  if let Some(f) = file_name.to_str() { Ok(f.to_string()) } else { Err(Error::PathCodec) }
+}
+
+pub fn card_slot(card_id: u64) -> Result<Option<String>> {
+  allow_missing_files(try_card_slot(card_id))
+}
+
+// Hwmon chips registered by the card's device, `device/hwmon/hwmon<id>`.
+pub fn try_card_hwmons(card_id: u64) -> Result<Vec<u64>> {
+  let mut chips: Vec<u64> = vec![];
+  for ent in fs::read_dir(sysfs::drm_card_hwmon(card_id))? {
+    let ent = ent?.file_name();
+    let ent = if let Some(ent) = ent.to_str() { ent } else { return Err(Error::PathCodec); };
+    if let Some(i) = ent.strip_prefix("hwmon") {
+      if let Ok(i) = i.parse::<u64>() { chips.push(i); }
+    }
+  }
+  chips.sort_unstable();
+  Ok(chips)
+}
+
+pub fn card_hwmons(card_id: u64) -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_card_hwmons(card_id))
+}
+
+pub fn try_card_vendor_id(card_id: u64) -> Result<u16> {
+  let res = try_read_pci_id(&sysfs::drm_card_vendor(card_id))?;
+  debug!("drm get_vendor card{} {:04x}", card_id, res);
+  Ok(res)
+}
+
+pub fn card_vendor_id(card_id: u64) -> Result<Option<u16>> {
+  allow_missing_files(try_card_vendor_id(card_id))
+}
+
+pub fn try_card_device_id(card_id: u64) -> Result<u16> {
+  let res = try_read_pci_id(&sysfs::drm_card_device_id(card_id))?;
+  debug!("drm get_device card{} {:04x}", card_id, res);
+  Ok(res)
+}
+
+pub fn card_device_id(card_id: u64) -> Result<Option<u16>> {
+  allow_missing_files(try_card_device_id(card_id))
+}
+
+pub fn try_card_subsystem_vendor_id(card_id: u64) -> Result<u16> {
+  let res = try_read_pci_id(&sysfs::drm_card_subsystem_vendor(card_id))?;
+  debug!("drm get_subsystem_vendor card{} {:04x}", card_id, res);
+  Ok(res)
+}
+
+pub fn card_subsystem_vendor_id(card_id: u64) -> Result<Option<u16>> {
+  allow_missing_files(try_card_subsystem_vendor_id(card_id))
+}
+
+pub fn try_card_subsystem_device_id(card_id: u64) -> Result<u16> {
+  let res = try_read_pci_id(&sysfs::drm_card_subsystem_device(card_id))?;
+  debug!("drm get_subsystem_device card{} {:04x}", card_id, res);
+  Ok(res)
+}
+
+pub fn card_subsystem_device_id(card_id: u64) -> Result<Option<u16>> {
+  allow_missing_files(try_card_subsystem_device_id(card_id))
+}
+
+// Whether the firmware used this card as the boot display.
+pub fn try_card_boot_vga(card_id: u64) -> Result<bool> {
+  let res = bool::read(&sysfs::drm_card_boot_vga(card_id))?;
+  debug!("drm get_boot_vga card{} {}", card_id, res);
+  Ok(res)
+}
+
+pub fn card_boot_vga(card_id: u64) -> Result<Option<bool>> {
+  allow_missing_files(try_card_boot_vga(card_id))
+}
+
+// The render node of the card's device, e.g. `renderD128`, None for display-only devices.
+pub fn try_card_render_node(card_id: u64) -> Result<Option<String>> {
+  let mut nodes = vec![];
+  for ent in fs::read_dir(sysfs::drm_card_device_drm(card_id))? {
+    let ent = ent?.file_name();
+    let ent = if let Some(ent) = ent.to_str() { ent } else { return Err(Error::PathCodec); };
+    if ent.starts_with("renderD") { nodes.push(ent.to_string()); }
+  }
+  nodes.sort_unstable();
+  let res = nodes.into_iter().next();
+  debug!("drm get_render_node card{} {:?}", card_id, res);
+  Ok(res)
+}
+
+pub fn card_render_node(card_id: u64) -> Result<Option<String>> {
+  Ok(allow_missing_files(try_card_render_node(card_id))?.flatten())
+}
@@ -0,0 +1,475 @@
+use {
+  crate::{
+    cpu,
+    pseudofs,
+    pseudofs::{Read, Write},
+    sysfs,
+    units::{Celsius, Rpm, Volts, Watts},
+  },
+  log::{debug, info},
+  std::{fs, path::PathBuf},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+
+  #[error(transparent)] CpuxCpu(#[from] crate::cpu::Error),
+  #[error(transparent)] CpuxPseudofs(#[from] crate::pseudofs::Error),
+  #[error(transparent)] StdIo(#[from] std::io::Error),
+
+  #[error("Error parsing pwm mode string: {0}")]
+  ParsePwmMode(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Ok(ok) => Ok(Some(ok)),
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
+    Err(Error::StdIo(err)) =>
+      match err.kind() {
+        std::io::ErrorKind::NotFound => Ok(None),
+        _ => Err(Error::StdIo(err)),
+      },
+    Err(err) => Err(err),
+  }
+}
+
+// Sensors fail reads with e.g. ENODATA, EIO or EAGAIN while they have no reading or their
+// device is runtime suspended, and some are readable by root only. Treat them as missing, so one
+// sensor does not fail the whole summary.
+fn allow_unreadable_sensors<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Err(Error::CpuxPseudofs(pseudofs::Error::Io(path, err) | pseudofs::Error::NoPermission(path, err))) => {
+      debug!("hwmon unreadable {} {}", path.display(), err);
+      Ok(None)
+    },
+    result => allow_missing_files(result),
+  }
+}
+
+// Ids of the files in the chip directory named `<prefix><id><suffix>`, e.g. `temp1_input`.
+fn try_attr_ids(chip_id: u64, prefix: &str, suffix: &str) -> Result<Vec<u64>> {
+  let mut ids = vec![];
+  for ent in fs::read_dir(sysfs::hwmon_chip(chip_id))? {
+    let ent = ent?.file_name();
+    let ent = if let Some(ent) = ent.to_str() { ent } else { continue; };
+    if let Some(id) = ent.strip_prefix(prefix).and_then(|e| e.strip_suffix(suffix)) {
+      if let Ok(id) = id.parse::<u64>() { ids.push(id); }
+    }
+  }
+  ids.sort_unstable();
+  Ok(ids)
+}
+
+pub fn available() -> bool {
+  matches!(chips(), Ok(Some(chips)) if ! chips.is_empty())
+}
+
+pub fn try_chips() -> Result<Vec<u64>> {
+  let mut chips = vec![];
+  for ent in fs::read_dir(sysfs::hwmon())? {
+    let ent = ent?.file_name();
+    let ent = if let Some(ent) = ent.to_str() { ent } else { continue; };
+    if let Some(id) = ent.strip_prefix("hwmon") {
+      if let Ok(id) = id.parse::<u64>() { chips.push(id); }
+    }
+  }
+  chips.sort_unstable();
+  Ok(chips)
+}
+
+pub fn chips() -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_chips())
+}
+
+pub fn try_name(chip_id: u64) -> Result<String> {
+  let res = String::read(&sysfs::hwmon_name(chip_id))?;
+  debug!(r#"hwmon get_name hwmon{} "{}""#, chip_id, res);
+  Ok(res)
+}
+
+pub fn name(chip_id: u64) -> Result<Option<String>> {
+  allow_missing_files(try_name(chip_id))
+}
+
+pub fn try_temps(chip_id: u64) -> Result<Vec<u64>> {
+  try_attr_ids(chip_id, "temp", "_input")
+}
+
+pub fn temps(chip_id: u64) -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_temps(chip_id))
+}
+
+pub fn try_temp(chip_id: u64, temp_id: u64) -> Result<Celsius> {
+  let res = i64::read(&sysfs::hwmon_temp_input(chip_id, temp_id))?;
+  debug!("hwmon get_temp hwmon{} temp{} {}", chip_id, temp_id, res);
+  Ok(Celsius::from_millicelsius(res))
+}
+
+pub fn temp(chip_id: u64, temp_id: u64) -> Result<Option<Celsius>> {
+  allow_unreadable_sensors(try_temp(chip_id, temp_id))
+}
+
+pub fn try_temp_label(chip_id: u64, temp_id: u64) -> Result<String> {
+  let res = String::read(&sysfs::hwmon_temp_label(chip_id, temp_id))?;
+  debug!(r#"hwmon get_temp_label hwmon{} temp{} "{}""#, chip_id, temp_id, res);
+  Ok(res)
+}
+
+pub fn temp_label(chip_id: u64, temp_id: u64) -> Result<Option<String>> {
+  allow_unreadable_sensors(try_temp_label(chip_id, temp_id))
+}
+
+pub fn try_temp_crit(chip_id: u64, temp_id: u64) -> Result<Celsius> {
+  let res = i64::read(&sysfs::hwmon_temp_crit(chip_id, temp_id))?;
+  debug!("hwmon get_temp_crit hwmon{} temp{} {}", chip_id, temp_id, res);
+  Ok(Celsius::from_millicelsius(res))
+}
+
+pub fn temp_crit(chip_id: u64, temp_id: u64) -> Result<Option<Celsius>> {
+  allow_unreadable_sensors(try_temp_crit(chip_id, temp_id))
+}
+
+pub fn try_temp_max(chip_id: u64, temp_id: u64) -> Result<Celsius> {
+  let res = i64::read(&sysfs::hwmon_temp_max(chip_id, temp_id))?;
+  debug!("hwmon get_temp_max hwmon{} temp{} {}", chip_id, temp_id, res);
+  Ok(Celsius::from_millicelsius(res))
+}
+
+pub fn temp_max(chip_id: u64, temp_id: u64) -> Result<Option<Celsius>> {
+  allow_unreadable_sensors(try_temp_max(chip_id, temp_id))
+}
+
+pub fn try_fans(chip_id: u64) -> Result<Vec<u64>> {
+  try_attr_ids(chip_id, "fan", "_input")
+}
+
+pub fn fans(chip_id: u64) -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_fans(chip_id))
+}
+
+pub fn try_fan(chip_id: u64, fan_id: u64) -> Result<Rpm> {
+  let res = u64::read(&sysfs::hwmon_fan_input(chip_id, fan_id))?;
+  debug!("hwmon get_fan hwmon{} fan{} {}", chip_id, fan_id, res);
+  Ok(Rpm::new(res))
+}
+
+pub fn fan(chip_id: u64, fan_id: u64) -> Result<Option<Rpm>> {
+  allow_unreadable_sensors(try_fan(chip_id, fan_id))
+}
+
+pub fn try_voltages(chip_id: u64) -> Result<Vec<u64>> {
+  try_attr_ids(chip_id, "in", "_input")
+}
+
+pub fn voltages(chip_id: u64) -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_voltages(chip_id))
+}
+
+pub fn try_voltage(chip_id: u64, in_id: u64) -> Result<Volts> {
+  let res = i64::read(&sysfs::hwmon_in_input(chip_id, in_id))?;
+  debug!("hwmon get_voltage hwmon{} in{} {}", chip_id, in_id, res);
+  Ok(Volts::from_millivolts(res))
+}
+
+pub fn voltage(chip_id: u64, in_id: u64) -> Result<Option<Volts>> {
+  allow_unreadable_sensors(try_voltage(chip_id, in_id))
+}
+
+// Values of `pwmN_enable`. Automatic modes above 2 are driver-specific and kept as-is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PwmMode {
+  Full,
+  Manual,
+  Auto(u64),
+}
+
+impl PwmMode {
+
+  pub fn value(&self) -> u64 {
+    match self {
+      Self::Full => 0,
+      Self::Manual => 1,
+      Self::Auto(val) => *val,
+    }
+  }
+}
+
+impl From<u64> for PwmMode {
+  fn from(val: u64) -> Self {
+    match val {
+      0 => Self::Full,
+      1 => Self::Manual,
+      _ => Self::Auto(val),
+    }
+  }
+}
+
+impl std::str::FromStr for PwmMode {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match &s.to_lowercase()[..] {
+      "full" => Ok(Self::Full),
+      "manual" => Ok(Self::Manual),
+      "auto" => Ok(Self::Auto(2)),
+      _ => Err(Error::ParsePwmMode(s.to_string())),
+    }
+  }
+}
+
+impl std::fmt::Display for PwmMode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Full => write!(f, "full"),
+      Self::Manual => write!(f, "manual"),
+      Self::Auto(2) => write!(f, "auto"),
+      Self::Auto(val) => write!(f, "auto({})", val),
+    }
+  }
+}
+
+impl From<PwmMode> for String { fn from(mode: PwmMode) -> Self { mode.to_string() } }
+
+impl serde::Serialize for PwmMode {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.to_string())
+  }
+}
+
+// amdgpu exposes the power cap as `power1_cap`, i915 and xe as `power1_max`.
+fn power_cap_path(chip_id: u64) -> PathBuf {
+  let path = sysfs::hwmon_power_cap(chip_id);
+  if path.exists() { path } else { sysfs::hwmon_power_max(chip_id) }
+}
+
+// Older amdgpu parts report `power1_average`, newer ones and most other drivers `power1_input`.
+fn power_average_path(chip_id: u64) -> PathBuf {
+  let path = sysfs::hwmon_power_average(chip_id);
+  if path.exists() { path } else { sysfs::hwmon_power_input(chip_id) }
+}
+
+pub fn try_power_cap(chip_id: u64) -> Result<Watts> {
+  let res = u64::read(&power_cap_path(chip_id))?;
+  debug!("hwmon get_power_cap hwmon{} {}", chip_id, res);
+  Ok(Watts::from_microwatts(res))
+}
+
+pub fn power_cap(chip_id: u64) -> Result<Option<Watts>> {
+  allow_unreadable_sensors(try_power_cap(chip_id))
+}
+
+pub fn try_set_power_cap<W: AsRef<Watts>>(chip_id: u64, val: W) -> Result<()> {
+  let uw = val.as_ref().microwatts();
+  info!("hwmon set_power_cap hwmon{} {}", chip_id, uw);
+  uw.write(&power_cap_path(chip_id))?;
+  Ok(())
+}
+
+pub fn set_power_cap<W: AsRef<Watts>>(chip_id: u64, val: W) -> Result<Option<()>> {
+  allow_missing_files(try_set_power_cap(chip_id, val))
+}
+
+pub fn try_power_cap_min(chip_id: u64) -> Result<Watts> {
+  let res = u64::read(&sysfs::hwmon_power_cap_min(chip_id))?;
+  debug!("hwmon get_power_cap_min hwmon{} {}", chip_id, res);
+  Ok(Watts::from_microwatts(res))
+}
+
+pub fn power_cap_min(chip_id: u64) -> Result<Option<Watts>> {
+  allow_unreadable_sensors(try_power_cap_min(chip_id))
+}
+
+pub fn try_power_cap_max(chip_id: u64) -> Result<Watts> {
+  let res = u64::read(&sysfs::hwmon_power_cap_max(chip_id))?;
+  debug!("hwmon get_power_cap_max hwmon{} {}", chip_id, res);
+  Ok(Watts::from_microwatts(res))
+}
+
+pub fn power_cap_max(chip_id: u64) -> Result<Option<Watts>> {
+  allow_unreadable_sensors(try_power_cap_max(chip_id))
+}
+
+pub fn try_power_average(chip_id: u64) -> Result<Watts> {
+  let res = u64::read(&power_average_path(chip_id))?;
+  debug!("hwmon get_power_average hwmon{} {}", chip_id, res);
+  Ok(Watts::from_microwatts(res))
+}
+
+pub fn power_average(chip_id: u64) -> Result<Option<Watts>> {
+  allow_unreadable_sensors(try_power_average(chip_id))
+}
+
+pub fn try_pwms(chip_id: u64) -> Result<Vec<u64>> {
+  try_attr_ids(chip_id, "pwm", "")
+}
+
+pub fn pwms(chip_id: u64) -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_pwms(chip_id))
+}
+
+pub fn try_pwm(chip_id: u64, pwm_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::hwmon_pwm(chip_id, pwm_id))?;
+  debug!("hwmon get_pwm hwmon{} pwm{} {}", chip_id, pwm_id, res);
+  Ok(res)
+}
+
+pub fn pwm(chip_id: u64, pwm_id: u64) -> Result<Option<u64>> {
+  allow_unreadable_sensors(try_pwm(chip_id, pwm_id))
+}
+
+pub fn try_set_pwm(chip_id: u64, pwm_id: u64, val: u64) -> Result<()> {
+  info!("hwmon set_pwm hwmon{} pwm{} {}", chip_id, pwm_id, val);
+  val.write(&sysfs::hwmon_pwm(chip_id, pwm_id))?;
+  Ok(())
+}
+
+pub fn set_pwm(chip_id: u64, pwm_id: u64, val: u64) -> Result<Option<()>> {
+  allow_missing_files(try_set_pwm(chip_id, pwm_id, val))
+}
+
+pub fn try_pwm_mode(chip_id: u64, pwm_id: u64) -> Result<PwmMode> {
+  let res = u64::read(&sysfs::hwmon_pwm_enable(chip_id, pwm_id))?;
+  debug!("hwmon get_pwm_mode hwmon{} pwm{} {}", chip_id, pwm_id, res);
+  Ok(PwmMode::from(res))
+}
+
+pub fn pwm_mode(chip_id: u64, pwm_id: u64) -> Result<Option<PwmMode>> {
+  allow_unreadable_sensors(try_pwm_mode(chip_id, pwm_id))
+}
+
+pub fn try_set_pwm_mode(chip_id: u64, pwm_id: u64, val: PwmMode) -> Result<()> {
+  info!("hwmon set_pwm_mode hwmon{} pwm{} {}", chip_id, pwm_id, val.value());
+  val.value().write(&sysfs::hwmon_pwm_enable(chip_id, pwm_id))?;
+  Ok(())
+}
+
+pub fn set_pwm_mode(chip_id: u64, pwm_id: u64, val: PwmMode) -> Result<Option<()>> {
+  allow_missing_files(try_set_pwm_mode(chip_id, pwm_id, val))
+}
+
+// Mode and value of one pwm channel, saved so they can be put back later.
+#[derive(Clone, Debug)]
+pub struct PwmState {
+  pub chip_id: u64,
+  pub pwm_id: u64,
+  pub mode: PwmMode,
+  pub value: Option<u64>,
+}
+
+impl PwmState {
+
+  pub fn save(chip_id: u64, pwm_id: u64) -> Result<Self> {
+    Ok(Self {
+      chip_id,
+      pwm_id,
+      mode: try_pwm_mode(chip_id, pwm_id)?,
+      value: pwm(chip_id, pwm_id)?,
+    })
+  }
+
+  pub fn restore(&self) -> Result<()> {
+    info!("hwmon restore_pwm hwmon{} pwm{} {}", self.chip_id, self.pwm_id, self.mode);
+    try_set_pwm_mode(self.chip_id, self.pwm_id, self.mode)?;
+    if let (PwmMode::Manual, Some(value)) = (self.mode, self.value) {
+      try_set_pwm(self.chip_id, self.pwm_id, value)?;
+    }
+    Ok(())
+  }
+}
+
+// Chips with the given driver name, in hwmon order.
+pub fn chips_named(name: &str) -> Result<Vec<u64>> {
+  let mut res = vec![];
+  for chip_id in chips()?.unwrap_or_default() {
+    if self::name(chip_id)?.as_deref() == Some(name) { res.push(chip_id); }
+  }
+  Ok(res)
+}
+
+// Temperature sensors of a chip with their labels.
+#[derive(Clone, Debug)]
+struct LabeledTemps {
+  chip_id: u64,
+  temps: Vec<(u64, String)>,
+}
+
+impl LabeledTemps {
+
+  fn read(chip_id: u64) -> Result<Self> {
+    let mut temps = vec![];
+    for temp_id in self::temps(chip_id)?.unwrap_or_default() {
+      if let Some(label) = temp_label(chip_id, temp_id)? { temps.push((temp_id, label)); }
+    }
+    Ok(Self { chip_id, temps })
+  }
+
+  fn has(&self, label: &str) -> bool {
+    self.temps.iter().any(|(_, l)| l == label)
+  }
+
+  fn temp(&self, label: &str) -> Result<Option<Celsius>> {
+    match self.temps.iter().find(|(_, l)| l == label) {
+      Some((temp_id, _)) => temp(self.chip_id, *temp_id),
+      None => Ok(None),
+    }
+  }
+}
+
+// The coretemp and k10temp sensors of the cpus, found once for reading the temperatures of
+// every cpu.
+#[derive(Clone, Debug)]
+pub struct CpuSensors {
+  coretemp: Vec<LabeledTemps>,
+  k10temp: Vec<LabeledTemps>,
+}
+
+impl CpuSensors {
+
+  pub fn read() -> Result<Self> {
+    let mut coretemp = vec![];
+    for chip_id in chips_named("coretemp")? { coretemp.push(LabeledTemps::read(chip_id)?); }
+    let mut k10temp = vec![];
+    for chip_id in chips_named("k10temp")? { k10temp.push(LabeledTemps::read(chip_id)?); }
+    Ok(Self { coretemp, k10temp })
+  }
+
+  // The coretemp chip which reports `Package id <package_id>`, one chip per package.
+  fn coretemp_chip(&self, package_id: u64) -> Option<&LabeledTemps> {
+    let label = format!("Package id {}", package_id);
+    self.coretemp.iter().find(|c| c.has(&label))
+  }
+
+  pub fn package_temp(&self, cpu_id: u64) -> Result<Option<Celsius>> {
+    let package_id = cpu::package_id(cpu_id)?.unwrap_or(0);
+    if let Some(chip) = self.coretemp_chip(package_id) {
+      return chip.temp(&format!("Package id {}", package_id));
+    }
+    // k10temp has one chip per package, and reports Tdie on older parts which offset Tctl.
+    if let Some(chip) = self.k10temp.get(package_id as usize) {
+      for label in &["Tdie", "Tctl"] {
+        if let Some(res) = chip.temp(label)? { return Ok(Some(res)); }
+      }
+    }
+    Ok(None)
+  }
+
+  pub fn core_temp(&self, cpu_id: u64) -> Result<Option<Celsius>> {
+    let package_id = cpu::package_id(cpu_id)?.unwrap_or(0);
+    let core_id = if let Some(core_id) = cpu::core_id(cpu_id)? { core_id } else { return Ok(None); };
+    if let Some(chip) = self.coretemp_chip(package_id) {
+      return chip.temp(&format!("Core {}", core_id));
+    }
+    Ok(None)
+  }
+}
+
+pub fn package_temp(cpu_id: u64) -> Result<Option<Celsius>> {
+  CpuSensors::read()?.package_temp(cpu_id)
+}
+
+pub fn core_temp(cpu_id: u64) -> Result<Option<Celsius>> {
+  CpuSensors::read()?.core_temp(cpu_id)
+}
@@ -7,6 +7,7 @@ use {
     units::{Hertz, HertzUnit},
   },
   log::{debug, info},
+  std::fs,
 };
 
 #[derive(thiserror::Error, Debug)]
@@ -14,6 +15,7 @@ pub enum Error {
 
   #[error(transparent)] CpuxDrm(#[from] crate::drm::Error),
   #[error(transparent)] CpuxPseudofs(#[from] crate::pseudofs::Error),
+  #[error(transparent)] StdIo(#[from] std::io::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -22,10 +24,18 @@ fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
   match result {
     Ok(ok) => Ok(Some(ok)),
     Err(Error::CpuxPseudofs(err)) =>  Ok(pseudofs::allow_missing_files(Err(err))?),
+    Err(Error::StdIo(err)) =>
+      match err.kind() {
+        std::io::ErrorKind::NotFound => Ok(None),
+        _ => Err(Error::StdIo(err)),
+      },
     Err(err) => Err(err),
   }
 }
 
+// Throttle reasons reported per GT as `throttle_reason_<reason>`.
+pub const THROTTLE_REASONS: &[&str] = &["pl1", "pl2", "thermal", "prochot", "ratl", "vr_tdc", "vr_thermalert"];
+
 pub fn available() -> bool {
   sysfs::i915_module().is_dir()
 }
@@ -51,7 +61,7 @@ pub fn try_actual(card_id: u64) -> Result<Hertz> {
 }
 
 pub fn actual(card_id: u64) -> Result<Option<Hertz>> {
-  Ok(allow_missing_files(try_actual(card_id))?)
+  allow_missing_files(try_actual(card_id))
 }
 
 pub fn try_boost(card_id: u64) -> Result<Hertz> {
@@ -61,7 +71,7 @@ pub fn try_boost(card_id: u64) -> Result<Hertz> {
 }
 
 pub fn boost(card_id: u64) -> Result<Option<Hertz>> {
-  Ok(allow_missing_files(try_boost(card_id))?)
+  allow_missing_files(try_boost(card_id))
 }
 
 pub fn try_set_boost<H: AsRef<Hertz>>(card_id: u64, val: H) -> Result<()> {
@@ -72,7 +82,7 @@ pub fn try_set_boost<H: AsRef<Hertz>>(card_id: u64, val: H) -> Result<()> {
 }
 
 pub fn set_boost<H: AsRef<Hertz>>(card_id: u64, val: H) -> Result<Option<()>> {
-  Ok(allow_missing_files(try_set_boost(card_id, val))?)
+  allow_missing_files(try_set_boost(card_id, val))
 }
 
 pub fn try_max(card_id: u64) -> Result<Hertz> {
@@ -82,7 +92,7 @@ pub fn try_max(card_id: u64) -> Result<Hertz> {
 }
 
 pub fn max(card_id: u64) -> Result<Option<Hertz>> {
-  Ok(allow_missing_files(try_max(card_id))?)
+  allow_missing_files(try_max(card_id))
 }
 
 pub fn try_max_limit(card_id: u64) -> Result<Hertz> {
@@ -92,7 +102,7 @@ pub fn try_max_limit(card_id: u64) -> Result<Hertz> {
 }
 
 pub fn max_limit(card_id: u64) -> Result<Option<Hertz>> {
-  Ok(allow_missing_files(try_max_limit(card_id))?)
+  allow_missing_files(try_max_limit(card_id))
 }
 
 pub fn try_set_max<H: AsRef<Hertz>>(card_id: u64, val: H) -> Result<()> {
@@ -103,7 +113,7 @@ pub fn try_set_max<H: AsRef<Hertz>>(card_id: u64, val: H) -> Result<()> {
 }
 
 pub fn set_max<H: AsRef<Hertz>>(card_id: u64, val: H) -> Result<Option<()>> {
-  Ok(allow_missing_files(try_set_max(card_id, val))?)
+  allow_missing_files(try_set_max(card_id, val))
 }
 
 pub fn try_min(card_id: u64) -> Result<Hertz> {
@@ -113,7 +123,7 @@ pub fn try_min(card_id: u64) -> Result<Hertz> {
 }
 
 pub fn min(card_id: u64) -> Result<Option<Hertz>> {
-  Ok(allow_missing_files(try_min(card_id))?)
+  allow_missing_files(try_min(card_id))
 }
 
 pub fn try_min_limit(card_id: u64) -> Result<Hertz> {
@@ -123,7 +133,7 @@ pub fn try_min_limit(card_id: u64) -> Result<Hertz> {
 }
 
 pub fn min_limit(card_id: u64) -> Result<Option<Hertz>> {
-  Ok(allow_missing_files(try_min_limit(card_id))?)
+  allow_missing_files(try_min_limit(card_id))
 }
 
 pub fn try_set_min<H: AsRef<Hertz>>(card_id: u64, val: H) -> Result<()> {
@@ -134,7 +144,7 @@ pub fn try_set_min<H: AsRef<Hertz>>(card_id: u64, val: H) -> Result<()> {
 }
 
 pub fn set_min<H: AsRef<Hertz>>(card_id: u64, val: H) -> Result<Option<()>> {
-  Ok(allow_missing_files(try_set_min(card_id, val))?)
+  allow_missing_files(try_set_min(card_id, val))
 }
 
 pub fn try_requested(card_id: u64) -> Result<Hertz> {
@@ -144,7 +154,7 @@ pub fn try_requested(card_id: u64) -> Result<Hertz> {
 }
 
 pub fn requested(card_id: u64) -> Result<Option<Hertz>> {
-  Ok(allow_missing_files(try_requested(card_id))?)
+  allow_missing_files(try_requested(card_id))
 }
 
 pub fn try_optimum_limit(card_id: u64) -> Result<Hertz> {
@@ -154,5 +164,182 @@ pub fn try_optimum_limit(card_id: u64) -> Result<Hertz> {
 }
 
 pub fn optimum_limit(card_id: u64) -> Result<Option<Hertz>> {
-  Ok(allow_missing_files(try_optimum_limit(card_id))?)
+  allow_missing_files(try_optimum_limit(card_id))
+}
+
+// GTs under `cardN/gt`, e.g. the render GT `gt0` and the Meteor Lake media GT `gt1`. Older
+// kernels only have the legacy `cardN/gt_*_freq_mhz` files.
+pub fn try_gts(card_id: u64) -> Result<Vec<u64>> {
+  let mut gts = vec![];
+  for ent in fs::read_dir(sysfs::i915_gts(card_id))? {
+    let ent = ent?.file_name();
+    let ent = if let Some(ent) = ent.to_str() { ent } else { continue; };
+    if let Some(id) = ent.strip_prefix("gt") {
+      if let Ok(id) = id.parse::<u64>() { gts.push(id); }
+    }
+  }
+  gts.sort_unstable();
+  debug!("i915 get_gts card{} {:?}", card_id, gts);
+  Ok(gts)
+}
+
+pub fn gts(card_id: u64) -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_gts(card_id))
+}
+
+pub fn try_gt_actual(card_id: u64, gt_id: u64) -> Result<Hertz> {
+  let mhz = u64::read(&sysfs::i915_gt_act_mhz(card_id, gt_id))?;
+  debug!("i915 get_rps_act_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  Ok(Hertz::from_mhz(mhz as f64))
+}
+
+pub fn gt_actual(card_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_gt_actual(card_id, gt_id))
+}
+
+pub fn try_gt_requested(card_id: u64, gt_id: u64) -> Result<Hertz> {
+  let mhz = u64::read(&sysfs::i915_gt_cur_mhz(card_id, gt_id))?;
+  debug!("i915 get_rps_cur_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  Ok(Hertz::from_mhz(mhz as f64))
+}
+
+pub fn gt_requested(card_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_gt_requested(card_id, gt_id))
+}
+
+pub fn try_gt_boost(card_id: u64, gt_id: u64) -> Result<Hertz> {
+  let mhz = u64::read(&sysfs::i915_gt_boost_mhz(card_id, gt_id))?;
+  debug!("i915 get_rps_boost_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  Ok(Hertz::from_mhz(mhz as f64))
+}
+
+pub fn gt_boost(card_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_gt_boost(card_id, gt_id))
+}
+
+pub fn try_set_gt_boost<H: AsRef<Hertz>>(card_id: u64, gt_id: u64, val: H) -> Result<()> {
+  let mhz = val.as_ref().mhz() as u64;
+  info!("i915 set_rps_boost_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  mhz.write(&sysfs::i915_gt_boost_mhz(card_id, gt_id))?;
+  Ok(())
+}
+
+pub fn set_gt_boost<H: AsRef<Hertz>>(card_id: u64, gt_id: u64, val: H) -> Result<Option<()>> {
+  allow_missing_files(try_set_gt_boost(card_id, gt_id, val))
+}
+
+pub fn try_gt_max(card_id: u64, gt_id: u64) -> Result<Hertz> {
+  let mhz = u64::read(&sysfs::i915_gt_max_mhz(card_id, gt_id))?;
+  debug!("i915 get_rps_max_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  Ok(Hertz::from_mhz(mhz as f64))
+}
+
+pub fn gt_max(card_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_gt_max(card_id, gt_id))
+}
+
+pub fn try_set_gt_max<H: AsRef<Hertz>>(card_id: u64, gt_id: u64, val: H) -> Result<()> {
+  let mhz = val.as_ref().mhz() as u64;
+  info!("i915 set_rps_max_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  mhz.write(&sysfs::i915_gt_max_mhz(card_id, gt_id))?;
+  Ok(())
+}
+
+pub fn set_gt_max<H: AsRef<Hertz>>(card_id: u64, gt_id: u64, val: H) -> Result<Option<()>> {
+  allow_missing_files(try_set_gt_max(card_id, gt_id, val))
+}
+
+pub fn try_gt_min(card_id: u64, gt_id: u64) -> Result<Hertz> {
+  let mhz = u64::read(&sysfs::i915_gt_min_mhz(card_id, gt_id))?;
+  debug!("i915 get_rps_min_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  Ok(Hertz::from_mhz(mhz as f64))
+}
+
+pub fn gt_min(card_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_gt_min(card_id, gt_id))
+}
+
+pub fn try_set_gt_min<H: AsRef<Hertz>>(card_id: u64, gt_id: u64, val: H) -> Result<()> {
+  let mhz = val.as_ref().mhz() as u64;
+  info!("i915 set_rps_min_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  mhz.write(&sysfs::i915_gt_min_mhz(card_id, gt_id))?;
+  Ok(())
+}
+
+pub fn set_gt_min<H: AsRef<Hertz>>(card_id: u64, gt_id: u64, val: H) -> Result<Option<()>> {
+  allow_missing_files(try_set_gt_min(card_id, gt_id, val))
+}
+
+pub fn try_gt_max_limit(card_id: u64, gt_id: u64) -> Result<Hertz> {
+  let mhz = u64::read(&sysfs::i915_gt_rp0_mhz(card_id, gt_id))?;
+  debug!("i915 get_rps_RP0_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  Ok(Hertz::from_mhz(mhz as f64))
+}
+
+pub fn gt_max_limit(card_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_gt_max_limit(card_id, gt_id))
+}
+
+pub fn try_gt_min_limit(card_id: u64, gt_id: u64) -> Result<Hertz> {
+  let mhz = u64::read(&sysfs::i915_gt_rpn_mhz(card_id, gt_id))?;
+  debug!("i915 get_rps_RPn_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  Ok(Hertz::from_mhz(mhz as f64))
+}
+
+pub fn gt_min_limit(card_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_gt_min_limit(card_id, gt_id))
+}
+
+pub fn try_gt_optimum_limit(card_id: u64, gt_id: u64) -> Result<Hertz> {
+  let mhz = u64::read(&sysfs::i915_gt_rp1_mhz(card_id, gt_id))?;
+  debug!("i915 get_rps_RP1_freq_mhz card{} gt{} {}", card_id, gt_id, mhz);
+  Ok(Hertz::from_mhz(mhz as f64))
+}
+
+pub fn gt_optimum_limit(card_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_gt_optimum_limit(card_id, gt_id))
+}
+
+// The file holds a mask of the enabled RC6 states, RC6 is enabled if any bit is set.
+pub fn try_gt_rc6_enable(card_id: u64, gt_id: u64) -> Result<bool> {
+  let mask = u64::read(&sysfs::i915_gt_rc6_enable(card_id, gt_id))?;
+  debug!("i915 get_rc6_enable card{} gt{} {}", card_id, gt_id, mask);
+  Ok(mask != 0)
+}
+
+pub fn gt_rc6_enable(card_id: u64, gt_id: u64) -> Result<Option<bool>> {
+  allow_missing_files(try_gt_rc6_enable(card_id, gt_id))
+}
+
+pub fn try_gt_rc6_residency_ms(card_id: u64, gt_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::i915_gt_rc6_residency_ms(card_id, gt_id))?;
+  debug!("i915 get_rc6_residency_ms card{} gt{} {}", card_id, gt_id, res);
+  Ok(res)
+}
+
+pub fn gt_rc6_residency_ms(card_id: u64, gt_id: u64) -> Result<Option<u64>> {
+  allow_missing_files(try_gt_rc6_residency_ms(card_id, gt_id))
+}
+
+// `reason` is one of `THROTTLE_REASONS`.
+pub fn try_gt_throttle_reason(card_id: u64, gt_id: u64, reason: &str) -> Result<bool> {
+  let res = bool::read(&sysfs::i915_gt_throttle_reason(card_id, gt_id, reason))?;
+  debug!("i915 get_throttle_reason_{} card{} gt{} {}", reason, card_id, gt_id, res);
+  Ok(res)
+}
+
+pub fn gt_throttle_reason(card_id: u64, gt_id: u64, reason: &str) -> Result<Option<bool>> {
+  allow_missing_files(try_gt_throttle_reason(card_id, gt_id, reason))
+}
+
+// The active throttle reasons, None if the GT reports none of `THROTTLE_REASONS`.
+pub fn gt_throttle_reasons(card_id: u64, gt_id: u64) -> Result<Option<Vec<String>>> {
+  let mut res = None;
+  for reason in THROTTLE_REASONS {
+    if let Some(active) = gt_throttle_reason(card_id, gt_id, reason)? {
+      let reasons = res.get_or_insert_with(Vec::new);
+      if active { reasons.push(reason.to_string()); }
+    }
+  }
+  Ok(res)
 }
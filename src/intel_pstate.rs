@@ -16,15 +16,16 @@ pub enum Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-fn allow_missing_if_cpu_exists<T>(cpu_id: u64, result: Result<T>) -> Result<Option<T>> {
+impl cpu::NotFound for Error {
+  fn is_not_found(&self) -> bool {
+    matches!(self, Error::CpuxPseudofs(pseudofs::Error::NotFound(_, _)))
+  }
+}
+
+fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
   match result {
     Ok(val) => Ok(Some(val)),
-    Err(Error::CpuxPseudofs(err)) => {
-      if let pseudofs::Error::NotFound(_, _) = err {
-        if ! cpu::exists(cpu_id) { return Err(Error::CpuxPseudofs(err)); }
-      }
-      Ok(pseudofs::allow_missing_files(Err(err))? )
-    },
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
   }
 }
 
@@ -39,7 +40,7 @@ pub fn try_epb(cpu_id: u64) -> Result<u64> {
 }
 
 pub fn epb(cpu_id: u64) -> Result<Option<u64>> {
-  allow_missing_if_cpu_exists(cpu_id, try_epb(cpu_id))
+  cpu::allow_missing_if_exists(cpu_id, try_epb(cpu_id), allow_missing_files)
 }
 
 pub fn try_set_epb(cpu_id: u64, val: u64) -> Result<()> {
@@ -49,7 +50,7 @@ pub fn try_set_epb(cpu_id: u64, val: u64) -> Result<()> {
 }
 
 pub fn set_epb(cpu_id: u64, val: u64) -> Result<Option<()>> {
-  allow_missing_if_cpu_exists(cpu_id, try_set_epb(cpu_id, val))
+  cpu::allow_missing_if_exists(cpu_id, try_set_epb(cpu_id, val), allow_missing_files)
 }
 
 pub fn try_epp(cpu_id: u64) -> Result<String> {
@@ -59,7 +60,7 @@ pub fn try_epp(cpu_id: u64) -> Result<String> {
 }
 
 pub fn epp(cpu_id: u64) -> Result<Option<String>> {
-  allow_missing_if_cpu_exists(cpu_id, try_epp(cpu_id))
+  cpu::allow_missing_if_exists(cpu_id, try_epp(cpu_id), allow_missing_files)
 }
 
 pub fn try_set_epp(cpu_id: u64, val: &str) -> Result<()> {
@@ -69,7 +70,7 @@ pub fn try_set_epp(cpu_id: u64, val: &str) -> Result<()> {
 }
 
 pub fn set_epp(cpu_id: u64, val: &str) -> Result<Option<()>> {
-  allow_missing_if_cpu_exists(cpu_id, try_set_epp(cpu_id, val))
+  cpu::allow_missing_if_exists(cpu_id, try_set_epp(cpu_id, val), allow_missing_files)
 }
 
 pub fn try_epps(cpu_id: u64) -> Result<Vec<String>> {
@@ -79,7 +80,7 @@ pub fn try_epps(cpu_id: u64) -> Result<Vec<String>> {
 }
 
 pub fn epps(cpu_id: u64) -> Result<Option<Vec<String>>> {
-  allow_missing_if_cpu_exists(cpu_id, try_epps(cpu_id))
+  cpu::allow_missing_if_exists(cpu_id, try_epps(cpu_id), allow_missing_files)
 }
 
 pub fn try_status() -> Result<String> {
@@ -89,10 +90,7 @@ pub fn try_status() -> Result<String> {
 }
 
 pub fn status() -> Result<Option<String>> {
-  match try_status() {
-    Ok(val) => Ok(Some(val)),
-    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
-  }
+  allow_missing_files(try_status())
 }
 
 pub fn try_set_status(val: &str) -> Result<()> {
@@ -102,8 +100,111 @@ pub fn try_set_status(val: &str) -> Result<()> {
 }
 
 pub fn set_status(val: &str) -> Result<Option<()>> {
-  match try_set_status(val) {
-    Ok(()) => Ok(Some(())),
-    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
-  }
+  allow_missing_files(try_set_status(val))
+}
+
+// Turbo is disabled while `no_turbo` is set.
+pub fn try_no_turbo() -> Result<bool> {
+  let res = bool::read(&sysfs::intel_pstate_no_turbo())?;
+  debug!("intel_pstate get_no_turbo {}", res);
+  Ok(res)
+}
+
+pub fn no_turbo() -> Result<Option<bool>> {
+  allow_missing_files(try_no_turbo())
+}
+
+pub fn try_set_no_turbo(val: bool) -> Result<()> {
+  info!("intel_pstate set_no_turbo {}", val);
+  val.write(&sysfs::intel_pstate_no_turbo())?;
+  Ok(())
+}
+
+pub fn set_no_turbo(val: bool) -> Result<Option<()>> {
+  allow_missing_files(try_set_no_turbo(val))
+}
+
+// Upper limit of the P-state range, in percent of the max turbo frequency.
+pub fn try_max_perf_pct() -> Result<u64> {
+  let res = u64::read(&sysfs::intel_pstate_max_perf_pct())?;
+  debug!("intel_pstate get_max_perf_pct {}", res);
+  Ok(res)
+}
+
+pub fn max_perf_pct() -> Result<Option<u64>> {
+  allow_missing_files(try_max_perf_pct())
+}
+
+pub fn try_set_max_perf_pct(val: u64) -> Result<()> {
+  info!("intel_pstate set_max_perf_pct {}", val);
+  val.write(&sysfs::intel_pstate_max_perf_pct())?;
+  Ok(())
+}
+
+pub fn set_max_perf_pct(val: u64) -> Result<Option<()>> {
+  allow_missing_files(try_set_max_perf_pct(val))
+}
+
+// Lower limit of the P-state range, in percent of the max turbo frequency.
+pub fn try_min_perf_pct() -> Result<u64> {
+  let res = u64::read(&sysfs::intel_pstate_min_perf_pct())?;
+  debug!("intel_pstate get_min_perf_pct {}", res);
+  Ok(res)
+}
+
+pub fn min_perf_pct() -> Result<Option<u64>> {
+  allow_missing_files(try_min_perf_pct())
+}
+
+pub fn try_set_min_perf_pct(val: u64) -> Result<()> {
+  info!("intel_pstate set_min_perf_pct {}", val);
+  val.write(&sysfs::intel_pstate_min_perf_pct())?;
+  Ok(())
+}
+
+pub fn set_min_perf_pct(val: u64) -> Result<Option<()>> {
+  allow_missing_files(try_set_min_perf_pct(val))
+}
+
+// Share of the P-states which are in the turbo range, read-only.
+pub fn try_turbo_pct() -> Result<u64> {
+  let res = u64::read(&sysfs::intel_pstate_turbo_pct())?;
+  debug!("intel_pstate get_turbo_pct {}", res);
+  Ok(res)
+}
+
+pub fn turbo_pct() -> Result<Option<u64>> {
+  allow_missing_files(try_turbo_pct())
+}
+
+// Number of P-states supported by the processor, turbo included, read-only.
+pub fn try_num_pstates() -> Result<u64> {
+  let res = u64::read(&sysfs::intel_pstate_num_pstates())?;
+  debug!("intel_pstate get_num_pstates {}", res);
+  Ok(res)
+}
+
+pub fn num_pstates() -> Result<Option<u64>> {
+  allow_missing_files(try_num_pstates())
+}
+
+// Only present in active mode with HWP.
+pub fn try_hwp_dynamic_boost() -> Result<bool> {
+  let res = bool::read(&sysfs::intel_pstate_hwp_dynamic_boost())?;
+  debug!("intel_pstate get_hwp_dynamic_boost {}", res);
+  Ok(res)
+}
+
+pub fn hwp_dynamic_boost() -> Result<Option<bool>> {
+  allow_missing_files(try_hwp_dynamic_boost())
+}
+
+pub fn try_set_hwp_dynamic_boost(val: bool) -> Result<()> {
+  info!("intel_pstate set_hwp_dynamic_boost {}", val);
+  val.write(&sysfs::intel_pstate_hwp_dynamic_boost())?;
+  Ok(())
+}
+
+pub fn set_hwp_dynamic_boost(val: bool) -> Result<Option<()>> {
+  allow_missing_files(try_set_hwp_dynamic_boost(val))
 }
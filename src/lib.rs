@@ -2,14 +2,27 @@
 #![allow(unused_imports)]
 #![allow(unused_variables)]
 
+pub mod amd_pstate;
+pub mod amdgpu;
+pub mod capture;
 pub mod cli;
 pub mod cpu;
 pub mod cpufreq;
+pub mod cpuidle;
+pub mod devfreq;
+pub mod hwmon;
 pub mod i915;
 pub mod intel_pstate;
+pub mod powercap;
+pub mod summary;
+pub mod turbo;
+pub mod units;
+pub mod xe;
 
 pub(crate) mod drm;
+pub(crate) mod pci_ids;
 pub(crate) mod pseudofs;
 pub(crate) mod sysfs;
-pub(crate) mod units;
 pub(crate) mod utils;
+
+pub use sysfs::{root, set_root};
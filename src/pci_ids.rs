@@ -0,0 +1,126 @@
+use {
+  crate::sysfs,
+  log::debug,
+  std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+  },
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct Names {
+  pub vendor: Option<String>,
+  pub device: Option<String>,
+  pub subsystem: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Device {
+  name: String,
+  subsystems: HashMap<String, String>,
+}
+
+#[derive(Debug, Default)]
+struct Vendor {
+  name: String,
+  devices: HashMap<String, Device>,
+}
+
+// The parsed database, keyed by lowercase hex ids as they appear in the file, e.g. `8086`.
+#[derive(Debug, Default)]
+struct Database {
+  vendors: HashMap<String, Vendor>,
+}
+
+impl Database {
+
+  // Vendors are listed at the start of a line, their devices are indented by one tab and
+  // the subsystems of a device by two, e.g. `\t\t1028 0b19  <name>`. The classes at the end
+  // of the file are skipped.
+  fn parse(text: &str) -> Self {
+    let mut db = Self::default();
+    let mut vendor: Option<String> = None;
+    let mut device: Option<String> = None;
+    for line in text.lines() {
+      if line.starts_with('#') || line.trim().is_empty() { continue; }
+      if let Some(line) = line.strip_prefix("\t\t") {
+        let (vendor, device) = if let (Some(v), Some(d)) = (vendor.as_ref(), device.as_ref()) { (v, d) } else { continue; };
+        if let Some((ids, name)) = line.split_once("  ") {
+          if let Some(device) = db.vendors.get_mut(vendor).and_then(|v| v.devices.get_mut(device)) {
+            device.subsystems.insert(ids.to_string(), name.to_string());
+          }
+        }
+      } else if let Some(line) = line.strip_prefix('\t') {
+        let vendor = if let Some(vendor) = vendor.as_ref() { vendor } else { continue; };
+        device = None;
+        if let Some((id, name)) = line.split_once("  ") {
+          if let Some(vendor) = db.vendors.get_mut(vendor) {
+            vendor.devices.insert(id.to_string(), Device { name: name.to_string(), ..Device::default() });
+            device = Some(id.to_string());
+          }
+        }
+      } else {
+        vendor = None;
+        device = None;
+        if line.starts_with("C ") { continue; }
+        if let Some((id, name)) = line.split_once("  ") {
+          db.vendors.insert(id.to_string(), Vendor { name: name.to_string(), ..Vendor::default() });
+          vendor = Some(id.to_string());
+        }
+      }
+    }
+    db
+  }
+
+  fn names(&self, vendor_id: u16, device_id: u16, subsystem: Option<(u16, u16)>) -> Names {
+    let vendor = self.vendors.get(&format!("{:04x}", vendor_id));
+    let device = vendor.and_then(|v| v.devices.get(&format!("{:04x}", device_id)));
+    let subsystem = subsystem.and_then(|(v, d)| device.and_then(|dev| dev.subsystems.get(&format!("{:04x} {:04x}", v, d))));
+    Names {
+      vendor: vendor.map(|v| v.name.clone()),
+      device: device.map(|d| d.name.clone()),
+      subsystem: subsystem.cloned(),
+    }
+  }
+}
+
+// The database of the last path loaded. It is parsed once per process, unless the root and so
+// the path changes.
+static DATABASE: Mutex<Option<(PathBuf, Arc<Database>)>> = Mutex::new(None);
+
+// Locations of the pci.ids database used by distributions, in order of preference.
+fn paths() -> Vec<PathBuf> {
+  ["usr/share/hwdata/pci.ids", "usr/share/misc/pci.ids", "usr/share/pci.ids"]
+    .iter()
+    .map(|p| sysfs::root().join(p))
+    .collect()
+}
+
+fn database() -> Option<Arc<Database>> {
+  let path = paths().into_iter().find(|p| p.is_file())?;
+  let mut cached = DATABASE.lock().unwrap_or_else(|e| e.into_inner());
+  if let Some((cached_path, db)) = cached.as_ref() {
+    if *cached_path == path { return Some(db.clone()); }
+  }
+  let db =
+    match fs::read(&path) {
+      Ok(bytes) => Arc::new(Database::parse(&String::from_utf8_lossy(&bytes))),
+      Err(err) => {
+        debug!("pci_ids read {} {}", path.display(), err);
+        return None;
+      },
+    };
+  debug!("pci_ids load {} {} vendors", path.display(), db.vendors.len());
+  *cached = Some((path, db.clone()));
+  Some(db)
+}
+
+// Names of a PCI device from the first pci.ids database found, None if none is installed or it
+// cannot be read.
+pub fn names(vendor_id: u16, device_id: u16, subsystem: Option<(u16, u16)>) -> Option<Names> {
+  let names = database()?.names(vendor_id, device_id, subsystem);
+  debug!("pci_ids get_names {:04x}:{:04x} {:?}", vendor_id, device_id, names);
+  Some(names)
+}
@@ -0,0 +1,266 @@
+use {
+  crate::{
+    pseudofs,
+    pseudofs::{Read, Write},
+    sysfs,
+    units::Watts,
+  },
+  log::{debug, info},
+  std::fs,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+
+  #[error(transparent)] CpuxPseudofs(#[from] crate::pseudofs::Error),
+  #[error(transparent)] StdIo(#[from] std::io::Error),
+
+  #[error("Error parsing rapl zone string: {0}")]
+  ParseZoneId(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Ok(ok) => Ok(Some(ok)),
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
+    Err(Error::StdIo(err)) =>
+      match err.kind() {
+        std::io::ErrorKind::NotFound => Ok(None),
+        _ => Err(Error::StdIo(err)),
+      },
+    Err(err) => Err(err),
+  }
+}
+
+// Energy counters are readable by root only since Linux 5.10, treat them as missing otherwise.
+fn allow_unreadable_files<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Err(Error::CpuxPseudofs(pseudofs::Error::NoPermission(path, err))) => {
+      debug!("powercap NoPermission {} {}", path.display(), err);
+      Ok(None)
+    },
+    result => allow_missing_files(result),
+  }
+}
+
+// A zone `intel-rapl:<zone>` or a subzone `intel-rapl:<zone>:<subzone>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ZoneId {
+  pub zone: u64,
+  pub subzone: Option<u64>,
+}
+
+impl ZoneId {
+
+  pub fn new(zone: u64, subzone: Option<u64>) -> Self { Self { zone, subzone } }
+
+  pub fn is_subzone(&self) -> bool { self.subzone.is_some() }
+}
+
+impl std::str::FromStr for ZoneId {
+  type Err = Error;
+
+  // Accepts `0`, `0:1`, `intel-rapl:0` and `intel-rapl:0:1`.
+  fn from_str(s: &str) -> Result<Self> {
+    let ids = s.trim().strip_prefix("intel-rapl:").unwrap_or_else(|| s.trim());
+    let ids: Vec<&str> = ids.split(':').collect();
+    let parse = |id: &str| id.parse::<u64>().map_err(|e| Error::ParseZoneId(s.to_string()));
+    match ids[..] {
+      [zone] => Ok(Self::new(parse(zone)?, None)),
+      [zone, subzone] => Ok(Self::new(parse(zone)?, Some(parse(subzone)?))),
+      _ => Err(Error::ParseZoneId(s.to_string())),
+    }
+  }
+}
+
+impl std::fmt::Display for ZoneId {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.subzone {
+      Some(subzone) => write!(f, "intel-rapl:{}:{}", self.zone, subzone),
+      None => write!(f, "intel-rapl:{}", self.zone),
+    }
+  }
+}
+
+impl serde::Serialize for ZoneId {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.to_string())
+  }
+}
+
+pub fn available() -> bool {
+  matches!(zones(), Ok(Some(zones)) if ! zones.is_empty())
+}
+
+// Zones and subzones, each zone followed by its subzones.
+pub fn try_zones() -> Result<Vec<ZoneId>> {
+  let mut zones = vec![];
+  for ent in fs::read_dir(sysfs::powercap())? {
+    let ent = ent?.file_name();
+    let ent = if let Some(ent) = ent.to_str() { ent } else { continue; };
+    if ! ent.starts_with("intel-rapl:") { continue; }
+    if let Ok(zone_id) = ent.parse::<ZoneId>() { zones.push(zone_id); }
+  }
+  zones.sort_unstable();
+  Ok(zones)
+}
+
+pub fn zones() -> Result<Option<Vec<ZoneId>>> {
+  allow_missing_files(try_zones())
+}
+
+pub fn try_name(zone_id: ZoneId) -> Result<String> {
+  let res = String::read(&sysfs::rapl_name(zone_id))?;
+  debug!(r#"powercap get_name {} "{}""#, zone_id, res);
+  Ok(res)
+}
+
+pub fn name(zone_id: ZoneId) -> Result<Option<String>> {
+  allow_missing_files(try_name(zone_id))
+}
+
+// Zones whose name is `name`, e.g. `package-0`, `core`, `psys`.
+pub fn zones_named(name: &str) -> Result<Vec<ZoneId>> {
+  let mut res = vec![];
+  for zone_id in zones()?.unwrap_or_default() {
+    if self::name(zone_id)?.as_deref() == Some(name) { res.push(zone_id); }
+  }
+  Ok(res)
+}
+
+pub fn try_enabled(zone_id: ZoneId) -> Result<bool> {
+  let res = bool::read(&sysfs::rapl_enabled(zone_id))?;
+  debug!("powercap get_enabled {} {}", zone_id, res);
+  Ok(res)
+}
+
+pub fn enabled(zone_id: ZoneId) -> Result<Option<bool>> {
+  allow_missing_files(try_enabled(zone_id))
+}
+
+pub fn try_set_enabled(zone_id: ZoneId, val: bool) -> Result<()> {
+  info!("powercap set_enabled {} {}", zone_id, val);
+  val.write(&sysfs::rapl_enabled(zone_id))?;
+  Ok(())
+}
+
+pub fn set_enabled(zone_id: ZoneId, val: bool) -> Result<Option<()>> {
+  allow_missing_files(try_set_enabled(zone_id, val))
+}
+
+pub fn try_energy_uj(zone_id: ZoneId) -> Result<u64> {
+  let res = u64::read(&sysfs::rapl_energy_uj(zone_id))?;
+  debug!("powercap get_energy_uj {} {}", zone_id, res);
+  Ok(res)
+}
+
+pub fn energy_uj(zone_id: ZoneId) -> Result<Option<u64>> {
+  allow_unreadable_files(try_energy_uj(zone_id))
+}
+
+pub fn try_max_energy_range_uj(zone_id: ZoneId) -> Result<u64> {
+  let res = u64::read(&sysfs::rapl_max_energy_range_uj(zone_id))?;
+  debug!("powercap get_max_energy_range_uj {} {}", zone_id, res);
+  Ok(res)
+}
+
+pub fn max_energy_range_uj(zone_id: ZoneId) -> Result<Option<u64>> {
+  allow_unreadable_files(try_max_energy_range_uj(zone_id))
+}
+
+// Energy consumed between two readings of `energy_uj`. The counter wraps around after
+// `max_energy_range_uj`, without that range a wrapped counter cannot be accounted for.
+pub fn energy_delta_uj(prev_uj: u64, cur_uj: u64, max_energy_range_uj: Option<u64>) -> Option<u64> {
+  if cur_uj >= prev_uj { return Some(cur_uj - prev_uj); }
+  let max = max_energy_range_uj?;
+  if prev_uj > max { return None; }
+  Some(max - prev_uj + cur_uj)
+}
+
+pub fn try_constraints(zone_id: ZoneId) -> Result<Vec<u64>> {
+  let mut ids = vec![];
+  for ent in fs::read_dir(sysfs::rapl_zone(zone_id))? {
+    let ent = ent?.file_name();
+    let ent = if let Some(ent) = ent.to_str() { ent } else { continue; };
+    if let Some(id) = ent.strip_prefix("constraint_").and_then(|e| e.strip_suffix("_name")) {
+      if let Ok(id) = id.parse::<u64>() { ids.push(id); }
+    }
+  }
+  ids.sort_unstable();
+  Ok(ids)
+}
+
+pub fn constraints(zone_id: ZoneId) -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_constraints(zone_id))
+}
+
+pub fn try_constraint_name(zone_id: ZoneId, constraint_id: u64) -> Result<String> {
+  let res = String::read(&sysfs::rapl_constraint_name(zone_id, constraint_id))?;
+  debug!(r#"powercap get_constraint_name {} {} "{}""#, zone_id, constraint_id, res);
+  Ok(res)
+}
+
+pub fn constraint_name(zone_id: ZoneId, constraint_id: u64) -> Result<Option<String>> {
+  allow_missing_files(try_constraint_name(zone_id, constraint_id))
+}
+
+// Constraint of the zone named `name`, e.g. `long_term`, `short_term`, `peak_power`.
+pub fn constraint_named(zone_id: ZoneId, name: &str) -> Result<Option<u64>> {
+  for constraint_id in constraints(zone_id)?.unwrap_or_default() {
+    if constraint_name(zone_id, constraint_id)?.as_deref() == Some(name) { return Ok(Some(constraint_id)); }
+  }
+  Ok(None)
+}
+
+pub fn try_power_limit(zone_id: ZoneId, constraint_id: u64) -> Result<Watts> {
+  let res = u64::read(&sysfs::rapl_constraint_power_limit_uw(zone_id, constraint_id))?;
+  debug!("powercap get_power_limit_uw {} {} {}", zone_id, constraint_id, res);
+  Ok(Watts::from_microwatts(res))
+}
+
+pub fn power_limit(zone_id: ZoneId, constraint_id: u64) -> Result<Option<Watts>> {
+  allow_missing_files(try_power_limit(zone_id, constraint_id))
+}
+
+pub fn try_set_power_limit<W: AsRef<Watts>>(zone_id: ZoneId, constraint_id: u64, val: W) -> Result<()> {
+  let uw = val.as_ref().microwatts();
+  info!("powercap set_power_limit_uw {} {} {}", zone_id, constraint_id, uw);
+  uw.write(&sysfs::rapl_constraint_power_limit_uw(zone_id, constraint_id))?;
+  Ok(())
+}
+
+pub fn set_power_limit<W: AsRef<Watts>>(zone_id: ZoneId, constraint_id: u64, val: W) -> Result<Option<()>> {
+  allow_missing_files(try_set_power_limit(zone_id, constraint_id, val))
+}
+
+pub fn try_time_window_us(zone_id: ZoneId, constraint_id: u64) -> Result<u64> {
+  let res = u64::read(&sysfs::rapl_constraint_time_window_us(zone_id, constraint_id))?;
+  debug!("powercap get_time_window_us {} {} {}", zone_id, constraint_id, res);
+  Ok(res)
+}
+
+pub fn time_window_us(zone_id: ZoneId, constraint_id: u64) -> Result<Option<u64>> {
+  allow_missing_files(try_time_window_us(zone_id, constraint_id))
+}
+
+pub fn try_set_time_window_us(zone_id: ZoneId, constraint_id: u64, val: u64) -> Result<()> {
+  info!("powercap set_time_window_us {} {} {}", zone_id, constraint_id, val);
+  val.write(&sysfs::rapl_constraint_time_window_us(zone_id, constraint_id))?;
+  Ok(())
+}
+
+pub fn set_time_window_us(zone_id: ZoneId, constraint_id: u64, val: u64) -> Result<Option<()>> {
+  allow_missing_files(try_set_time_window_us(zone_id, constraint_id, val))
+}
+
+pub fn try_max_power(zone_id: ZoneId, constraint_id: u64) -> Result<Watts> {
+  let res = u64::read(&sysfs::rapl_constraint_max_power_uw(zone_id, constraint_id))?;
+  debug!("powercap get_max_power_uw {} {} {}", zone_id, constraint_id, res);
+  Ok(Watts::from_microwatts(res))
+}
+
+pub fn max_power(zone_id: ZoneId, constraint_id: u64) -> Result<Option<Watts>> {
+  allow_missing_files(try_max_power(zone_id, constraint_id))
+}
@@ -18,6 +18,9 @@ pub enum Error {
   #[error("{0}: value could not be parsed as bool: `{1}`")]
   ParseBool(String, String),
 
+  #[error("{0}: value could not be parsed as i64: `{1}`")]
+  ParseI64(String, String),
+
   #[error("{0}: value could not be parsed as u64: `{1}")]
   ParseU64(String, String),
 }
@@ -134,6 +137,20 @@ impl Write for u64 {
   }
 }
 
+impl Read for i64 {
+  type Item = i64;
+
+  fn read(path: &Path) -> Result<Self::Item> {
+    trace!("pseudofs read_i64 {}", path.display());
+    let val = read_to_string(path)?;
+    let val = val.trim_end();
+    match val.parse::<i64>() {
+      Ok(val) => Ok(val),
+      Err(_) => Err(Error::ParseI64(path.display().to_string(), val.to_string())),
+    }
+  }
+}
+
 impl Read for String {
   type Item = String;
 
@@ -0,0 +1,843 @@
+use {
+  crate::{
+    amd_pstate,
+    amdgpu::{self, DpmLevel, OdTable, PowerProfile},
+    cpu,
+    cpufreq,
+    cpuidle,
+    devfreq::{self, TransStat},
+    drm,
+    hwmon::{self, PwmMode},
+    i915,
+    intel_pstate as pstate,
+    pci_ids,
+    powercap::{self, ZoneId},
+    turbo,
+    units::{Celsius, Hertz, Rpm, Volts, Watts},
+    xe,
+  },
+  serde::Serialize,
+  std::time::Duration,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+
+  #[error(transparent)] CpuxAmdPstate(#[from] crate::amd_pstate::Error),
+  #[error(transparent)] CpuxAmdgpu(#[from] crate::amdgpu::Error),
+  #[error(transparent)] CpuxCpu(#[from] crate::cpu::Error),
+  #[error(transparent)] CpuxCpufreq(#[from] crate::cpufreq::Error),
+  #[error(transparent)] CpuxCpuidle(#[from] crate::cpuidle::Error),
+  #[error(transparent)] CpuxDevfreq(#[from] crate::devfreq::Error),
+  #[error(transparent)] CpuxDrm(#[from] crate::drm::Error),
+  #[error(transparent)] CpuxHwmon(#[from] crate::hwmon::Error),
+  #[error(transparent)] CpuxI915(#[from] crate::i915::Error),
+  #[error(transparent)] CpuxIntelPstate(#[from] crate::intel_pstate::Error),
+  #[error(transparent)] CpuxPowercap(#[from] crate::powercap::Error),
+  #[error(transparent)] CpuxTurbo(#[from] crate::turbo::Error),
+  #[error(transparent)] CpuxXe(#[from] crate::xe::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// Parts of the summary to read. Parts which are not selected are None, as are the per-cpu values
+// which belong to them, e.g. `Cpu::epb` without `intel_pstate`.
+#[derive(Clone, Debug, Default)]
+pub struct Select {
+  pub cpufreq: bool,
+  pub intel_pstate: bool,
+  pub amd_pstate: bool,
+  pub cpuidle: bool,
+  pub gpus: bool,
+  pub i915: bool,
+  pub xe: bool,
+  pub amdgpu: bool,
+  pub devfreq: bool,
+  pub hwmon: bool,
+  pub rapl: bool,
+}
+
+impl Select {
+
+  pub fn all() -> Self {
+    Self {
+      cpufreq: true,
+      intel_pstate: true,
+      amd_pstate: true,
+      cpuidle: true,
+      gpus: true,
+      i915: true,
+      xe: true,
+      amdgpu: true,
+      devfreq: true,
+      hwmon: true,
+      rapl: true,
+    }
+  }
+}
+
+fn read_if<T, E: Into<Error>>(selected: bool, read: impl FnOnce() -> std::result::Result<Option<T>, E>) -> Result<Option<T>> {
+  if selected { read().map_err(Into::into) } else { Ok(None) }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Cpu {
+  pub id: u64,
+  pub online: bool,
+  pub policy: Option<u64>,
+  pub cur: Option<Hertz>,
+  pub min: Option<Hertz>,
+  pub max: Option<Hertz>,
+  pub min_limit: Option<Hertz>,
+  pub max_limit: Option<Hertz>,
+  pub governor: Option<String>,
+  pub governors: Option<Vec<String>>,
+  pub epb: Option<u64>,
+  pub epp: Option<String>,
+  pub epps: Option<Vec<String>>,
+  pub package_temp: Option<Celsius>,
+  pub core_temp: Option<Celsius>,
+  pub amd_pstate: Option<AmdPstateCpu>,
+  pub idle_states: Option<Vec<IdleState>>,
+  pub turbo: Option<bool>,
+}
+
+impl Cpu {
+
+  pub fn read(cpu_id: u64) -> Result<Self> {
+    let sensors = hwmon::CpuSensors::read()?;
+    Self::read_selected(cpu_id, &Select::all(), Some(&sensors))
+  }
+
+  // Temperatures are read from `sensors`, which is read once for all cpus. The policy
+  // attributes of an offline cpu are None, its policy may be inactive.
+  fn read_selected(cpu_id: u64, select: &Select, sensors: Option<&hwmon::CpuSensors>) -> Result<Self> {
+    let online = cpu::online(cpu_id)?.unwrap_or(true);
+    let cpufreq = select.cpufreq && online;
+    let epp = (select.intel_pstate || select.amd_pstate) && online;
+    Ok(Self {
+      id: cpu_id,
+      online,
+      policy: read_if(select.cpufreq, || cpufreq::policy(cpu_id))?,
+      cur: read_if(cpufreq, || cpufreq::cur(cpu_id))?,
+      min: read_if(cpufreq, || cpufreq::min(cpu_id))?,
+      max: read_if(cpufreq, || cpufreq::max(cpu_id))?,
+      min_limit: read_if(cpufreq, || cpufreq::min_limit(cpu_id))?,
+      max_limit: read_if(cpufreq, || cpufreq::max_limit(cpu_id))?,
+      governor: read_if(cpufreq, || cpufreq::governor(cpu_id))?,
+      governors: read_if(cpufreq, || cpufreq::governors(cpu_id))?,
+      epb: read_if(select.intel_pstate, || pstate::epb(cpu_id))?,
+      epp: read_if(epp, || pstate::epp(cpu_id))?,
+      epps: read_if(epp, || pstate::epps(cpu_id))?,
+      package_temp: if let Some(sensors) = sensors { sensors.package_temp(cpu_id)? } else { None },
+      core_temp: if let Some(sensors) = sensors { sensors.core_temp(cpu_id)? } else { None },
+      amd_pstate: if select.amd_pstate && online && amd_pstate::available() { Some(AmdPstateCpu::read(cpu_id)?) } else { None },
+      idle_states: if select.cpuidle { IdleState::read_all(cpu_id)? } else { None },
+      turbo: read_if(cpufreq, || turbo::turbo(cpu_id))?,
+    })
+  }
+}
+
+// A cpufreq policy, shared by the cpus which switch frequency together.
+#[derive(Clone, Debug, Serialize)]
+pub struct Policy {
+  pub id: u64,
+  pub active: bool,
+  pub affected_cpus: Option<Vec<u64>>,
+  pub related_cpus: Option<Vec<u64>>,
+  pub driver: Option<String>,
+  pub cur: Option<Hertz>,
+  pub cpuinfo_cur: Option<Hertz>,
+  pub min: Option<Hertz>,
+  pub max: Option<Hertz>,
+  pub min_limit: Option<Hertz>,
+  pub max_limit: Option<Hertz>,
+  pub base: Option<Hertz>,
+  pub bios_limit: Option<Hertz>,
+  pub transition_latency: Option<u64>,
+  pub governor: Option<String>,
+  pub governors: Option<Vec<String>>,
+}
+
+impl Policy {
+
+  // The attributes of an inactive policy are None, the kernel refuses to read them.
+  pub fn read(policy_id: u64) -> Result<Self> {
+    let active = cpufreq::policy_active(policy_id)?.unwrap_or(false);
+    Ok(Self {
+      id: policy_id,
+      active,
+      affected_cpus: read_if(active, || cpufreq::affected_cpus(policy_id))?,
+      related_cpus: read_if(active, || cpufreq::related_cpus(policy_id))?,
+      driver: read_if(active, || cpufreq::policy_driver(policy_id))?,
+      cur: read_if(active, || cpufreq::policy_cur(policy_id))?,
+      cpuinfo_cur: read_if(active, || cpufreq::policy_cpuinfo_cur(policy_id))?,
+      min: read_if(active, || cpufreq::policy_min(policy_id))?,
+      max: read_if(active, || cpufreq::policy_max(policy_id))?,
+      min_limit: read_if(active, || cpufreq::policy_min_limit(policy_id))?,
+      max_limit: read_if(active, || cpufreq::policy_max_limit(policy_id))?,
+      base: read_if(active, || cpufreq::policy_base(policy_id))?,
+      bios_limit: read_if(active, || cpufreq::policy_bios_limit(policy_id))?,
+      transition_latency: read_if(active, || cpufreq::policy_transition_latency(policy_id))?.filter(|ns| *ns != u32::MAX as u64),
+      governor: read_if(active, || cpufreq::policy_governor(policy_id))?,
+      governors: read_if(active, || cpufreq::policy_governors(policy_id))?,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AmdPstateCpu {
+  pub highest_perf: Option<u64>,
+  pub lowest_nonlinear_freq: Option<Hertz>,
+  pub max_freq: Option<Hertz>,
+  pub prefcore_ranking: Option<u64>,
+}
+
+impl AmdPstateCpu {
+
+  pub fn read(cpu_id: u64) -> Result<Self> {
+    Ok(Self {
+      highest_perf: amd_pstate::highest_perf(cpu_id)?,
+      lowest_nonlinear_freq: amd_pstate::lowest_nonlinear_freq(cpu_id)?,
+      max_freq: amd_pstate::max_freq(cpu_id)?,
+      prefcore_ranking: amd_pstate::prefcore_ranking(cpu_id)?,
+    })
+  }
+}
+
+// Latency, residency and time are in µs.
+#[derive(Clone, Debug, Serialize)]
+pub struct IdleState {
+  pub id: u64,
+  pub name: Option<String>,
+  pub desc: Option<String>,
+  pub latency: Option<u64>,
+  pub residency: Option<u64>,
+  pub usage: Option<u64>,
+  pub time: Option<u64>,
+  pub disabled: Option<bool>,
+}
+
+impl IdleState {
+
+  pub fn read(cpu_id: u64, state_id: u64) -> Result<Self> {
+    Ok(Self {
+      id: state_id,
+      name: cpuidle::name(cpu_id, state_id)?,
+      desc: cpuidle::desc(cpu_id, state_id)?,
+      latency: cpuidle::latency(cpu_id, state_id)?,
+      residency: cpuidle::residency(cpu_id, state_id)?,
+      usage: cpuidle::usage(cpu_id, state_id)?,
+      time: cpuidle::time(cpu_id, state_id)?,
+      disabled: cpuidle::disable(cpu_id, state_id)?,
+    })
+  }
+
+  // None if the cpu has no cpuidle directory.
+  pub fn read_all(cpu_id: u64) -> Result<Option<Vec<Self>>> {
+    let state_ids = if let Some(state_ids) = cpuidle::states(cpu_id)? { state_ids } else { return Ok(None); };
+    let mut states = vec![];
+    for state_id in state_ids { states.push(Self::read(cpu_id, state_id)?); }
+    Ok(Some(states))
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Cpuidle {
+  pub driver: Option<String>,
+  pub governor: Option<String>,
+  pub governors: Option<Vec<String>>,
+}
+
+impl Cpuidle {
+
+  pub fn read() -> Result<Self> {
+    Ok(Self {
+      driver: cpuidle::driver()?,
+      governor: cpuidle::governor()?,
+      governors: cpuidle::governors()?,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct IntelPstate {
+  pub status: Option<String>,
+  pub no_turbo: Option<bool>,
+  pub max_perf_pct: Option<u64>,
+  pub min_perf_pct: Option<u64>,
+  pub turbo_pct: Option<u64>,
+  pub num_pstates: Option<u64>,
+  pub hwp_dynamic_boost: Option<bool>,
+}
+
+impl IntelPstate {
+
+  // `no_turbo`, `turbo_pct` and `num_pstates` fail with EAGAIN while the driver is off.
+  pub fn read() -> Result<Self> {
+    let status = pstate::status()?;
+    let on = status.as_deref() != Some("off");
+    Ok(Self {
+      no_turbo: read_if(on, pstate::no_turbo)?,
+      max_perf_pct: pstate::max_perf_pct()?,
+      min_perf_pct: pstate::min_perf_pct()?,
+      turbo_pct: read_if(on, pstate::turbo_pct)?,
+      num_pstates: read_if(on, pstate::num_pstates)?,
+      hwp_dynamic_boost: pstate::hwp_dynamic_boost()?,
+      status,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AmdPstate {
+  pub status: Option<String>,
+  pub prefcore: Option<String>,
+}
+
+impl AmdPstate {
+
+  pub fn read() -> Result<Self> {
+    Ok(Self {
+      status: amd_pstate::status()?,
+      prefcore: amd_pstate::prefcore()?,
+    })
+  }
+}
+
+// PCI identification of a DRM card, with names from pci.ids if it is installed. Card
+// numbering can change between boots, the PCI slot does not.
+#[derive(Clone, Debug, Serialize)]
+pub struct Gpu {
+  pub id: u64,
+  pub driver: Option<String>,
+  pub slot: Option<String>,
+  pub vendor_id: Option<u16>,
+  pub device_id: Option<u16>,
+  pub subsystem_vendor_id: Option<u16>,
+  pub subsystem_device_id: Option<u16>,
+  pub boot_vga: Option<bool>,
+  pub render_node: Option<String>,
+  pub vendor: Option<String>,
+  pub device: Option<String>,
+  pub subsystem: Option<String>,
+}
+
+impl Gpu {
+
+  pub fn read(card_id: u64) -> Result<Self> {
+    let vendor_id = drm::card_vendor_id(card_id)?;
+    let device_id = drm::card_device_id(card_id)?;
+    let subsystem_vendor_id = drm::card_subsystem_vendor_id(card_id)?;
+    let subsystem_device_id = drm::card_subsystem_device_id(card_id)?;
+    let names =
+      match (vendor_id, device_id) {
+        (Some(vendor_id), Some(device_id)) => {
+          let subsystem = subsystem_vendor_id.zip(subsystem_device_id);
+          pci_ids::names(vendor_id, device_id, subsystem).unwrap_or_default()
+        },
+        _ => pci_ids::Names::default(),
+      };
+    Ok(Self {
+      id: card_id,
+      driver: drm::card_driver(card_id)?,
+      slot: drm::card_slot(card_id)?,
+      vendor_id,
+      device_id,
+      subsystem_vendor_id,
+      subsystem_device_id,
+      boot_vga: drm::card_boot_vga(card_id)?,
+      render_node: drm::card_render_node(card_id)?,
+      vendor: names.vendor,
+      device: names.device,
+      subsystem: names.subsystem,
+    })
+  }
+}
+
+// Power cap and draw of a DRM card, read from the hwmon chip of the card's device.
+#[derive(Clone, Debug, Serialize)]
+pub struct GpuPower {
+  pub chip_id: u64,
+  pub cap: Option<Watts>,
+  pub cap_min: Option<Watts>,
+  pub cap_max: Option<Watts>,
+  pub average: Option<Watts>,
+}
+
+impl GpuPower {
+
+  // None if no hwmon chip of the card reports a power cap or draw.
+  pub fn read(card_id: u64) -> Result<Option<Self>> {
+    for chip_id in drm::card_hwmons(card_id)?.unwrap_or_default() {
+      let power = Self {
+        chip_id,
+        cap: hwmon::power_cap(chip_id)?,
+        cap_min: hwmon::power_cap_min(chip_id)?,
+        cap_max: hwmon::power_cap_max(chip_id)?,
+        average: hwmon::power_average(chip_id)?,
+      };
+      if power.cap.is_some() || power.average.is_some() { return Ok(Some(power)); }
+    }
+    Ok(None)
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct I915Gt {
+  pub id: u64,
+  pub actual: Option<Hertz>,
+  pub requested: Option<Hertz>,
+  pub min: Option<Hertz>,
+  pub max: Option<Hertz>,
+  pub boost: Option<Hertz>,
+  pub min_limit: Option<Hertz>,
+  pub max_limit: Option<Hertz>,
+  pub optimum_limit: Option<Hertz>,
+  pub rc6_enable: Option<bool>,
+  pub rc6_residency_ms: Option<u64>,
+  pub rc6: Option<f64>,
+  pub throttle_reasons: Option<Vec<String>>,
+}
+
+impl I915Gt {
+
+  pub fn read(card_id: u64, gt_id: u64) -> Result<Self> {
+    Ok(Self {
+      id: gt_id,
+      actual: i915::gt_actual(card_id, gt_id)?,
+      requested: i915::gt_requested(card_id, gt_id)?,
+      min: i915::gt_min(card_id, gt_id)?,
+      max: i915::gt_max(card_id, gt_id)?,
+      boost: i915::gt_boost(card_id, gt_id)?,
+      min_limit: i915::gt_min_limit(card_id, gt_id)?,
+      max_limit: i915::gt_max_limit(card_id, gt_id)?,
+      optimum_limit: i915::gt_optimum_limit(card_id, gt_id)?,
+      rc6_enable: i915::gt_rc6_enable(card_id, gt_id)?,
+      rc6_residency_ms: i915::gt_rc6_residency_ms(card_id, gt_id)?,
+      rc6: None,
+      throttle_reasons: i915::gt_throttle_reasons(card_id, gt_id)?,
+    })
+  }
+
+  // Percentage of the time spent in RC6 since `prev` was read, `elapsed` ago.
+  pub fn set_rc6(&mut self, prev: &I915Gt, elapsed: Duration) {
+    self.rc6 =
+      match (prev.rc6_residency_ms, self.rc6_residency_ms) {
+        (Some(prev_ms), Some(cur_ms)) if cur_ms >= prev_ms && elapsed > Duration::from_millis(0) =>
+          Some(((cur_ms - prev_ms) as f64 / elapsed.as_secs_f64() / 10.).min(100.)),
+        _ => None,
+      };
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct I915Card {
+  pub id: u64,
+  pub driver: String,
+  pub actual: Option<Hertz>,
+  pub requested: Option<Hertz>,
+  pub min: Option<Hertz>,
+  pub max: Option<Hertz>,
+  pub boost: Option<Hertz>,
+  pub min_limit: Option<Hertz>,
+  pub max_limit: Option<Hertz>,
+  pub optimum_limit: Option<Hertz>,
+  pub gts: Vec<I915Gt>,
+  pub power: Option<GpuPower>,
+}
+
+impl I915Card {
+
+  pub fn read(card_id: u64) -> Result<Self> {
+    let mut gts = vec![];
+    for gt_id in i915::gts(card_id)?.unwrap_or_default() { gts.push(I915Gt::read(card_id, gt_id)?); }
+    Ok(Self {
+      id: card_id,
+      driver: "i915".to_string(),
+      actual: i915::actual(card_id)?,
+      requested: i915::requested(card_id)?,
+      min: i915::min(card_id)?,
+      max: i915::max(card_id)?,
+      boost: i915::boost(card_id)?,
+      min_limit: i915::min_limit(card_id)?,
+      max_limit: i915::max_limit(card_id)?,
+      optimum_limit: i915::optimum_limit(card_id)?,
+      gts,
+      power: GpuPower::read(card_id)?,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct XeGt {
+  pub tile_id: u64,
+  pub id: u64,
+  pub actual: Option<Hertz>,
+  pub requested: Option<Hertz>,
+  pub min: Option<Hertz>,
+  pub max: Option<Hertz>,
+  pub min_limit: Option<Hertz>,
+  pub max_limit: Option<Hertz>,
+  pub optimum_limit: Option<Hertz>,
+}
+
+impl XeGt {
+
+  pub fn read(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Self> {
+    Ok(Self {
+      tile_id,
+      id: gt_id,
+      actual: xe::actual(card_id, tile_id, gt_id)?,
+      requested: xe::requested(card_id, tile_id, gt_id)?,
+      min: xe::min(card_id, tile_id, gt_id)?,
+      max: xe::max(card_id, tile_id, gt_id)?,
+      min_limit: xe::min_limit(card_id, tile_id, gt_id)?,
+      max_limit: xe::max_limit(card_id, tile_id, gt_id)?,
+      optimum_limit: xe::optimum_limit(card_id, tile_id, gt_id)?,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct XeCard {
+  pub id: u64,
+  pub driver: String,
+  pub gts: Vec<XeGt>,
+  pub power: Option<GpuPower>,
+}
+
+impl XeCard {
+
+  pub fn read(card_id: u64) -> Result<Self> {
+    let mut gts = vec![];
+    for tile_id in xe::tiles(card_id)?.unwrap_or_default() {
+      for gt_id in xe::gts(card_id, tile_id)?.unwrap_or_default() { gts.push(XeGt::read(card_id, tile_id, gt_id)?); }
+    }
+    Ok(Self {
+      id: card_id,
+      driver: "xe".to_string(),
+      gts,
+      power: GpuPower::read(card_id)?,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AmdgpuCard {
+  pub id: u64,
+  pub driver: String,
+  pub performance_level: Option<String>,
+  pub power_profile: Option<String>,
+  pub power_profiles: Option<Vec<PowerProfile>>,
+  pub sclk: Option<Hertz>,
+  pub mclk: Option<Hertz>,
+  pub sclk_levels: Option<Vec<DpmLevel>>,
+  pub mclk_levels: Option<Vec<DpmLevel>>,
+  pub od: Option<OdTable>,
+  pub power: Option<GpuPower>,
+}
+
+impl AmdgpuCard {
+
+  pub fn read(card_id: u64) -> Result<Self> {
+    let power_profiles = amdgpu::power_profiles(card_id)?;
+    Ok(Self {
+      id: card_id,
+      driver: "amdgpu".to_string(),
+      performance_level: amdgpu::performance_level(card_id)?,
+      power_profile: power_profiles.as_ref().and_then(|p| p.iter().find(|p| p.active)).map(|p| p.name.clone()),
+      power_profiles,
+      sclk: amdgpu::sclk(card_id)?,
+      mclk: amdgpu::mclk(card_id)?,
+      sclk_levels: amdgpu::sclk_levels(card_id)?,
+      mclk_levels: amdgpu::mclk_levels(card_id)?,
+      od: amdgpu::od_table(card_id)?,
+      power: GpuPower::read(card_id)?,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DevfreqDevice {
+  pub name: String,
+  pub cur: Option<Hertz>,
+  pub min: Option<Hertz>,
+  pub max: Option<Hertz>,
+  pub frequencies: Option<Vec<Hertz>>,
+  pub governor: Option<String>,
+  pub governors: Option<Vec<String>>,
+  pub trans_stat: Option<TransStat>,
+}
+
+impl DevfreqDevice {
+
+  pub fn read(device: &str) -> Result<Self> {
+    Ok(Self {
+      name: device.to_string(),
+      cur: devfreq::cur(device)?,
+      min: devfreq::min(device)?,
+      max: devfreq::max(device)?,
+      frequencies: devfreq::available_frequencies(device)?,
+      governor: devfreq::governor(device)?,
+      governors: devfreq::available_governors(device)?,
+      trans_stat: devfreq::trans_stat(device)?,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HwmonTemp {
+  pub id: u64,
+  pub label: Option<String>,
+  pub input: Option<Celsius>,
+  pub max: Option<Celsius>,
+  pub crit: Option<Celsius>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HwmonFan {
+  pub id: u64,
+  pub input: Option<Rpm>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HwmonVoltage {
+  pub id: u64,
+  pub input: Option<Volts>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HwmonPwm {
+  pub id: u64,
+  pub mode: Option<PwmMode>,
+  pub value: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HwmonChip {
+  pub id: u64,
+  pub name: Option<String>,
+  pub temps: Vec<HwmonTemp>,
+  pub fans: Vec<HwmonFan>,
+  pub voltages: Vec<HwmonVoltage>,
+  pub pwms: Vec<HwmonPwm>,
+}
+
+impl HwmonChip {
+
+  pub fn read(chip_id: u64) -> Result<Self> {
+    let mut temps = vec![];
+    for temp_id in hwmon::temps(chip_id)?.unwrap_or_default() {
+      temps.push(HwmonTemp {
+        id: temp_id,
+        label: hwmon::temp_label(chip_id, temp_id)?,
+        input: hwmon::temp(chip_id, temp_id)?,
+        max: hwmon::temp_max(chip_id, temp_id)?,
+        crit: hwmon::temp_crit(chip_id, temp_id)?,
+      });
+    }
+    let mut fans = vec![];
+    for fan_id in hwmon::fans(chip_id)?.unwrap_or_default() {
+      fans.push(HwmonFan {
+        id: fan_id,
+        input: hwmon::fan(chip_id, fan_id)?,
+      });
+    }
+    let mut voltages = vec![];
+    for in_id in hwmon::voltages(chip_id)?.unwrap_or_default() {
+      voltages.push(HwmonVoltage {
+        id: in_id,
+        input: hwmon::voltage(chip_id, in_id)?,
+      });
+    }
+    let mut pwms = vec![];
+    for pwm_id in hwmon::pwms(chip_id)?.unwrap_or_default() {
+      pwms.push(HwmonPwm {
+        id: pwm_id,
+        mode: hwmon::pwm_mode(chip_id, pwm_id)?,
+        value: hwmon::pwm(chip_id, pwm_id)?,
+      });
+    }
+    Ok(Self {
+      id: chip_id,
+      name: hwmon::name(chip_id)?,
+      temps,
+      fans,
+      voltages,
+      pwms,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RaplConstraint {
+  pub id: u64,
+  pub name: Option<String>,
+  pub power_limit: Option<Watts>,
+  pub time_window_us: Option<u64>,
+  pub max_power: Option<Watts>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RaplZone {
+  pub id: ZoneId,
+  pub name: Option<String>,
+  pub enabled: Option<bool>,
+  pub energy_uj: Option<u64>,
+  pub max_energy_range_uj: Option<u64>,
+  pub power: Option<Watts>,
+  pub constraints: Vec<RaplConstraint>,
+}
+
+impl RaplZone {
+
+  pub fn read(zone_id: ZoneId) -> Result<Self> {
+    let mut constraints = vec![];
+    for constraint_id in powercap::constraints(zone_id)?.unwrap_or_default() {
+      constraints.push(RaplConstraint {
+        id: constraint_id,
+        name: powercap::constraint_name(zone_id, constraint_id)?,
+        power_limit: powercap::power_limit(zone_id, constraint_id)?,
+        time_window_us: powercap::time_window_us(zone_id, constraint_id)?,
+        max_power: powercap::max_power(zone_id, constraint_id)?,
+      });
+    }
+    Ok(Self {
+      id: zone_id,
+      name: powercap::name(zone_id)?,
+      enabled: powercap::enabled(zone_id)?,
+      energy_uj: powercap::energy_uj(zone_id)?,
+      max_energy_range_uj: powercap::max_energy_range_uj(zone_id)?,
+      power: None,
+      constraints,
+    })
+  }
+
+  // Average power since `prev` was read, `elapsed` ago.
+  pub fn set_power(&mut self, prev: &RaplZone, elapsed: Duration) {
+    self.power =
+      match (prev.energy_uj, self.energy_uj) {
+        (Some(prev_uj), Some(cur_uj)) if elapsed > Duration::from_millis(0) =>
+          powercap::energy_delta_uj(prev_uj, cur_uj, self.max_energy_range_uj)
+            .map(|uj| Watts::from_microwatts((uj as f64 / elapsed.as_secs_f64()) as u64)),
+        _ => None,
+      };
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Summary {
+  pub cpus: Vec<Cpu>,
+  pub policies: Option<Vec<Policy>>,
+  pub intel_pstate: Option<IntelPstate>,
+  pub amd_pstate: Option<AmdPstate>,
+  pub cpuidle: Option<Cpuidle>,
+  pub gpus: Option<Vec<Gpu>>,
+  pub i915: Option<Vec<I915Card>>,
+  pub xe: Option<Vec<XeCard>>,
+  pub amdgpu: Option<Vec<AmdgpuCard>>,
+  pub devfreq: Option<Vec<DevfreqDevice>>,
+  pub hwmon: Option<Vec<HwmonChip>>,
+  pub rapl: Option<Vec<RaplZone>>,
+}
+
+impl Summary {
+
+  pub fn read() -> Result<Self> {
+    Self::read_selected(&Select::all())
+  }
+
+  pub fn read_selected(select: &Select) -> Result<Self> {
+    let sensors = if select.hwmon { Some(hwmon::CpuSensors::read()?) } else { None };
+    let mut cpus = vec![];
+    for cpu_id in cpu::cpus()? { cpus.push(Cpu::read_selected(cpu_id, select, sensors.as_ref())?); }
+    let policies =
+      if select.cpufreq && cpufreq::available() {
+        let mut policies = vec![];
+        for policy_id in cpufreq::policies()?.unwrap_or_default() { policies.push(Policy::read(policy_id)?); }
+        Some(policies)
+      } else { None };
+    let intel_pstate = if select.intel_pstate && pstate::available() { Some(IntelPstate::read()?) } else { None };
+    let amd_pstate = if select.amd_pstate && amd_pstate::available() { Some(AmdPstate::read()?) } else { None };
+    let cpuidle = if select.cpuidle && cpuidle::available() { Some(Cpuidle::read()?) } else { None };
+    let card_ids = if select.gpus { drm::cards()? } else { None };
+    let gpus =
+      match card_ids {
+        Some(mut card_ids) if ! card_ids.is_empty() => {
+          card_ids.sort_unstable();
+          let mut cards = vec![];
+          for card_id in card_ids { cards.push(Gpu::read(card_id)?); }
+          Some(cards)
+        },
+        _ => None,
+      };
+    let i915 =
+      if select.i915 && i915::available() {
+        let mut cards = vec![];
+        for card_id in i915::cards()?.unwrap_or_default() { cards.push(I915Card::read(card_id)?); }
+        Some(cards)
+      } else { None };
+    let xe =
+      if select.xe && xe::available() {
+        let mut cards = vec![];
+        for card_id in xe::cards()?.unwrap_or_default() { cards.push(XeCard::read(card_id)?); }
+        Some(cards)
+      } else { None };
+    let amdgpu =
+      if select.amdgpu && amdgpu::available() {
+        let mut cards = vec![];
+        for card_id in amdgpu::cards()?.unwrap_or_default() { cards.push(AmdgpuCard::read(card_id)?); }
+        Some(cards)
+      } else { None };
+    let devfreq =
+      if select.devfreq && devfreq::available() {
+        let mut devices = vec![];
+        for device in devfreq::devices()?.unwrap_or_default() { devices.push(DevfreqDevice::read(&device)?); }
+        Some(devices)
+      } else { None };
+    let hwmon =
+      if select.hwmon && hwmon::available() {
+        let mut chips = vec![];
+        for chip_id in hwmon::chips()?.unwrap_or_default() { chips.push(HwmonChip::read(chip_id)?); }
+        Some(chips)
+      } else { None };
+    let rapl =
+      if select.rapl && powercap::available() {
+        let mut zones = vec![];
+        for zone_id in powercap::zones()?.unwrap_or_default() { zones.push(RaplZone::read(zone_id)?); }
+        Some(zones)
+      } else { None };
+    Ok(Self {
+      cpus,
+      policies,
+      intel_pstate,
+      amd_pstate,
+      cpuidle,
+      gpus,
+      i915,
+      xe,
+      amdgpu,
+      devfreq,
+      hwmon,
+      rapl,
+    })
+  }
+
+  // Fills in values computed from two samples, e.g. RAPL power from energy counters.
+  pub fn set_deltas(&mut self, prev: &Summary, elapsed: Duration) {
+    if let (Some(cards), Some(prev_cards)) = (self.i915.as_mut(), prev.i915.as_ref()) {
+      for card in cards {
+        let prev_card = if let Some(prev_card) = prev_cards.iter().find(|c| c.id == card.id) { prev_card } else { continue; };
+        for gt in &mut card.gts {
+          if let Some(prev_gt) = prev_card.gts.iter().find(|g| g.id == gt.id) { gt.set_rc6(prev_gt, elapsed); }
+        }
+      }
+    }
+    if let (Some(zones), Some(prev_zones)) = (self.rapl.as_mut(), prev.rapl.as_ref()) {
+      for zone in zones {
+        if let Some(prev_zone) = prev_zones.iter().find(|z| z.id == zone.id) { zone.set_power(prev_zone, elapsed); }
+      }
+    }
+  }
+}
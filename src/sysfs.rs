@@ -1,17 +1,119 @@
 use {
-  std::path::PathBuf,
+  crate::powercap::ZoneId,
+  std::{
+    path::{Path, PathBuf},
+    sync::RwLock,
+  },
 };
 
+static ROOT: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+pub fn root() -> PathBuf {
+  ROOT.read().unwrap_or_else(|e| e.into_inner()).clone().unwrap_or_else(|| PathBuf::from("/"))
+}
+
+pub fn set_root<P: AsRef<Path>>(path: P) {
+  *ROOT.write().unwrap_or_else(|e| e.into_inner()) = Some(path.as_ref().to_path_buf());
+}
+
+fn rooted(path: &str) -> PathBuf {
+  let mut p = root();
+  p.push(path);
+  p
+}
+
 pub fn cpu(cpu_id: u64) -> PathBuf {
-  PathBuf::from(format!("/sys/devices/system/cpu/cpu{}", cpu_id))
+  rooted(&format!("sys/devices/system/cpu/cpu{}", cpu_id))
 }
 
 pub fn cpu_present() -> PathBuf {
-  PathBuf::from("/sys/devices/system/cpu/present")
+  rooted("sys/devices/system/cpu/present")
 }
 
 pub fn cpu_cpufreq() -> PathBuf {
-  PathBuf::from("/sys/devices/system/cpu/cpufreq")
+  rooted("sys/devices/system/cpu/cpufreq")
+}
+
+pub fn cpu_cpuidle() -> PathBuf {
+  rooted("sys/devices/system/cpu/cpuidle")
+}
+
+pub fn cpuidle_current_driver() -> PathBuf {
+  let mut p = cpu_cpuidle();
+  p.push("current_driver");
+  p
+}
+
+pub fn cpuidle_current_governor() -> PathBuf {
+  let mut p = cpu_cpuidle();
+  p.push("current_governor");
+  p
+}
+
+pub fn cpuidle_current_governor_ro() -> PathBuf {
+  let mut p = cpu_cpuidle();
+  p.push("current_governor_ro");
+  p
+}
+
+pub fn cpuidle_available_governors() -> PathBuf {
+  let mut p = cpu_cpuidle();
+  p.push("available_governors");
+  p
+}
+
+pub fn cpuidle(cpu_id: u64) -> PathBuf {
+  let mut p = cpu(cpu_id);
+  p.push("cpuidle");
+  p
+}
+
+pub fn cpuidle_state(cpu_id: u64, state_id: u64) -> PathBuf {
+  let mut p = cpuidle(cpu_id);
+  p.push(format!("state{}", state_id));
+  p
+}
+
+pub fn cpuidle_state_name(cpu_id: u64, state_id: u64) -> PathBuf {
+  let mut p = cpuidle_state(cpu_id, state_id);
+  p.push("name");
+  p
+}
+
+pub fn cpuidle_state_desc(cpu_id: u64, state_id: u64) -> PathBuf {
+  let mut p = cpuidle_state(cpu_id, state_id);
+  p.push("desc");
+  p
+}
+
+pub fn cpuidle_state_latency(cpu_id: u64, state_id: u64) -> PathBuf {
+  let mut p = cpuidle_state(cpu_id, state_id);
+  p.push("latency");
+  p
+}
+
+pub fn cpuidle_state_residency(cpu_id: u64, state_id: u64) -> PathBuf {
+  let mut p = cpuidle_state(cpu_id, state_id);
+  p.push("residency");
+  p
+}
+
+pub fn cpuidle_state_usage(cpu_id: u64, state_id: u64) -> PathBuf {
+  let mut p = cpuidle_state(cpu_id, state_id);
+  p.push("usage");
+  p
+}
+
+pub fn cpuidle_state_time(cpu_id: u64, state_id: u64) -> PathBuf {
+  let mut p = cpuidle_state(cpu_id, state_id);
+  p.push("time");
+  p
+}
+
+pub fn cpuidle_state_disable(cpu_id: u64, state_id: u64) -> PathBuf {
+  let mut p = cpuidle_state(cpu_id, state_id);
+  p.push("disable");
+  p
 }
 
 pub fn cpufreq(cpu_id: u64) -> PathBuf {
@@ -20,6 +122,108 @@ pub fn cpufreq(cpu_id: u64) -> PathBuf {
   p
 }
 
+pub fn cpufreq_boost() -> PathBuf {
+  let mut p = cpu_cpufreq();
+  p.push("boost");
+  p
+}
+
+pub fn cpufreq_policy(policy_id: u64) -> PathBuf {
+  let mut p = cpu_cpufreq();
+  p.push(format!("policy{}", policy_id));
+  p
+}
+
+pub fn cpufreq_policy_affected_cpus(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("affected_cpus");
+  p
+}
+
+pub fn cpufreq_policy_related_cpus(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("related_cpus");
+  p
+}
+
+pub fn cpufreq_policy_boost(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("boost");
+  p
+}
+
+pub fn cpufreq_policy_base_khz(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("base_frequency");
+  p
+}
+
+pub fn cpufreq_policy_bios_limit_khz(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("bios_limit");
+  p
+}
+
+pub fn cpufreq_policy_cpuinfo_cur_khz(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("cpuinfo_cur_freq");
+  p
+}
+
+pub fn cpufreq_policy_driver(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("scaling_driver");
+  p
+}
+
+pub fn cpufreq_policy_transition_latency(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("cpuinfo_transition_latency");
+  p
+}
+
+pub fn cpufreq_policy_cur_khz(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("scaling_cur_freq");
+  p
+}
+
+pub fn cpufreq_policy_governor(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("scaling_governor");
+  p
+}
+
+pub fn cpufreq_policy_governors(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("scaling_available_governors");
+  p
+}
+
+pub fn cpufreq_policy_max_khz(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("scaling_max_freq");
+  p
+}
+
+pub fn cpufreq_policy_max_khz_limit(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("cpuinfo_max_freq");
+  p
+}
+
+pub fn cpufreq_policy_min_khz(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("scaling_min_freq");
+  p
+}
+
+pub fn cpufreq_policy_min_khz_limit(policy_id: u64) -> PathBuf {
+  let mut p = cpufreq_policy(policy_id);
+  p.push("cpuinfo_min_freq");
+  p
+}
+
 pub fn cpufreq_cur_khz(cpu_id: u64) -> PathBuf {
   let mut p = cpufreq(cpu_id);
   p.push("scaling_cur_freq");
@@ -69,7 +273,7 @@ pub fn cpu_online(cpu_id: u64) -> PathBuf {
 }
 
 pub fn intel_pstate() -> PathBuf {
-  PathBuf::from("/sys/devices/system/cpu/intel_pstate")
+  rooted("sys/devices/system/cpu/intel_pstate")
 }
 
 pub fn intel_pstate_epb(cpu_id: u64) -> PathBuf {
@@ -97,8 +301,96 @@ pub fn intel_pstate_status() -> PathBuf {
   p
 }
 
+pub fn intel_pstate_no_turbo() -> PathBuf {
+  let mut p = intel_pstate();
+  p.push("no_turbo");
+  p
+}
+
+pub fn intel_pstate_max_perf_pct() -> PathBuf {
+  let mut p = intel_pstate();
+  p.push("max_perf_pct");
+  p
+}
+
+pub fn intel_pstate_min_perf_pct() -> PathBuf {
+  let mut p = intel_pstate();
+  p.push("min_perf_pct");
+  p
+}
+
+pub fn intel_pstate_turbo_pct() -> PathBuf {
+  let mut p = intel_pstate();
+  p.push("turbo_pct");
+  p
+}
+
+pub fn intel_pstate_num_pstates() -> PathBuf {
+  let mut p = intel_pstate();
+  p.push("num_pstates");
+  p
+}
+
+pub fn intel_pstate_hwp_dynamic_boost() -> PathBuf {
+  let mut p = intel_pstate();
+  p.push("hwp_dynamic_boost");
+  p
+}
+
+pub fn amd_pstate() -> PathBuf {
+  rooted("sys/devices/system/cpu/amd_pstate")
+}
+
+pub fn amd_pstate_status() -> PathBuf {
+  let mut p = amd_pstate();
+  p.push("status");
+  p
+}
+
+pub fn amd_pstate_prefcore() -> PathBuf {
+  let mut p = amd_pstate();
+  p.push("prefcore");
+  p
+}
+
+pub fn amd_pstate_epp(cpu_id: u64) -> PathBuf {
+  let mut p = cpufreq(cpu_id);
+  p.push("energy_performance_preference");
+  p
+}
+
+pub fn amd_pstate_epps(cpu_id: u64) -> PathBuf {
+  let mut p = cpufreq(cpu_id);
+  p.push("energy_performance_available_preferences");
+  p
+}
+
+pub fn amd_pstate_highest_perf(cpu_id: u64) -> PathBuf {
+  let mut p = cpufreq(cpu_id);
+  p.push("amd_pstate_highest_perf");
+  p
+}
+
+pub fn amd_pstate_lowest_nonlinear_freq(cpu_id: u64) -> PathBuf {
+  let mut p = cpufreq(cpu_id);
+  p.push("amd_pstate_lowest_nonlinear_freq");
+  p
+}
+
+pub fn amd_pstate_max_freq(cpu_id: u64) -> PathBuf {
+  let mut p = cpufreq(cpu_id);
+  p.push("amd_pstate_max_freq");
+  p
+}
+
+pub fn amd_pstate_prefcore_ranking(cpu_id: u64) -> PathBuf {
+  let mut p = cpufreq(cpu_id);
+  p.push("amd_pstate_prefcore_ranking");
+  p
+}
+
 pub fn drm() -> PathBuf {
-  PathBuf::from("/sys/class/drm")
+  rooted("sys/class/drm")
 }
 
 pub fn drm_card(card_id: u64) -> PathBuf {
@@ -114,8 +406,90 @@ pub fn drm_card_driver(card_id: u64) -> PathBuf {
   p
 }
 
+pub fn drm_card_device(card_id: u64) -> PathBuf {
+  let mut p = drm_card(card_id);
+  p.push("device");
+  p
+}
+
+pub fn drm_card_vendor(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("vendor");
+  p
+}
+
+pub fn drm_card_device_id(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("device");
+  p
+}
+
+pub fn drm_card_subsystem_vendor(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("subsystem_vendor");
+  p
+}
+
+pub fn drm_card_subsystem_device(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("subsystem_device");
+  p
+}
+
+pub fn drm_card_boot_vga(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("boot_vga");
+  p
+}
+
+pub fn drm_card_device_drm(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("drm");
+  p
+}
+
+pub fn amdgpu_module() -> PathBuf {
+  rooted("sys/module/amdgpu")
+}
+
+pub fn amdgpu_performance_level(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("power_dpm_force_performance_level");
+  p
+}
+
+pub fn amdgpu_power_profile_mode(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("pp_power_profile_mode");
+  p
+}
+
+pub fn amdgpu_dpm_sclk(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("pp_dpm_sclk");
+  p
+}
+
+pub fn amdgpu_dpm_mclk(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("pp_dpm_mclk");
+  p
+}
+
+pub fn drm_card_hwmon(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("hwmon");
+  p
+}
+
+pub fn amdgpu_od_clk_voltage(card_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push("pp_od_clk_voltage");
+  p
+}
+
 pub fn i915_module() -> PathBuf {
-  PathBuf::from("/sys/module/i915")
+  rooted("sys/module/i915")
 }
 
 pub fn i915_rp0_mhz(card_id: u64) -> PathBuf {
@@ -165,3 +539,369 @@ pub fn i915_min_mhz(card_id: u64) -> PathBuf {
   p.push("gt_min_freq_mhz");
   p
 }
+
+pub fn i915_gts(card_id: u64) -> PathBuf {
+  let mut p = drm_card(card_id);
+  p.push("gt");
+  p
+}
+
+pub fn i915_gt(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gts(card_id);
+  p.push(format!("gt{}", gt_id));
+  p
+}
+
+pub fn i915_gt_rp0_mhz(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push("rps_RP0_freq_mhz");
+  p
+}
+
+pub fn i915_gt_rp1_mhz(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push("rps_RP1_freq_mhz");
+  p
+}
+
+pub fn i915_gt_rpn_mhz(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push("rps_RPn_freq_mhz");
+  p
+}
+
+pub fn i915_gt_act_mhz(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push("rps_act_freq_mhz");
+  p
+}
+
+pub fn i915_gt_boost_mhz(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push("rps_boost_freq_mhz");
+  p
+}
+
+pub fn i915_gt_cur_mhz(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push("rps_cur_freq_mhz");
+  p
+}
+
+pub fn i915_gt_max_mhz(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push("rps_max_freq_mhz");
+  p
+}
+
+pub fn i915_gt_min_mhz(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push("rps_min_freq_mhz");
+  p
+}
+
+pub fn i915_gt_rc6_enable(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push("rc6_enable");
+  p
+}
+
+pub fn i915_gt_rc6_residency_ms(card_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push("rc6_residency_ms");
+  p
+}
+
+pub fn i915_gt_throttle_reason(card_id: u64, gt_id: u64, reason: &str) -> PathBuf {
+  let mut p = i915_gt(card_id, gt_id);
+  p.push(format!("throttle_reason_{}", reason));
+  p
+}
+
+pub fn xe_module() -> PathBuf {
+  rooted("sys/module/xe")
+}
+
+pub fn xe_tile(card_id: u64, tile_id: u64) -> PathBuf {
+  let mut p = drm_card_device(card_id);
+  p.push(format!("tile{}", tile_id));
+  p
+}
+
+pub fn xe_gt(card_id: u64, tile_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = xe_tile(card_id, tile_id);
+  p.push(format!("gt{}", gt_id));
+  p
+}
+
+pub fn xe_freq(card_id: u64, tile_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = xe_gt(card_id, tile_id, gt_id);
+  p.push("freq0");
+  p
+}
+
+pub fn xe_act_freq(card_id: u64, tile_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = xe_freq(card_id, tile_id, gt_id);
+  p.push("act_freq");
+  p
+}
+
+pub fn xe_cur_freq(card_id: u64, tile_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = xe_freq(card_id, tile_id, gt_id);
+  p.push("cur_freq");
+  p
+}
+
+pub fn xe_max_freq(card_id: u64, tile_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = xe_freq(card_id, tile_id, gt_id);
+  p.push("max_freq");
+  p
+}
+
+pub fn xe_min_freq(card_id: u64, tile_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = xe_freq(card_id, tile_id, gt_id);
+  p.push("min_freq");
+  p
+}
+
+pub fn xe_rp0_freq(card_id: u64, tile_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = xe_freq(card_id, tile_id, gt_id);
+  p.push("rp0_freq");
+  p
+}
+
+pub fn xe_rpe_freq(card_id: u64, tile_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = xe_freq(card_id, tile_id, gt_id);
+  p.push("rpe_freq");
+  p
+}
+
+pub fn xe_rpn_freq(card_id: u64, tile_id: u64, gt_id: u64) -> PathBuf {
+  let mut p = xe_freq(card_id, tile_id, gt_id);
+  p.push("rpn_freq");
+  p
+}
+
+pub fn cpu_core_id(cpu_id: u64) -> PathBuf {
+  let mut p = cpu(cpu_id);
+  p.push("topology");
+  p.push("core_id");
+  p
+}
+
+pub fn cpu_package_id(cpu_id: u64) -> PathBuf {
+  let mut p = cpu(cpu_id);
+  p.push("topology");
+  p.push("physical_package_id");
+  p
+}
+
+pub fn hwmon() -> PathBuf {
+  rooted("sys/class/hwmon")
+}
+
+pub fn hwmon_chip(chip_id: u64) -> PathBuf {
+  let mut p = hwmon();
+  p.push(format!("hwmon{}", chip_id));
+  p
+}
+
+pub fn hwmon_name(chip_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push("name");
+  p
+}
+
+pub fn hwmon_temp_input(chip_id: u64, temp_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push(format!("temp{}_input", temp_id));
+  p
+}
+
+pub fn hwmon_temp_label(chip_id: u64, temp_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push(format!("temp{}_label", temp_id));
+  p
+}
+
+pub fn hwmon_temp_crit(chip_id: u64, temp_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push(format!("temp{}_crit", temp_id));
+  p
+}
+
+pub fn hwmon_temp_max(chip_id: u64, temp_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push(format!("temp{}_max", temp_id));
+  p
+}
+
+pub fn hwmon_fan_input(chip_id: u64, fan_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push(format!("fan{}_input", fan_id));
+  p
+}
+
+pub fn hwmon_in_input(chip_id: u64, in_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push(format!("in{}_input", in_id));
+  p
+}
+
+pub fn hwmon_power_cap(chip_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push("power1_cap");
+  p
+}
+
+pub fn hwmon_power_cap_min(chip_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push("power1_cap_min");
+  p
+}
+
+pub fn hwmon_power_cap_max(chip_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push("power1_cap_max");
+  p
+}
+
+pub fn hwmon_power_max(chip_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push("power1_max");
+  p
+}
+
+pub fn hwmon_power_average(chip_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push("power1_average");
+  p
+}
+
+pub fn hwmon_power_input(chip_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push("power1_input");
+  p
+}
+
+pub fn hwmon_pwm(chip_id: u64, pwm_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push(format!("pwm{}", pwm_id));
+  p
+}
+
+pub fn hwmon_pwm_enable(chip_id: u64, pwm_id: u64) -> PathBuf {
+  let mut p = hwmon_chip(chip_id);
+  p.push(format!("pwm{}_enable", pwm_id));
+  p
+}
+
+pub fn powercap() -> PathBuf {
+  rooted("sys/class/powercap")
+}
+
+pub fn rapl_zone(zone_id: ZoneId) -> PathBuf {
+  let mut p = powercap();
+  p.push(zone_id.to_string());
+  p
+}
+
+pub fn rapl_name(zone_id: ZoneId) -> PathBuf {
+  let mut p = rapl_zone(zone_id);
+  p.push("name");
+  p
+}
+
+pub fn rapl_enabled(zone_id: ZoneId) -> PathBuf {
+  let mut p = rapl_zone(zone_id);
+  p.push("enabled");
+  p
+}
+
+pub fn rapl_energy_uj(zone_id: ZoneId) -> PathBuf {
+  let mut p = rapl_zone(zone_id);
+  p.push("energy_uj");
+  p
+}
+
+pub fn rapl_max_energy_range_uj(zone_id: ZoneId) -> PathBuf {
+  let mut p = rapl_zone(zone_id);
+  p.push("max_energy_range_uj");
+  p
+}
+
+pub fn rapl_constraint_name(zone_id: ZoneId, constraint_id: u64) -> PathBuf {
+  let mut p = rapl_zone(zone_id);
+  p.push(format!("constraint_{}_name", constraint_id));
+  p
+}
+
+pub fn rapl_constraint_power_limit_uw(zone_id: ZoneId, constraint_id: u64) -> PathBuf {
+  let mut p = rapl_zone(zone_id);
+  p.push(format!("constraint_{}_power_limit_uw", constraint_id));
+  p
+}
+
+pub fn rapl_constraint_time_window_us(zone_id: ZoneId, constraint_id: u64) -> PathBuf {
+  let mut p = rapl_zone(zone_id);
+  p.push(format!("constraint_{}_time_window_us", constraint_id));
+  p
+}
+
+pub fn rapl_constraint_max_power_uw(zone_id: ZoneId, constraint_id: u64) -> PathBuf {
+  let mut p = rapl_zone(zone_id);
+  p.push(format!("constraint_{}_max_power_uw", constraint_id));
+  p
+}
+
+pub fn devfreq() -> PathBuf {
+  rooted("sys/class/devfreq")
+}
+
+pub fn devfreq_device(device: &str) -> PathBuf {
+  let mut p = devfreq();
+  p.push(device);
+  p
+}
+
+pub fn devfreq_cur_freq(device: &str) -> PathBuf {
+  let mut p = devfreq_device(device);
+  p.push("cur_freq");
+  p
+}
+
+pub fn devfreq_min_freq(device: &str) -> PathBuf {
+  let mut p = devfreq_device(device);
+  p.push("min_freq");
+  p
+}
+
+pub fn devfreq_max_freq(device: &str) -> PathBuf {
+  let mut p = devfreq_device(device);
+  p.push("max_freq");
+  p
+}
+
+pub fn devfreq_available_frequencies(device: &str) -> PathBuf {
+  let mut p = devfreq_device(device);
+  p.push("available_frequencies");
+  p
+}
+
+pub fn devfreq_governor(device: &str) -> PathBuf {
+  let mut p = devfreq_device(device);
+  p.push("governor");
+  p
+}
+
+pub fn devfreq_available_governors(device: &str) -> PathBuf {
+  let mut p = devfreq_device(device);
+  p.push("available_governors");
+  p
+}
+
+pub fn devfreq_trans_stat(device: &str) -> PathBuf {
+  let mut p = devfreq_device(device);
+  p.push("trans_stat");
+  p
+}
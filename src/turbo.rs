@@ -0,0 +1,46 @@
+use {
+  crate::{
+    cpufreq,
+    intel_pstate,
+    sysfs,
+  },
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+
+  #[error(transparent)] CpuxCpufreq(#[from] crate::cpufreq::Error),
+  #[error(transparent)] CpuxIntelPstate(#[from] crate::intel_pstate::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// `no_turbo` stays in sysfs while intel_pstate is off, but another driver, e.g. acpi-cpufreq,
+// then handles turbo through `boost`.
+fn intel_pstate_active() -> Result<bool> {
+  if ! sysfs::intel_pstate_no_turbo().is_file() { return Ok(false); }
+  Ok(intel_pstate::status()?.as_deref() != Some("off"))
+}
+
+// Whether the cpu may run above its base frequency, from `intel_pstate/no_turbo`,
+// `cpufreq/boost` or the cpu's policy `boost`, whichever the system has first. intel_pstate is
+// skipped while it is off.
+pub fn turbo(cpu_id: u64) -> Result<Option<bool>> {
+  if intel_pstate_active()? { return Ok(intel_pstate::no_turbo()?.map(|v| ! v)); }
+  if sysfs::cpufreq_boost().is_file() { return Ok(cpufreq::boost()?); }
+  let policy_id = if let Some(policy_id) = cpufreq::policy(cpu_id)? { policy_id } else { return Ok(None); };
+  Ok(cpufreq::policy_boost(policy_id)?)
+}
+
+// Switches turbo for all cpus through the same attribute `turbo` reads. Per-policy `boost` is
+// written once per active policy, inactive policies refuse writes.
+pub fn set_turbo(val: bool) -> Result<Option<()>> {
+  if intel_pstate_active()? { return Ok(intel_pstate::set_no_turbo(! val)?); }
+  if sysfs::cpufreq_boost().is_file() { return Ok(cpufreq::set_boost(val)?); }
+  let mut res = None;
+  for policy_id in cpufreq::policies()?.unwrap_or_default() {
+    if ! cpufreq::policy_active(policy_id)?.unwrap_or(false) { continue; }
+    if cpufreq::policy_boost(policy_id)?.is_some() { res = cpufreq::set_policy_boost(policy_id, val)?; }
+  }
+  Ok(res)
+}
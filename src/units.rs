@@ -3,11 +3,18 @@ pub enum Error {
 
   #[error("Error parsing frequency string: {0}")]
   ParseHertz(String),
+
+  #[error("Error parsing power string: {0}")]
+  ParseWatts(String),
+
+  #[error("Error parsing voltage string: {0}")]
+  ParseVolts(String),
 }
 
 type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Clone, Debug)]
+#[repr(u64)]
 pub enum HertzUnit {
   Hz = 1,
   Khz = 1_000,
@@ -46,7 +53,7 @@ impl Hertz {
 
   pub fn is_thz(&self) -> bool { self.0 >= HertzUnit::Khz.multiple() }
 
-  pub fn hz(&self) -> u64 { self.0.clone() }
+  pub fn hz(&self) -> u64 { self.0 }
 
   pub fn khz(&self) -> f64 { self.0 as f64 / HertzUnit::Khz.multiple() as f64 }
 
@@ -63,12 +70,18 @@ impl From<Hertz> for String { fn from(hz: Hertz) -> Self { hz.to_string() } }
 
 impl AsRef<Hertz> for Hertz { fn as_ref(&self) -> &Hertz { self } }
 
+impl serde::Serialize for Hertz {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(self.0)
+  }
+}
+
 impl std::str::FromStr for Hertz {
   type Err = Error;
 
   fn from_str(s: &str) -> Result<Self> {
     let unit =
-      match &(&s[s.len()-3..].to_lowercase())[..] {
+      match &s.get(s.len().saturating_sub(3)..).unwrap_or_default().to_lowercase()[..] {
         "khz" => HertzUnit::Khz,
         "mhz" => HertzUnit::Mhz,
         "ghz" => HertzUnit::Ghz,
@@ -95,3 +108,158 @@ impl std::fmt::Display for Hertz {
     write!(f, "{}", val)
   }
 }
+
+#[derive(Clone, Debug)]
+pub struct Celsius(i64);
+
+impl Celsius {
+
+  pub fn from_millicelsius(mc: i64) -> Self { Self(mc) }
+
+  pub fn from_celsius(c: f64) -> Self { Self((c * 1_000.) as i64) }
+
+  pub fn millicelsius(&self) -> i64 { self.0 }
+
+  pub fn celsius(&self) -> f64 { self.0 as f64 / 1_000. }
+}
+
+impl From<Celsius> for String { fn from(c: Celsius) -> Self { c.to_string() } }
+
+impl AsRef<Celsius> for Celsius { fn as_ref(&self) -> &Celsius { self } }
+
+impl serde::Serialize for Celsius {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(self.celsius())
+  }
+}
+
+impl std::fmt::Display for Celsius {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:.1} °C", self.celsius())
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rpm(u64);
+
+impl Rpm {
+
+  pub fn new(rpm: u64) -> Self { Self(rpm) }
+
+  pub fn rpm(&self) -> u64 { self.0 }
+}
+
+impl From<u64> for Rpm { fn from(rpm: u64) -> Self { Self::new(rpm) } }
+
+impl From<Rpm> for String { fn from(rpm: Rpm) -> Self { rpm.to_string() } }
+
+impl AsRef<Rpm> for Rpm { fn as_ref(&self) -> &Rpm { self } }
+
+impl serde::Serialize for Rpm {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(self.0)
+  }
+}
+
+impl std::fmt::Display for Rpm {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} RPM", self.0)
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Volts(i64);
+
+impl Volts {
+
+  pub fn from_millivolts(mv: i64) -> Self { Self(mv) }
+
+  pub fn from_volts(v: f64) -> Self { Self((v * 1_000.) as i64) }
+
+  pub fn millivolts(&self) -> i64 { self.0 }
+
+  pub fn volts(&self) -> f64 { self.0 as f64 / 1_000. }
+}
+
+impl From<Volts> for String { fn from(v: Volts) -> Self { v.to_string() } }
+
+impl AsRef<Volts> for Volts { fn as_ref(&self) -> &Volts { self } }
+
+impl serde::Serialize for Volts {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(self.volts())
+  }
+}
+
+impl std::str::FromStr for Volts {
+  type Err = Error;
+
+  // Volts unless suffixed, e.g. 1.1, 1.1v, 1100mv, 1100mV.
+  fn from_str(s: &str) -> Result<Self> {
+    let lower = s.trim().to_lowercase();
+    let (val, mul) =
+      if let Some(val) = lower.strip_suffix("mv") { (val, 1.) }
+      else if let Some(val) = lower.strip_suffix('v') { (val, 1_000.) }
+      else { (&lower[..], 1_000.) };
+    let val = val.trim().parse::<f64>().map_err(|e| Error::ParseVolts(s.to_string()))?;
+    if ! val.is_finite() { return Err(Error::ParseVolts(s.to_string())); }
+    Ok(Self((val * mul).round() as i64))
+  }
+}
+
+impl std::fmt::Display for Volts {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:.3} V", self.volts())
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Watts(u64);
+
+impl Watts {
+
+  pub fn from_microwatts(uw: u64) -> Self { Self(uw) }
+
+  pub fn from_milliwatts(mw: f64) -> Self { Self((mw * 1_000.) as u64) }
+
+  pub fn from_watts(w: f64) -> Self { Self((w * 1_000_000.) as u64) }
+
+  pub fn microwatts(&self) -> u64 { self.0 }
+
+  pub fn milliwatts(&self) -> f64 { self.0 as f64 / 1_000. }
+
+  pub fn watts(&self) -> f64 { self.0 as f64 / 1_000_000. }
+}
+
+impl From<Watts> for String { fn from(w: Watts) -> Self { w.to_string() } }
+
+impl AsRef<Watts> for Watts { fn as_ref(&self) -> &Watts { self } }
+
+impl serde::Serialize for Watts {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(self.watts())
+  }
+}
+
+impl std::str::FromStr for Watts {
+  type Err = Error;
+
+  // Watts unless suffixed, e.g. 15, 15w, 28.5W, 800mw, 15000000uw.
+  fn from_str(s: &str) -> Result<Self> {
+    let lower = s.trim().to_lowercase();
+    let (val, mul) =
+      if let Some(val) = lower.strip_suffix("uw") { (val, 1.) }
+      else if let Some(val) = lower.strip_suffix("mw") { (val, 1_000.) }
+      else if let Some(val) = lower.strip_suffix('w') { (val, 1_000_000.) }
+      else { (&lower[..], 1_000_000.) };
+    let val = val.trim().parse::<f64>().map_err(|e| Error::ParseWatts(s.to_string()))?;
+    if ! val.is_finite() || val < 0. { return Err(Error::ParseWatts(s.to_string())); }
+    Ok(Self((val * mul).round() as u64))
+  }
+}
+
+impl std::fmt::Display for Watts {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:.1} W", self.watts())
+  }
+}
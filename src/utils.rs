@@ -1,9 +1,13 @@
+#[allow(clippy::enum_variant_names)]
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
 
   #[error("Error parsing frequency string: {0}")]
   ParseHertz(String),
 
+  #[error("Error parsing cards string: {0}")]
+  ParseCards(String),
+
   #[error("Error parsing indices string: {0}")]
   ParseIndices(String),
   
@@ -22,13 +26,32 @@ impl Indices {
 
   pub fn dedup(&mut self) { self.0.dedup(); }
 
-  pub fn iter(&self) -> IndicesIter { IndicesIter(Box::new(self.0.iter())) }
+  pub fn iter(&self) -> IndicesIter<'_> { IndicesIter(Box::new(self.0.iter())) }
 
   pub fn sort(&mut self) { self.0.sort(); }
 
+  #[allow(clippy::wrong_self_convention)]
   pub fn to_vec(self) -> Vec<u64> { self.0 }
 }
 
+// Formats as ranges where possible, e.g. `0-3,6`.
+impl std::fmt::Display for Indices {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let mut ranges: Vec<(u64, u64)> = vec![];
+    for id in self.0.iter() {
+      match ranges.last_mut() {
+        Some((_, end)) if *end + 1 == *id => *end = *id,
+        _ => ranges.push((*id, *id)),
+      }
+    }
+    let ranges: Vec<String> = ranges
+      .into_iter()
+      .map(|(start, end)| if start == end { start.to_string() } else { format!("{}-{}", start, end) })
+      .collect();
+    write!(f, "{}", ranges.join(","))
+  }
+}
+
 pub struct IndicesIter<'a>(Box<dyn Iterator<Item=&'a u64> + 'a>);
 
 impl<'a> Iterator for IndicesIter<'a> {
@@ -51,13 +74,13 @@ impl std::str::FromStr for Indices {
     let mut ids: Vec<u64> = vec![];
     for part in s.split(',') {
       let val: Vec<&str> = part.split('-').collect();
-      match &val[..] {
-        &[id] =>
+      match val[..] {
+        [id] =>
           match id.parse::<u64>() {
             Ok(val) => ids.push(val),
             Err(_) => return Err(Error::ParseIndices(s.to_string())),
           },
-        &[first, last] =>
+        [first, last] =>
           std::ops::Range {
             start:
               match first.parse::<u64>() {
@@ -82,7 +105,7 @@ pub struct Toggles(Vec<Option<bool>>);
 
 impl Toggles {
 
-  pub fn iter(&self) -> TogglesIter { TogglesIter(Box::new(self.0.iter())) }
+  pub fn iter(&self) -> TogglesIter<'_> { TogglesIter(Box::new(self.0.iter())) }
 }
 
 pub struct TogglesIter<'a>(Box<dyn Iterator<Item=&'a Option<bool>> + 'a>);
@@ -117,3 +140,62 @@ impl std::str::FromStr for Toggles {
     Ok(Self(bits))
   }
 }
+
+// A DRM card by index, PCI slot or driver name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Card {
+  Index(u64),
+  Slot(String),
+  Driver(String),
+}
+
+impl std::fmt::Display for Card {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Index(id) => write!(f, "card{}", id),
+      Self::Slot(slot) => write!(f, "{}", slot),
+      Self::Driver(driver) => write!(f, "{}", driver),
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Cards(Vec<Card>);
+
+impl Cards {
+
+  pub fn iter(&self) -> CardsIter<'_> { CardsIter(Box::new(self.0.iter())) }
+}
+
+pub struct CardsIter<'a>(Box<dyn Iterator<Item=&'a Card> + 'a>);
+
+impl<'a> Iterator for CardsIter<'a> {
+  type Item = &'a Card;
+
+  fn next(&mut self) -> Option<Self::Item> { self.0.next() }
+}
+
+impl std::str::FromStr for Cards {
+  type Err = Error;
+
+  // Indices as for `Indices`, PCI slots with or without the domain, and driver names, e.g.
+  // `0,2-3,0000:03:00.0,03:00.0,amdgpu`.
+  fn from_str(s: &str) -> Result<Self> {
+    let mut cards = vec![];
+    for part in s.split(',') {
+      let part = part.trim().to_lowercase();
+      if ! part.is_empty() && part.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        let ids = part.parse::<Indices>().map_err(|_| Error::ParseCards(s.to_string()))?;
+        cards.extend(ids.into_iter().map(Card::Index));
+      } else if part.contains(':') {
+        let slot = if part.matches(':').count() == 1 { format!("0000:{}", part) } else { part };
+        cards.push(Card::Slot(slot));
+      } else if ! part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        cards.push(Card::Driver(part));
+      } else {
+        return Err(Error::ParseCards(s.to_string()));
+      }
+    }
+    Ok(Self(cards))
+  }
+}
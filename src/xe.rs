@@ -0,0 +1,191 @@
+use {
+  crate::{
+    drm,
+    pseudofs,
+    pseudofs::{Read, Write},
+    sysfs,
+    units::Hertz,
+  },
+  log::{debug, info},
+  std::{fs, path::Path},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+
+  #[error(transparent)] CpuxDrm(#[from] crate::drm::Error),
+  #[error(transparent)] CpuxPseudofs(#[from] crate::pseudofs::Error),
+  #[error(transparent)] StdIo(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+fn allow_missing_files<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Ok(ok) => Ok(Some(ok)),
+    Err(Error::CpuxPseudofs(err)) => Ok(pseudofs::allow_missing_files(Err(err))?),
+    Err(Error::StdIo(err)) =>
+      match err.kind() {
+        std::io::ErrorKind::NotFound => Ok(None),
+        _ => Err(Error::StdIo(err)),
+      },
+    Err(err) => Err(err),
+  }
+}
+
+// Ids of the directory entries named `<prefix><id>`, e.g. `tile0`.
+fn try_ids(dir: &Path, prefix: &str) -> Result<Vec<u64>> {
+  let mut ids = vec![];
+  for ent in fs::read_dir(dir)? {
+    let ent = ent?.file_name();
+    let ent = if let Some(ent) = ent.to_str() { ent } else { continue; };
+    if let Some(id) = ent.strip_prefix(prefix) {
+      if let Ok(id) = id.parse::<u64>() { ids.push(id); }
+    }
+  }
+  ids.sort_unstable();
+  Ok(ids)
+}
+
+fn try_read_mhz(path: &Path) -> Result<Hertz> {
+  let mhz = u64::read(path)?;
+  Ok(Hertz::from_mhz(mhz as f64))
+}
+
+pub fn available() -> bool {
+  sysfs::xe_module().is_dir()
+}
+
+pub fn try_cards() -> Result<Vec<u64>> {
+  let mut cards: Vec<u64> = vec![];
+  for card_id in drm::try_cards()? {
+    if drm::try_card_driver(card_id)?.eq("xe") {
+      cards.push(card_id);
+    }
+  }
+  cards.sort_unstable();
+  Ok(cards)
+}
+
+pub fn cards() -> Result<Option<Vec<u64>>> {
+  match try_cards() {
+    Ok(cards) => Ok(Some(cards)),
+    Err(Error::CpuxDrm(drm::Error::StdIo(err))) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+    Err(err) => Err(err),
+  }
+}
+
+pub fn try_tiles(card_id: u64) -> Result<Vec<u64>> {
+  let res = try_ids(&sysfs::drm_card_device(card_id), "tile")?;
+  debug!("xe get_tiles card{} {:?}", card_id, res);
+  Ok(res)
+}
+
+pub fn tiles(card_id: u64) -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_tiles(card_id))
+}
+
+// GT ids are unique per device, e.g. a media GT is `tile0/gt1`, and the first GT of the
+// second tile is `tile1/gt2` on a device with one GT per tile.
+pub fn try_gts(card_id: u64, tile_id: u64) -> Result<Vec<u64>> {
+  let res = try_ids(&sysfs::xe_tile(card_id, tile_id), "gt")?;
+  debug!("xe get_gts card{} tile{} {:?}", card_id, tile_id, res);
+  Ok(res)
+}
+
+pub fn gts(card_id: u64, tile_id: u64) -> Result<Option<Vec<u64>>> {
+  allow_missing_files(try_gts(card_id, tile_id))
+}
+
+pub fn try_actual(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Hertz> {
+  let res = try_read_mhz(&sysfs::xe_act_freq(card_id, tile_id, gt_id))?;
+  debug!("xe get_act_freq card{} tile{} gt{} {}", card_id, tile_id, gt_id, res.mhz());
+  Ok(res)
+}
+
+pub fn actual(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_actual(card_id, tile_id, gt_id))
+}
+
+pub fn try_requested(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Hertz> {
+  let res = try_read_mhz(&sysfs::xe_cur_freq(card_id, tile_id, gt_id))?;
+  debug!("xe get_cur_freq card{} tile{} gt{} {}", card_id, tile_id, gt_id, res.mhz());
+  Ok(res)
+}
+
+pub fn requested(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_requested(card_id, tile_id, gt_id))
+}
+
+pub fn try_max(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Hertz> {
+  let res = try_read_mhz(&sysfs::xe_max_freq(card_id, tile_id, gt_id))?;
+  debug!("xe get_max_freq card{} tile{} gt{} {}", card_id, tile_id, gt_id, res.mhz());
+  Ok(res)
+}
+
+pub fn max(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_max(card_id, tile_id, gt_id))
+}
+
+pub fn try_set_max<H: AsRef<Hertz>>(card_id: u64, tile_id: u64, gt_id: u64, val: H) -> Result<()> {
+  let mhz = val.as_ref().mhz() as u64;
+  info!("xe set_max_freq card{} tile{} gt{} {}", card_id, tile_id, gt_id, mhz);
+  mhz.write(&sysfs::xe_max_freq(card_id, tile_id, gt_id))?;
+  Ok(())
+}
+
+pub fn set_max<H: AsRef<Hertz>>(card_id: u64, tile_id: u64, gt_id: u64, val: H) -> Result<Option<()>> {
+  allow_missing_files(try_set_max(card_id, tile_id, gt_id, val))
+}
+
+pub fn try_min(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Hertz> {
+  let res = try_read_mhz(&sysfs::xe_min_freq(card_id, tile_id, gt_id))?;
+  debug!("xe get_min_freq card{} tile{} gt{} {}", card_id, tile_id, gt_id, res.mhz());
+  Ok(res)
+}
+
+pub fn min(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_min(card_id, tile_id, gt_id))
+}
+
+pub fn try_set_min<H: AsRef<Hertz>>(card_id: u64, tile_id: u64, gt_id: u64, val: H) -> Result<()> {
+  let mhz = val.as_ref().mhz() as u64;
+  info!("xe set_min_freq card{} tile{} gt{} {}", card_id, tile_id, gt_id, mhz);
+  mhz.write(&sysfs::xe_min_freq(card_id, tile_id, gt_id))?;
+  Ok(())
+}
+
+pub fn set_min<H: AsRef<Hertz>>(card_id: u64, tile_id: u64, gt_id: u64, val: H) -> Result<Option<()>> {
+  allow_missing_files(try_set_min(card_id, tile_id, gt_id, val))
+}
+
+pub fn try_max_limit(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Hertz> {
+  let res = try_read_mhz(&sysfs::xe_rp0_freq(card_id, tile_id, gt_id))?;
+  debug!("xe get_rp0_freq card{} tile{} gt{} {}", card_id, tile_id, gt_id, res.mhz());
+  Ok(res)
+}
+
+pub fn max_limit(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_max_limit(card_id, tile_id, gt_id))
+}
+
+pub fn try_min_limit(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Hertz> {
+  let res = try_read_mhz(&sysfs::xe_rpn_freq(card_id, tile_id, gt_id))?;
+  debug!("xe get_rpn_freq card{} tile{} gt{} {}", card_id, tile_id, gt_id, res.mhz());
+  Ok(res)
+}
+
+pub fn min_limit(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_min_limit(card_id, tile_id, gt_id))
+}
+
+// `rpe_freq`, the most efficient frequency, like i915's RP1.
+pub fn try_optimum_limit(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Hertz> {
+  let res = try_read_mhz(&sysfs::xe_rpe_freq(card_id, tile_id, gt_id))?;
+  debug!("xe get_rpe_freq card{} tile{} gt{} {}", card_id, tile_id, gt_id, res.mhz());
+  Ok(res)
+}
+
+pub fn optimum_limit(card_id: u64, tile_id: u64, gt_id: u64) -> Result<Option<Hertz>> {
+  allow_missing_files(try_optimum_limit(card_id, tile_id, gt_id))
+}
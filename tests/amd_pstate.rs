@@ -0,0 +1,41 @@
+mod support;
+
+use {
+  cpux::amd_pstate,
+  support::Fixture,
+};
+
+#[test]
+fn status_and_prefcore() {
+  let fixture = Fixture::amd_laptop();
+  let _root = fixture.enter();
+  assert!(amd_pstate::available());
+  assert_eq!(amd_pstate::status().unwrap().as_deref(), Some("active"));
+  assert_eq!(amd_pstate::prefcore().unwrap().as_deref(), Some("enabled"));
+  amd_pstate::set_status("guided").unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/amd_pstate/status"), "guided");
+}
+
+#[test]
+fn per_cpu_values() {
+  let fixture = Fixture::amd_laptop();
+  let _root = fixture.enter();
+  assert_eq!(amd_pstate::highest_perf(3).unwrap(), Some(166));
+  assert_eq!(amd_pstate::prefcore_ranking(0).unwrap(), Some(236));
+  assert_eq!(amd_pstate::prefcore_ranking(9).unwrap(), Some(231));
+  assert_eq!(amd_pstate::lowest_nonlinear_freq(1).unwrap().unwrap().mhz(), 1800.);
+  assert_eq!(amd_pstate::max_freq(1).unwrap().unwrap().mhz(), 5137.);
+  assert_eq!(amd_pstate::epps(2).unwrap().unwrap().len(), 5);
+  amd_pstate::set_epp(2, "power").unwrap();
+  assert_eq!(amd_pstate::epp(2).unwrap().as_deref(), Some("power"));
+  assert!(amd_pstate::epp(16).is_err());
+}
+
+#[test]
+fn not_available() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert!(! amd_pstate::available());
+  assert_eq!(amd_pstate::status().unwrap(), None);
+  assert_eq!(amd_pstate::highest_perf(0).unwrap(), None);
+}
@@ -0,0 +1,131 @@
+mod support;
+
+use {
+  cpux::amdgpu,
+  support::Fixture,
+};
+
+#[test]
+fn cards_by_driver() {
+  let fixture = Fixture::workstation();
+  let _root = fixture.enter();
+  assert!(amdgpu::available());
+  assert_eq!(amdgpu::cards().unwrap(), Some(vec![1]));
+  assert_eq!(amdgpu::performance_level(1).unwrap().as_deref(), Some("auto"));
+}
+
+#[test]
+fn power_profiles() {
+  let fixture = Fixture::workstation();
+  let _root = fixture.enter();
+  let profiles = amdgpu::power_profiles(1).unwrap().unwrap();
+  let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+  assert_eq!(names, vec!["BOOTUP_DEFAULT", "3D_FULL_SCREEN", "POWER_SAVING", "VIDEO", "VR", "COMPUTE", "CUSTOM"]);
+  assert_eq!(amdgpu::power_profile(1).unwrap().unwrap().id, 1);
+  amdgpu::set_power_profile(1, 5).unwrap();
+  assert_eq!(fixture.read("sys/class/drm/card1/device/pp_power_profile_mode"), "5");
+}
+
+#[test]
+fn power_profiles_polaris_layout() {
+  let fixture = Fixture::workstation();
+  fixture.write("sys/class/drm/card1/device/pp_power_profile_mode", &[
+    "NUM        MODE_NAME     SCLK_UP_HYST   SCLK_DOWN_HYST SCLK_ACTIVE_LEVEL",
+    "  0   BOOTUP_DEFAULT:        -             -               -",
+    "  1 3D_FULL_SCREEN *:        0           100              30",
+    "  2     POWER_SAVING:       10             0              30",
+  ].join("\n"));
+  let _root = fixture.enter();
+  let profiles = amdgpu::power_profiles(1).unwrap().unwrap();
+  assert_eq!(profiles.len(), 3);
+  assert_eq!(profiles[1].name, "3D_FULL_SCREEN");
+  assert!(profiles[1].active);
+  assert!(! profiles[2].active);
+}
+
+#[test]
+fn dpm_levels() {
+  let fixture = Fixture::workstation();
+  let _root = fixture.enter();
+  let levels = amdgpu::sclk_levels(1).unwrap().unwrap();
+  assert_eq!(levels.len(), 3);
+  assert_eq!(levels[2].freq.mhz(), 2615.);
+  assert_eq!(amdgpu::sclk(1).unwrap().unwrap().mhz(), 1200.);
+  assert_eq!(amdgpu::mclk(1).unwrap().unwrap().mhz(), 1000.);
+  amdgpu::set_mclk_levels(1, &[2, 3]).unwrap();
+  assert_eq!(fixture.read("sys/class/drm/card1/device/pp_dpm_mclk"), "2 3");
+  drop(_root);
+  fixture.write("sys/class/drm/card1/device/pp_dpm_sclk", "S: 19Mhz *\n0: 500Mhz\n1: 2615Mhz");
+  let _root = fixture.enter();
+  assert_eq!(amdgpu::sclk_levels(1).unwrap().unwrap().len(), 2);
+  assert_eq!(amdgpu::sclk(1).unwrap().unwrap().mhz(), 19.);
+}
+
+#[test]
+fn od_table() {
+  let fixture = Fixture::workstation();
+  let _root = fixture.enter();
+  let od = amdgpu::od_table(1).unwrap().unwrap();
+  assert_eq!(od.sclk.len(), 2);
+  assert_eq!(od.mclk[1].freq.mhz(), 1000.);
+  assert!(od.sclk[0].voltage.is_none());
+  assert!(od.vddc_curve.is_empty());
+  assert_eq!(od.freq_range("SCLK").unwrap().max.mhz(), 3150.);
+  assert!(od.voltage_ranges.is_empty());
+  amdgpu::set_od(1, &["sclk:1:2800mhz".parse().unwrap(), "mclk:1:1100mhz".parse().unwrap()]).unwrap();
+  assert_eq!(fixture.read("sys/class/drm/card1/device/pp_od_clk_voltage"), "c");
+  amdgpu::stage_od(1, &"s:1:2800mhz".parse().unwrap()).unwrap();
+  assert_eq!(fixture.read("sys/class/drm/card1/device/pp_od_clk_voltage"), "s 1 2800");
+  amdgpu::reset_od(1).unwrap();
+  assert_eq!(fixture.read("sys/class/drm/card1/device/pp_od_clk_voltage"), "r");
+}
+
+#[test]
+fn od_validation() {
+  let fixture = Fixture::workstation();
+  fixture.write("sys/class/drm/card1/device/pp_od_clk_voltage", &[
+    "OD_SCLK:",
+    "0: 800Mhz",
+    "1: 2100Mhz",
+    "OD_MCLK:",
+    "1: 875MHz",
+    "OD_VDDC_CURVE:",
+    "0: 800MHz 711mV",
+    "1: 1450MHz 768mV",
+    "2: 2100MHz 1168mV",
+    "OD_RANGE:",
+    "SCLK:     800Mhz       2150Mhz",
+    "MCLK:     625Mhz        950Mhz",
+    "VDDC_CURVE_SCLK[0]:     800Mhz       2150Mhz",
+    "VDDC_CURVE_VOLT[0]:     750mV        1200mV",
+    "VDDC_CURVE_SCLK[2]:     800Mhz       2150Mhz",
+    "VDDC_CURVE_VOLT[2]:     750mV        1200mV",
+  ].join("\n"));
+  let _root = fixture.enter();
+  let od = amdgpu::od_table(1).unwrap().unwrap();
+  assert_eq!(od.vddc_curve.len(), 3);
+  assert_eq!(od.vddc_curve[2].voltage.as_ref().unwrap().millivolts(), 1168);
+  assert_eq!(od.voltage_range("VDDC_CURVE_VOLT[2]").unwrap().max.millivolts(), 1200);
+  let edit = |s: &str| s.parse::<amdgpu::OdEdit>().unwrap();
+  assert!(od.validate(&edit("vc:2:2100mhz:1150mv")).is_ok());
+  assert_eq!(edit("vc:2:2100mhz:1.15v").command(), "vc 2 2100 1150");
+  assert!(matches!(od.validate(&edit("vc:2:2200mhz:1150mv")), Err(amdgpu::Error::OdRange(..))));
+  assert!(matches!(od.validate(&edit("vc:2:2100mhz:1250mv")), Err(amdgpu::Error::OdRange(..))));
+  assert!(matches!(od.validate(&edit("vc:1:1450mhz:800mv")), Err(amdgpu::Error::OdRangeNotFound(..))));
+  assert!(matches!(od.validate(&edit("mclk:0:875mhz")), Err(amdgpu::Error::OdPointNotFound(..))));
+  assert!(matches!(od.validate(&edit("sclk:1:2100mhz:1100mv")), Err(amdgpu::Error::OdVoltageUnsupported(..))));
+  assert!("vc:2:2100mhz".parse::<amdgpu::OdEdit>().is_err());
+  assert!("sclk:1:21".parse::<amdgpu::OdEdit>().is_err());
+  assert!("fclk:1:2100mhz".parse::<amdgpu::OdEdit>().is_err());
+  assert!(amdgpu::set_od(1, &[edit("sclk:1:2000mhz"), edit("mclk:1:1000mhz")]).is_err());
+  assert!(fixture.read("sys/class/drm/card1/device/pp_od_clk_voltage").starts_with("OD_SCLK:"));
+}
+
+#[test]
+fn no_amdgpu() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert!(! amdgpu::available());
+  assert_eq!(amdgpu::cards().unwrap(), Some(vec![]));
+  assert!(amdgpu::sclk(0).unwrap().is_none());
+}
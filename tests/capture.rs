@@ -0,0 +1,70 @@
+mod support;
+
+use {
+  std::{fs, os::unix::fs::PermissionsExt},
+  support::Fixture,
+};
+
+#[test]
+fn capture_dir_is_loadable_as_root() {
+  let fixture = Fixture::intel_laptop();
+  let dest = tempfile::tempdir().unwrap();
+  let dest = dest.path().join("capture");
+  fixture.cpux_ok(&["capture", dest.to_str().unwrap()]);
+  let out = std::process::Command::new(env!("CARGO_BIN_EXE_cpux"))
+    .arg("--root")
+    .arg(&dest)
+    .output()
+    .unwrap();
+  assert!(out.status.success());
+  assert_eq!(String::from_utf8(out.stdout).unwrap(), fixture.cpux_ok(&[]));
+}
+
+#[test]
+fn capture_keeps_symlinks_and_modes() {
+  let fixture = Fixture::intel_laptop();
+  fs::set_permissions(fixture.path("sys/class/drm/card0/gt_RP0_freq_mhz"), fs::Permissions::from_mode(0o444)).unwrap();
+  let dest = tempfile::tempdir().unwrap();
+  let dest = dest.path().join("capture");
+  fixture.cpux_ok(&["capture", dest.to_str().unwrap()]);
+  let link = fs::read_link(dest.join("sys/class/drm/card0")).unwrap();
+  assert_eq!(link, fs::read_link(fixture.path("sys/class/drm/card0")).unwrap());
+  let driver = fs::read_link(dest.join("sys/class/drm/card0/device/driver")).unwrap();
+  assert!(driver.ends_with("i915"));
+  let meta = fs::metadata(dest.join("sys/class/drm/card0/gt_RP0_freq_mhz")).unwrap();
+  assert_eq!(meta.permissions().mode() & 0o777, 0o444);
+  assert!(dest.join("sys/devices/system/cpu/cpufreq/policy11/scaling_driver").is_file());
+}
+
+#[test]
+fn capture_does_not_follow_final_symlinks() {
+  let fixture = Fixture::intel_laptop();
+  fixture.write("sys/bus/pci/drivers/i915/secret", "x");
+  let dest = tempfile::tempdir().unwrap();
+  let dest = dest.path().join("capture");
+  fixture.cpux_ok(&["capture", dest.to_str().unwrap()]);
+  assert!(! dest.join("sys/bus/pci/drivers/i915/secret").exists());
+}
+
+#[test]
+fn capture_tar() {
+  let fixture = Fixture::amd_desktop();
+  let dest = tempfile::tempdir().unwrap();
+  let dest = dest.path().join("capture.tar");
+  fixture.cpux_ok(&["capture", dest.to_str().unwrap()]);
+  let mut archive = tar::Archive::new(fs::File::open(&dest).unwrap());
+  let names: Vec<String> = archive
+    .entries()
+    .unwrap()
+    .map(|e| e.unwrap().path().unwrap().display().to_string())
+    .collect();
+  assert!(names.contains(&"sys/devices/system/cpu/cpufreq/boost".to_string()));
+  assert!(names.contains(&"sys/devices/system/cpu/cpu15/cpufreq".to_string()));
+}
+
+#[test]
+fn capture_refuses_existing_destination() {
+  let fixture = Fixture::vm();
+  let dest = tempfile::tempdir().unwrap();
+  assert!(! fixture.cpux(&["capture", dest.path().to_str().unwrap()]).status.success());
+}
@@ -0,0 +1,656 @@
+mod support;
+
+use support::Fixture;
+
+#[test]
+fn default_tables_intel_laptop() {
+  let fixture = Fixture::intel_laptop();
+  let out = fixture.cpux_ok(&[]);
+  assert!(out.contains("intel_pstate: active"));
+  assert!(out.contains("Governors"));
+  assert!(out.contains("cpu11"));
+  assert!(out.contains("card0  i915"));
+}
+
+#[test]
+fn default_tables_amd_desktop() {
+  let fixture = Fixture::amd_desktop();
+  let out = fixture.cpux_ok(&[]);
+  assert!(! out.contains("intel_pstate"));
+  assert!(out.contains("schedutil"));
+  assert!(out.contains("cpu15"));
+  assert!(! out.contains("Card"));
+}
+
+#[test]
+fn default_tables_vm() {
+  let fixture = Fixture::vm();
+  let out = fixture.cpux_ok(&[]);
+  assert!(! out.contains("Governor"));
+  assert!(out.contains("cpu3"));
+  assert!(out.contains("n/a"));
+}
+
+#[test]
+fn table_args_replace_defaults() {
+  let fixture = Fixture::intel_laptop();
+  let out = fixture.cpux_ok(&["--i915"]);
+  assert!(out.contains("card0"));
+  assert!(! out.contains("cpu0"));
+}
+
+#[test]
+fn table_args_read_only_their_subsystems() {
+  let fixture = Fixture::rockchip();
+  fixture.write("sys/devices/platform/dmc/devfreq/dmc/cur_freq", "garbage");
+  assert!(! fixture.cpux(&[]).status.success());
+  let out = fixture.cpux_ok(&["--cpu"]);
+  assert!(out.contains("cpu5"));
+  fixture.cpux_ok(&["--format", "csv"]);
+}
+
+#[test]
+fn root_from_env() {
+  let fixture = Fixture::vm();
+  let out = std::process::Command::new(env!("CARGO_BIN_EXE_cpux"))
+    .env("CPUX_ROOT", fixture.root())
+    .args(["--cpus", "2", "--cpu-on", "false"])
+    .output()
+    .unwrap();
+  assert!(out.status.success());
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu2/online"), "0");
+}
+
+#[test]
+fn set_cpufreq_per_cpus() {
+  let fixture = Fixture::amd_desktop();
+  fixture.cpux_ok(&["--cpus", "1-2", "--freq-gov", "performance", "--freq-max", "3ghz"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy0/scaling_governor"), "schedutil");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy1/scaling_governor"), "performance");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy2/scaling_max_freq"), "3000000");
+}
+
+#[test]
+fn offline_cpu_settings_keep_online_state() {
+  let fixture = Fixture::rockchip();
+  fixture.write("sys/devices/system/cpu/cpu5/online", "0").write("sys/devices/system/cpu/cpufreq/policy4/affected_cpus", "4");
+  fixture.cpux_ok(&["--cpus", "5", "--freq-max", "1.2ghz"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu5/online"), "0");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy4/scaling_max_freq"), "1200000");
+  let fixture = Fixture::intel_laptop();
+  fixture.write("sys/devices/system/cpu/cpu5/online", "0").write("sys/devices/system/cpu/cpufreq/policy5/affected_cpus", "");
+  let out = fixture.cpux(&["--cpus", "5", "--pstate-epp", "power"]);
+  assert!(String::from_utf8_lossy(&out.stderr).contains("policy5 has no online cpu"));
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy5/energy_performance_preference"), "balance_performance");
+  fixture.cpux_ok(&["--cpus", "4-5", "--pstate-epb", "4"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu4/power/energy_perf_bias"), "4");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu5/power/energy_perf_bias"), "6");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu5/online"), "0");
+  fixture.cpux_ok(&["--cpus", "5", "--cpu-on", "true", "--pstate-epp", "power"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu5/online"), "1");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy5/energy_performance_preference"), "power");
+}
+
+#[test]
+fn policies() {
+  let fixture = Fixture::rockchip();
+  let out = fixture.cpux_ok(&["--freq", "--cpu"]);
+  assert!(out.contains("policy0  0-3     0-3     schedutil"));
+  assert!(out.contains("policy4  4-5     4-5     schedutil"));
+  assert!(out.contains("cpu5    true    policy4"));
+  let out = fixture.cpux(&["--cpus", "2-5", "--freq-gov", "performance", "--log-level", "info"]);
+  let stderr = String::from_utf8_lossy(&out.stderr);
+  assert_eq!(stderr.matches("set_governor policy0").count(), 1);
+  assert_eq!(stderr.matches("set_governor policy4").count(), 1);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy0/scaling_governor"), "performance");
+}
+
+#[test]
+fn freq_driver_and_optional_columns() {
+  let fixture = Fixture::amd_desktop();
+  let out = fixture.cpux_ok(&["--freq"]);
+  assert!(out.contains("cpufreq: acpi-cpufreq"));
+  assert!(out.contains("BIOS limit  HW cur      Latency"));
+  assert!(out.contains("3.7 GHz     2.2 GHz     0 ns"));
+  assert!(! out.contains("Base"));
+  let out = Fixture::intel_laptop().cpux_ok(&["--freq"]);
+  assert!(out.contains("cpufreq: intel_pstate"));
+  assert!(out.contains("Base"));
+  assert!(out.contains("1.6 GHz"));
+  assert!(! out.contains("BIOS limit"));
+}
+
+#[test]
+fn cpu_on_each() {
+  let fixture = Fixture::vm();
+  fixture.cpux_ok(&["--cpu-on-each=-01-"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu1/online"), "0");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu2/online"), "1");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu3/online"), "1");
+}
+
+#[test]
+fn nonexistent_cpu_is_error() {
+  let fixture = Fixture::vm();
+  let out = fixture.cpux(&["--cpus", "9", "--cpu-on", "true"]);
+  assert!(! out.status.success());
+}
+
+#[test]
+fn set_i915_frequencies() {
+  let fixture = Fixture::intel_laptop();
+  fixture.cpux_ok(&["--i915-freq-min", "350mhz", "--i915-freq-max", "0.9ghz"]);
+  assert_eq!(fixture.read("sys/class/drm/card0/gt_min_freq_mhz"), "350");
+  assert_eq!(fixture.read("sys/class/drm/card0/gt_max_freq_mhz"), "900");
+}
+
+#[test]
+fn format_json() {
+  let fixture = Fixture::intel_laptop();
+  fixture.write("sys/devices/system/cpu/cpu1/online", "0");
+  fixture.remove("sys/devices/system/cpu/cpu1/power/energy_perf_bias");
+  let out = fixture.cpux_ok(&["--format", "json"]);
+  let doc: serde_json::Value = serde_json::from_str(&out).unwrap();
+  assert_eq!(doc["cpus"].as_array().unwrap().len(), 12);
+  assert_eq!(doc["cpus"][0]["max_limit"], 4_700_000_000u64);
+  assert_eq!(doc["cpus"][0]["governors"][1], "performance");
+  assert_eq!(doc["cpus"][1]["online"], false);
+  assert!(doc["cpus"][1]["epb"].is_null());
+  assert_eq!(doc["intel_pstate"]["status"], "active");
+  assert_eq!(doc["i915"][0]["min"], 300_000_000u64);
+}
+
+#[test]
+fn format_json_vm() {
+  let fixture = Fixture::vm();
+  let doc: serde_json::Value = serde_json::from_str(&fixture.cpux_ok(&["--format", "json"])).unwrap();
+  assert!(doc["cpus"][2]["cur"].is_null());
+  assert!(doc["intel_pstate"].is_null());
+}
+
+#[test]
+fn format_unknown_is_error() {
+  let fixture = Fixture::vm();
+  assert!(! fixture.cpux(&["--format", "xml"]).status.success());
+}
+
+#[test]
+fn format_csv() {
+  let fixture = Fixture::intel_laptop();
+  fixture.write("sys/devices/system/cpu/cpufreq/policy2/scaling_cur_freq", "2100000");
+  let out = fixture.cpux_ok(&["--format", "csv"]);
+  let lines: Vec<&str> = out.lines().collect();
+  assert_eq!(lines.len(), 13);
+  assert_eq!(lines[0], "time,cpu,online,cur,min,max,min_limit,max_limit,governor,epb,epp,package_temp,core_temp");
+  let row: Vec<&str> = lines[3].split(',').collect();
+  assert_eq!(&row[1..], &["cpu2", "true", "2100000000", "400000000", "4700000000", "400000000", "4700000000", "powersave", "6", "balance_performance", "52", "47"]);
+}
+
+#[test]
+fn format_tsv_cards() {
+  let fixture = Fixture::intel_laptop();
+  let out = fixture.cpux_ok(&["--format", "tsv", "--i915"]);
+  let lines: Vec<&str> = out.lines().collect();
+  assert_eq!(lines.len(), 2);
+  assert!(lines[0].starts_with("time\tcard\tdriver\tgt\t"));
+  assert!(lines[1].contains("\tcard0\ti915\t\t300000000\t"));
+  assert!(! fixture.cpux(&["--format", "tsv", "--i915", "--cpu"]).status.success());
+  let out = Fixture::meteor_lake().cpux_ok(&["--format", "csv", "--i915"]);
+  let lines: Vec<&str> = out.lines().collect();
+  assert_eq!(lines.len(), 3);
+  assert!(lines[1].contains(",card0,i915,gt0,"));
+  assert!(lines[2].contains(",card0,i915,gt1,"));
+}
+
+#[test]
+fn format_csv_xe_amdgpu_devfreq() {
+  let out = Fixture::xe_laptop().cpux_ok(&["--format", "csv", "--xe"]);
+  let lines: Vec<&str> = out.lines().collect();
+  assert_eq!(lines.len(), 3);
+  assert_eq!(lines[0], "time,card,driver,tile,gt,actual,requested,min,max,min_limit,max_limit");
+  assert!(lines[2].contains(",card0,xe,tile0,gt1,100000000,"));
+  let out = Fixture::workstation().cpux_ok(&["--format", "csv", "--amdgpu"]);
+  let lines: Vec<&str> = out.lines().collect();
+  assert_eq!(lines.len(), 2);
+  assert_eq!(lines[0], "time,card,driver,performance_level,power_profile,sclk,mclk,power,power_cap");
+  assert!(lines[1].ends_with(",card1,amdgpu,auto,3D_FULL_SCREEN,1200000000,1000000000,35,150"));
+  let fixture = Fixture::rockchip();
+  let out = fixture.cpux_ok(&["--format", "tsv", "--devfreq"]);
+  let lines: Vec<&str> = out.lines().collect();
+  assert_eq!(lines.len(), 3);
+  assert_eq!(lines[0], "time\tdevice\tgovernor\tcur\tmin\tmax");
+  assert!(lines[2].ends_with("\tff9a0000.gpu\tsimple_ondemand\t200000000\t200000000\t800000000"));
+  let out = fixture.cpux(&["--format", "csv", "--devfreq", "--hwmon"]);
+  assert!(String::from_utf8_lossy(&out.stderr).contains("--devfreq cannot be combined with --hwmon"));
+}
+
+#[test]
+fn format_csv_cards() {
+  let fixture = Fixture::workstation();
+  let out = fixture.cpux_ok(&["--format", "csv", "--cards", "amdgpu"]);
+  let lines: Vec<&str> = out.lines().collect();
+  assert_eq!(lines.len(), 2);
+  assert!(lines[0].starts_with("time,card,driver,performance_level,"));
+  assert!(lines[1].contains(",card1,amdgpu,"));
+  let out = fixture.cpux_ok(&["--format", "csv", "--i915", "--cards", "0"]);
+  assert_eq!(out.lines().count(), 2);
+  let out = fixture.cpux(&["--format", "csv", "--i915", "--cards", "1"]);
+  assert!(String::from_utf8_lossy(&out.stderr).contains("--cards selects card1 (amdgpu) which has no i915 rows"));
+  assert!(! fixture.cpux(&["--format", "csv", "--cards", "0-1"]).status.success());
+  assert_eq!(fixture.cpux_ok(&["--format", "csv", "--cpu", "--cards", "1"]).lines().next(), Some("time,cpu,online,cur,min,max,min_limit,max_limit,governor,epb,epp,package_temp,core_temp"));
+}
+
+#[test]
+fn format_csv_streams_with_one_header() {
+  use std::io::BufRead;
+  let fixture = Fixture::vm();
+  let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_cpux"))
+    .arg("--root")
+    .arg(fixture.root())
+    .args(["--format", "csv", "1"])
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let lines: Vec<String> = std::io::BufReader::new(child.stdout.take().unwrap())
+    .lines()
+    .take(9)
+    .map(|l| l.unwrap())
+    .collect();
+  child.kill().unwrap();
+  child.wait().unwrap();
+  assert!(lines[0].starts_with("time,"));
+  assert!(lines[1..].iter().all(|l| ! l.starts_with("time,")));
+  assert_eq!(lines.iter().filter(|l| l.contains(",cpu3,")).count(), 2);
+}
+
+#[test]
+fn hwmon_table() {
+  let fixture = Fixture::intel_laptop();
+  let out = fixture.cpux_ok(&["--hwmon"]);
+  assert!(out.contains("hwmon1   coretemp"));
+  assert!(out.contains("Package id 0"));
+  assert!(out.contains("52.0 °C"));
+  assert!(out.contains("2900 RPM"));
+  assert!(out.contains("Package temp"));
+  assert!(! fixture.cpux_ok(&["--cpu"]).contains("hwmon"));
+  assert!(! Fixture::vm().cpux_ok(&[]).contains("hwmon"));
+}
+
+#[test]
+fn hwmon_pwm_control() {
+  let fixture = Fixture::amd_desktop();
+  let out = fixture.cpux_ok(&["--hwmon-chip", "nct6775", "--hwmon-pwms", "2", "--hwmon-pwm", "128", "--hwmon"]);
+  assert!(out.contains("pwm2"));
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm2_enable"), "1");
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm2"), "128");
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm1_enable"), "5");
+  fixture.cpux_ok(&["--hwmon-chip", "hwmon1", "--hwmon-pwm-mode", "auto"]);
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm1_enable"), "2");
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm2_enable"), "2");
+}
+
+#[test]
+fn hwmon_pwm_refused() {
+  let fixture = Fixture::amd_desktop();
+  for args in &[
+    &["--hwmon-chip", "1", "--hwmon-pwm", "20"][..],
+    &["--hwmon-chip", "1", "--hwmon-pwm", "300"][..],
+    &["--hwmon-chip", "1", "--hwmon-pwm-mode", "manual"][..],
+    &["--hwmon-chip", "1", "--hwmon-pwm-mode", "full", "--hwmon-pwm", "128"][..],
+    &["--hwmon-chip", "1", "--hwmon-pwms", "3", "--hwmon-pwm", "128"][..],
+    &["--hwmon-chip", "nouveau", "--hwmon-pwm", "128"][..],
+    &["--hwmon-pwm", "128"][..],
+  ] {
+    assert!(! fixture.cpux(args).status.success(), "{:?}", args);
+  }
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm1_enable"), "5");
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm1"), "96");
+  fixture.cpux_ok(&["--hwmon-chip", "1", "--hwmon-pwm-min", "0", "--hwmon-pwm", "20"]);
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm1"), "20");
+}
+
+#[test]
+fn hwmon_pwm_hold_restores() {
+  let fixture = Fixture::intel_laptop();
+  let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_cpux"))
+    .arg("--root")
+    .arg(fixture.root())
+    .args(["--hwmon-chip", "thinkpad", "--hwmon-pwm", "200", "--hwmon-pwm-hold"])
+    .stdout(std::process::Stdio::null())
+    .spawn()
+    .expect("run cpux");
+  let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+  while fixture.read("sys/class/hwmon/hwmon2/pwm1") != "200" {
+    assert!(std::time::Instant::now() < deadline, "pwm not set");
+    std::thread::sleep(std::time::Duration::from_millis(20));
+  }
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon2/pwm1_enable"), "1");
+  let status = std::process::Command::new("kill").arg(child.id().to_string()).status().expect("kill");
+  assert!(status.success());
+  child.wait().expect("wait cpux");
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon2/pwm1_enable"), "2");
+}
+
+#[test]
+fn rapl_table_and_limits() {
+  let fixture = Fixture::intel_laptop();
+  let out = fixture.cpux_ok(&["--rapl"]);
+  assert!(out.contains("intel-rapl:0:1"));
+  assert!(out.contains("package-0"));
+  assert!(out.contains("15.0 W"));
+  assert!(out.contains("28.0 s"));
+  assert!(out.contains("2.4 ms"));
+  assert!(fixture.cpux_ok(&[]).contains("psys"));
+  fixture.cpux_ok(&["--rapl-limit-long", "12w", "--rapl-limit-short", "28.5W"]);
+  assert_eq!(fixture.read("sys/class/powercap/intel-rapl:0/constraint_0_power_limit_uw"), "12000000");
+  assert_eq!(fixture.read("sys/class/powercap/intel-rapl:0/constraint_1_power_limit_uw"), "28500000");
+  assert_eq!(fixture.read("sys/class/powercap/intel-rapl:1/constraint_0_power_limit_uw"), "0");
+  fixture.cpux_ok(&["--rapl-zones", "1,0:0", "--rapl-enabled", "false"]);
+  assert_eq!(fixture.read("sys/class/powercap/intel-rapl:1/enabled"), "0");
+  assert_eq!(fixture.read("sys/class/powercap/intel-rapl:0:0/enabled"), "0");
+  assert_eq!(fixture.read("sys/class/powercap/intel-rapl:0/enabled"), "1");
+  assert!(! fixture.cpux(&["--rapl-limit-long", "16w"]).status.success());
+  assert!(! fixture.cpux(&["--rapl-zones", "0:0", "--rapl-limit-short", "5w"]).status.success());
+  assert!(! fixture.cpux(&["--rapl-zones", "2", "--rapl-enabled", "true"]).status.success());
+  assert!(! Fixture::vm().cpux_ok(&[]).contains("Zone"));
+}
+
+#[test]
+fn rapl_power_in_refresh_mode() {
+  use std::io::BufRead;
+  let fixture = Fixture::intel_laptop();
+  let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_cpux"))
+    .arg("--root")
+    .arg(fixture.root())
+    .args(["--format", "json", "1"])
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let mut lines = std::io::BufReader::new(child.stdout.take().unwrap()).lines();
+  let first: serde_json::Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+  fixture.write("sys/class/powercap/intel-rapl:0/energy_uj", "71236567890");
+  let second: serde_json::Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+  child.kill().unwrap();
+  child.wait().unwrap();
+  assert!(first["rapl"][0]["power"].is_null());
+  let watts = second["rapl"][0]["power"].as_f64().unwrap();
+  assert!(watts > 0. && watts <= 2., "{}", watts);
+  assert!(fixture.cpux_ok(&["--rapl"]).contains("Power"));
+}
+
+#[test]
+fn pstate_globals_table_and_controls() {
+  let fixture = Fixture::intel_laptop();
+  let out = fixture.cpux_ok(&["--pstate"]);
+  assert!(out.contains("intel_pstate: active  turbo: on  perf: 9% - 100%  pstates: 44  turbo pstates: 33%  dynamic boost: off"));
+  fixture.cpux_ok(&["--pstate-turbo", "false", "--pstate-perf-max", "80", "--pstate-perf-min", "20", "--pstate-dynamic-boost", "true"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/no_turbo"), "1");
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/max_perf_pct"), "80");
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/min_perf_pct"), "20");
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/hwp_dynamic_boost"), "1");
+  assert!(fixture.cpux_ok(&["--pstate"]).contains("turbo: off  perf: 20% - 80%"));
+  assert!(! fixture.cpux(&["--pstate-perf-max", "101"]).status.success());
+  assert!(! fixture.cpux(&["--pstate-perf-min", "50", "--pstate-perf-max", "40"]).status.success());
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/max_perf_pct"), "80");
+}
+
+#[test]
+fn pstate_status() {
+  let fixture = Fixture::intel_laptop();
+  fixture.cpux_ok(&["--pstate-status", "passive"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/status"), "passive");
+  assert!(fixture.cpux_ok(&["--pstate"]).contains("intel_pstate: passive"));
+  assert!(! fixture.cpux(&["--pstate-status", "guided"]).status.success());
+  // Arguments which do not depend on the mode are refused before the switch.
+  assert!(! fixture.cpux(&["--pstate-status", "active", "--pstate-perf-max", "101"]).status.success());
+  assert!(! fixture.cpux(&["--pstate-status", "active", "--idle-gov", "bogus"]).status.success());
+  assert!(! fixture.cpux(&["--pstate-status", "active", "--idle-disable", "C7"]).status.success());
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/status"), "passive");
+  // Without a switch the governor is refused before --pstate-turbo is written.
+  assert!(! fixture.cpux(&["--pstate-turbo", "false", "--freq-gov", "bogus"]).status.success());
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/no_turbo"), "0");
+  // intel_cpufreq lists the generic governors, which the fixture does not emulate.
+  let out = fixture.cpux(&["--freq-gov", "schedutil", "--pstate-epp", "power"]);
+  assert!(! out.status.success());
+  assert!(String::from_utf8_lossy(&out.stderr).contains("available governors: powersave,performance"));
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy0/energy_performance_preference"), "balance_performance");
+  assert!(! fixture.cpux(&["--pstate-epp", "max_power"]).status.success());
+  fixture.cpux_ok(&["--pstate-epp", "128"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy0/energy_performance_preference"), "128");
+  // A failed check after the switch restores the previous mode.
+  assert!(! fixture.cpux(&["--pstate-status", "active", "--freq-gov", "bogus"]).status.success());
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/status"), "passive");
+  fixture.remove("sys/devices/system/cpu/cpufreq/policy0/energy_performance_preference");
+  let out = fixture.cpux(&["--cpus", "0", "--pstate-status", "active", "--pstate-epp", "power"]);
+  assert!(String::from_utf8_lossy(&out.stderr).contains("policy0 has no energy/performance preference"));
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/status"), "passive");
+}
+
+#[test]
+fn turbo() {
+  let fixture = Fixture::intel_laptop();
+  assert!(fixture.cpux_ok(&["--cpu"]).contains("Turbo"));
+  fixture.cpux_ok(&["--turbo", "off"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/no_turbo"), "1");
+  let fixture = Fixture::amd_desktop();
+  fixture.cpux_ok(&["--turbo", "off"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/boost"), "0");
+  let fixture = Fixture::amd_laptop();
+  let out = fixture.cpux_ok(&["--turbo", "off", "--cpu"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy9/boost"), "0");
+  assert!(out.lines().any(|l| l.trim().starts_with("cpu9") && l.trim().ends_with("off")));
+  let fixture = Fixture::vm();
+  assert!(fixture.cpux_ok(&["--cpu"]).lines().any(|l| l.trim().starts_with("cpu0") && l.trim().ends_with("n/a")));
+  assert!(! fixture.cpux(&["--turbo", "on"]).status.success());
+}
+
+#[test]
+fn amd_pstate_table_and_controls() {
+  let fixture = Fixture::amd_laptop();
+  let out = fixture.cpux_ok(&[]);
+  assert!(out.contains("amd_pstate: active  prefcore: enabled"));
+  assert!(out.contains("Ranking"));
+  assert!(out.contains("5.1 GHz"));
+  assert!(! out.contains("intel_pstate"));
+  assert!(! Fixture::intel_laptop().cpux_ok(&[]).contains("amd_pstate"));
+  fixture.cpux_ok(&["--cpus", "1-2", "--amd-pstate-epp", "power"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy2/energy_performance_preference"), "power");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy3/energy_performance_preference"), "balance_performance");
+  fixture.cpux_ok(&["--amd-pstate-status", "passive"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/amd_pstate/status"), "passive");
+  assert!(! fixture.cpux(&["--amd-pstate-status", "off"]).status.success());
+}
+
+#[test]
+fn amdgpu_table_and_controls() {
+  let fixture = Fixture::workstation();
+  let out = fixture.cpux_ok(&[]);
+  assert!(out.contains("card0  i915"));
+  assert!(out.contains("card1  amdgpu"));
+  assert!(out.contains("3D_FULL_SCREEN"));
+  assert!(out.contains("0:500 1:1200 2:2615"));
+  assert!(! Fixture::intel_laptop().cpux_ok(&[]).contains("amdgpu"));
+  fixture.cpux_ok(&["--amdgpu-profile", "compute", "--amdgpu-sclk-levels", "1-2"]);
+  assert_eq!(fixture.read("sys/class/drm/card1/device/power_dpm_force_performance_level"), "manual");
+  assert_eq!(fixture.read("sys/class/drm/card1/device/pp_power_profile_mode"), "5");
+  assert_eq!(fixture.read("sys/class/drm/card1/device/pp_dpm_sclk"), "1 2");
+  assert!(! fixture.cpux(&["--amdgpu-profile", "turbo"]).status.success());
+  assert!(! fixture.cpux(&["--amdgpu-mclk-levels", "4"]).status.success());
+  assert!(! fixture.cpux(&["--amdgpu-perf-level", "auto", "--amdgpu-mclk-levels", "1"]).status.success());
+  fixture.cpux_ok(&["--amdgpu-perf-level", "auto"]);
+  assert_eq!(fixture.read("sys/class/drm/card1/device/power_dpm_force_performance_level"), "auto");
+}
+
+#[test]
+fn amdgpu_overdrive() {
+  let fixture = Fixture::workstation();
+  let out = fixture.cpux_ok(&["--amdgpu"]);
+  assert!(out.contains("card1  sclk 1     2615 MHz"));
+  assert!(out.contains("500 - 3150 MHz"));
+  assert!(! fixture.cpux(&["--amdgpu-od", "sclk:1:3200mhz"]).status.success());
+  assert!(fixture.read("sys/class/drm/card1/device/pp_od_clk_voltage").starts_with("OD_SCLK:"));
+  assert!(! fixture.cpux(&["--amdgpu-perf-level", "auto", "--amdgpu-od", "sclk:1:2800mhz"]).status.success());
+  fixture.cpux_ok(&["--amdgpu-od", "sclk:1:2800mhz,mclk:1:1100mhz"]);
+  assert_eq!(fixture.read("sys/class/drm/card1/device/power_dpm_force_performance_level"), "manual");
+  assert_eq!(fixture.read("sys/class/drm/card1/device/pp_od_clk_voltage"), "c");
+  fixture.cpux_ok(&["--amdgpu-od-reset"]);
+  assert_eq!(fixture.read("sys/class/drm/card1/device/pp_od_clk_voltage"), "r");
+}
+
+#[test]
+fn gpu_power_cap() {
+  let fixture = Fixture::workstation();
+  let out = fixture.cpux_ok(&["--i915", "--amdgpu"]);
+  assert!(out.contains("15.0 W"));
+  assert!(out.contains("35.0 W   150.0 W (100.0 W - 186.0 W)"));
+  assert!(! fixture.cpux(&["--gpu-power-cap", "200w"]).status.success());
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/power1_cap"), "150000000");
+  fixture.cpux_ok(&["--gpu-power-cap", "120w"]);
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon0/power1_max"), "120000000");
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/power1_cap"), "120000000");
+}
+
+#[test]
+fn gpu_table() {
+  let fixture = Fixture::workstation();
+  let out = fixture.cpux_ok(&["--amdgpu"]);
+  assert!(out.contains("card0  i915    0000:00:02.0 8086:4680 1043:8694 no       renderD128 Intel Corporation AlderLake-S GT1"));
+  assert!(out.contains("card1  amdgpu  0000:03:00.0 1002:73bf 1da2:e438 yes      renderD129 Advanced Micro Devices"));
+  assert!(! fixture.cpux_ok(&["--cpu"]).contains("renderD128"));
+}
+
+#[test]
+fn cards() {
+  let fixture = Fixture::workstation();
+  fixture.cpux_ok(&["--cards", "1", "--gpu-power-cap", "120w"]);
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon0/power1_max"), "15000000");
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/power1_cap"), "120000000");
+  fixture.cpux_ok(&["--cards", "00:02.0", "--gpu-power-cap", "10w"]);
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon0/power1_max"), "10000000");
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/power1_cap"), "120000000");
+  fixture.cpux_ok(&["--cards", "amdgpu", "--gpu-power-cap", "110w"]);
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon0/power1_max"), "10000000");
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/power1_cap"), "110000000");
+  fixture.cpux_ok(&["--cards", "0000:03:00.0", "--amdgpu-perf-level", "high"]);
+  assert_eq!(fixture.read("sys/class/drm/card1/device/power_dpm_force_performance_level"), "high");
+  let out = fixture.cpux(&["--cards", "2", "--i915-freq-max", "1ghz"]);
+  assert!(! out.status.success());
+  assert!(String::from_utf8_lossy(&out.stderr).contains("no such card: card2"));
+  assert!(! fixture.cpux(&["--cards", "nouveau", "--gpu-power-cap", "10w"]).status.success());
+  assert!(! fixture.cpux(&["--cards", "0,x.y", "--gpu-power-cap", "10w"]).status.success());
+}
+
+#[test]
+fn idle_table_and_controls() {
+  let fixture = Fixture::intel_laptop();
+  let out = fixture.cpux_ok(&["--idle"]);
+  assert!(out.contains("cpuidle: intel_idle, governor menu (ladder,menu,teo)"));
+  assert!(out.contains("state2  C6       170 µs   600 µs    MWAIT 0x20"));
+  fixture.cpux_ok(&["--cpus", "0-1", "--idle-disable", "C8,c10"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu1/cpuidle/state3/disable"), "1");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu1/cpuidle/state4/disable"), "1");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu1/cpuidle/state2/disable"), "0");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu2/cpuidle/state3/disable"), "0");
+  assert!(fixture.cpux_ok(&["--idle"]).contains("cpu1    on   on   on   off  off"));
+  fixture.cpux_ok(&["--idle-latency-max", "100", "--idle-enable", "C10"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu1/cpuidle/state1/disable"), "0");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu5/cpuidle/state2/disable"), "1");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu1/cpuidle/state3/disable"), "1");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu1/cpuidle/state4/disable"), "0");
+  fixture.cpux_ok(&["--idle-enable", "2"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu5/cpuidle/state2/disable"), "0");
+  fixture.cpux_ok(&["--idle-gov", "teo"]);
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpuidle/current_governor"), "teo");
+  assert!(! fixture.cpux(&["--idle-disable", "C7"]).status.success());
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu0/cpuidle/state3/disable"), "1");
+}
+
+#[test]
+fn devfreq_table_and_controls() {
+  let fixture = Fixture::rockchip();
+  let out = fixture.cpux_ok(&[]);
+  assert!(out.contains("ff9a0000.gpu simple_ondemand"));
+  assert!(out.contains("200 300 400 600 800"));
+  assert!(! fixture.cpux_ok(&["--cpu"]).contains("dmc"));
+  fixture.cpux_ok(&["--devfreq-devices", "ff9a0000.gpu", "--devfreq-gov", "performance", "--devfreq-max", "600mhz", "--devfreq-min", "300mhz"]);
+  assert_eq!(fixture.read("sys/class/devfreq/ff9a0000.gpu/governor"), "performance");
+  assert_eq!(fixture.read("sys/class/devfreq/ff9a0000.gpu/max_freq"), "600000000");
+  assert_eq!(fixture.read("sys/class/devfreq/ff9a0000.gpu/min_freq"), "300000000");
+  assert_eq!(fixture.read("sys/class/devfreq/dmc/governor"), "simple_ondemand");
+  fixture.cpux_ok(&["--devfreq-gov", "powersave"]);
+  assert_eq!(fixture.read("sys/class/devfreq/dmc/governor"), "powersave");
+  assert!(! fixture.cpux(&["--devfreq-devices", "ff000000.npu", "--devfreq-gov", "performance"]).status.success());
+}
+
+#[test]
+fn xe_table_and_controls() {
+  let fixture = Fixture::xe_laptop();
+  let out = fixture.cpux_ok(&[]);
+  assert!(out.contains("card0  xe      tile0 gt0"));
+  assert!(out.contains("card0  xe      tile0 gt1"));
+  assert!(! out.contains("i915"));
+  fixture.cpux_ok(&["--xe-gts", "1", "--xe-freq-max", "800mhz", "--xe-freq-min", "200mhz"]);
+  assert_eq!(fixture.read("sys/class/drm/card0/device/tile0/gt1/freq0/max_freq"), "800");
+  assert_eq!(fixture.read("sys/class/drm/card0/device/tile0/gt1/freq0/min_freq"), "200");
+  assert_eq!(fixture.read("sys/class/drm/card0/device/tile0/gt0/freq0/max_freq"), "2050");
+  fixture.cpux_ok(&["--xe-freq-max", "1.5ghz"]);
+  assert_eq!(fixture.read("sys/class/drm/card0/device/tile0/gt0/freq0/max_freq"), "1500");
+  assert_eq!(fixture.read("sys/class/drm/card0/device/tile0/gt1/freq0/max_freq"), "1500");
+  assert!(! fixture.cpux(&["--xe-tiles", "1", "--xe-freq-max", "1ghz"]).status.success());
+}
+
+#[test]
+fn i915_per_gt() {
+  let fixture = Fixture::meteor_lake();
+  let out = fixture.cpux_ok(&["--i915"]);
+  assert!(out.contains("card0  i915    gt0"));
+  assert!(out.contains("card0  i915    gt1"));
+  assert!(Fixture::intel_laptop().cpux_ok(&["--i915"]).contains("card0  i915    n/a"));
+  fixture.cpux_ok(&["--i915-gts", "1", "--i915-freq-max", "800mhz"]);
+  assert_eq!(fixture.read("sys/class/drm/card0/gt/gt1/rps_max_freq_mhz"), "800");
+  assert_eq!(fixture.read("sys/class/drm/card0/gt/gt0/rps_max_freq_mhz"), "2250");
+  fixture.cpux_ok(&["--i915-freq-min", "300mhz"]);
+  assert_eq!(fixture.read("sys/class/drm/card0/gt/gt0/rps_min_freq_mhz"), "300");
+  assert_eq!(fixture.read("sys/class/drm/card0/gt/gt1/rps_min_freq_mhz"), "300");
+  assert!(! fixture.cpux(&["--i915-gts", "2", "--i915-freq-max", "1ghz"]).status.success());
+}
+
+#[test]
+fn i915_rc6_in_refresh_mode() {
+  use std::io::BufRead;
+  let fixture = Fixture::meteor_lake();
+  fixture.write("sys/class/drm/card0/gt/gt1/throttle_reason_ratl", "1");
+  let out = fixture.cpux_ok(&["--i915"]);
+  assert!(out.contains("RC6 residency"));
+  assert!(out.contains("card0  gt0 true  n/a           none"));
+  assert!(out.contains("ratl"));
+  let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_cpux"))
+    .arg("--root")
+    .arg(fixture.root())
+    .args(["--format", "json", "1"])
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let mut lines = std::io::BufReader::new(child.stdout.take().unwrap()).lines();
+  let first: serde_json::Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+  fixture.write("sys/class/drm/card0/gt/gt0/rc6_residency_ms", "1000500");
+  let second: serde_json::Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+  child.kill().unwrap();
+  child.wait().unwrap();
+  assert!(first["i915"][0]["gts"][0]["rc6"].is_null());
+  let rc6 = second["i915"][0]["gts"][0]["rc6"].as_f64().unwrap();
+  assert!(rc6 > 0. && rc6 <= 50., "{}", rc6);
+  assert_eq!(second["i915"][0]["gts"][1]["throttle_reasons"], serde_json::json!(["ratl"]));
+}
+
+#[test]
+fn short_frequencies() {
+  let fixture = Fixture::rockchip();
+  for arg in ["--devfreq-max", "--devfreq-min", "--xe-freq-max", "--xe-freq-min", "--i915-freq-boost"] {
+    let out = fixture.cpux(&[arg, "8g"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Error parsing frequency string: 8g"));
+  }
+  fixture.cpux_ok(&["--devfreq-max", "80"]);
+  assert_eq!(fixture.read("sys/class/devfreq/dmc/max_freq"), "80");
+}
@@ -0,0 +1,53 @@
+mod support;
+
+use {
+  cpux::cpu,
+  support::Fixture,
+};
+
+#[test]
+fn cpus_from_present() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert_eq!(cpu::cpus().unwrap(), (0..12).collect::<Vec<u64>>());
+}
+
+#[test]
+fn cpus_parses_ranges_and_lists() {
+  let fixture = Fixture::vm();
+  fixture.write("sys/devices/system/cpu/present", "0,2-3");
+  let _root = fixture.enter();
+  assert_eq!(cpu::cpus().unwrap(), vec![0, 2, 3]);
+}
+
+#[test]
+fn online_missing_file_for_existing_cpu_is_none() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert_eq!(cpu::online(0).unwrap(), None);
+  assert_eq!(cpu::online(1).unwrap(), Some(true));
+}
+
+#[test]
+fn online_missing_cpu_is_error() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert!(cpu::online(42).is_err());
+  assert!(cpu::try_online(0).is_err());
+}
+
+#[test]
+fn set_online_writes_file() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  cpu::set_online(3, false).unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu3/online"), "0");
+  assert_eq!(cpu::online(3).unwrap(), Some(false));
+}
+
+#[test]
+fn set_online_missing_cpu_is_error() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert!(cpu::set_online(4, true).is_err());
+}
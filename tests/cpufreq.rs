@@ -0,0 +1,109 @@
+mod support;
+
+use {
+  cpux::{cpufreq, units::Hertz},
+  support::Fixture,
+};
+
+#[test]
+fn available() {
+  let fixture = Fixture::amd_desktop();
+  let _root = fixture.enter();
+  assert!(cpufreq::available());
+  drop(_root);
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert!(! cpufreq::available());
+}
+
+#[test]
+fn governors() {
+  let fixture = Fixture::amd_desktop();
+  let _root = fixture.enter();
+  assert_eq!(cpufreq::governor(0).unwrap().as_deref(), Some("schedutil"));
+  assert_eq!(cpufreq::governors(15).unwrap().unwrap().len(), 6);
+}
+
+#[test]
+fn frequencies_are_hertz() {
+  let fixture = Fixture::amd_desktop();
+  let _root = fixture.enter();
+  assert_eq!(cpufreq::min_limit(0).unwrap().unwrap().hz(), 2_200_000_000);
+  assert_eq!(cpufreq::max_limit(0).unwrap().unwrap().hz(), 3_700_000_000);
+}
+
+#[test]
+fn set_min_max_writes_khz() {
+  let fixture = Fixture::amd_desktop();
+  let _root = fixture.enter();
+  cpufreq::set_max(3, "3.2ghz".parse::<Hertz>().unwrap()).unwrap();
+  cpufreq::set_min(3, Hertz::from_mhz(2400.)).unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy3/scaling_max_freq"), "3200000");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy3/scaling_min_freq"), "2400000");
+}
+
+#[test]
+fn missing_cpufreq_is_none_for_existing_cpu() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert!(cpufreq::cur(0).unwrap().is_none());
+  assert!(cpufreq::governor(3).unwrap().is_none());
+  assert!(cpufreq::set_governor(3, "performance").unwrap().is_none());
+  assert!(cpufreq::try_cur(0).is_err());
+  assert!(cpufreq::cur(4).is_err());
+}
+
+#[test]
+fn boost() {
+  let fixture = Fixture::amd_desktop();
+  let _root = fixture.enter();
+  assert_eq!(cpufreq::boost().unwrap(), Some(true));
+  assert!(cpufreq::policy_boost(0).unwrap().is_none());
+  cpufreq::set_boost(false).unwrap().unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/boost"), "0");
+  drop(_root);
+  let fixture = Fixture::amd_laptop();
+  let _root = fixture.enter();
+  assert!(cpufreq::boost().unwrap().is_none());
+  cpufreq::set_policy_boost(2, false).unwrap().unwrap();
+  assert_eq!(cpufreq::policy_boost(2).unwrap(), Some(false));
+  assert_eq!(cpufreq::policy_boost(3).unwrap(), Some(true));
+}
+
+#[test]
+fn policies() {
+  let fixture = Fixture::rockchip();
+  let _root = fixture.enter();
+  assert_eq!(cpufreq::policies().unwrap().unwrap(), vec![0, 4]);
+  assert_eq!(cpufreq::policy(5).unwrap(), Some(4));
+  assert_eq!(cpufreq::affected_cpus(4).unwrap().unwrap(), vec![4, 5]);
+  assert_eq!(cpufreq::related_cpus(0).unwrap().unwrap(), vec![0, 1, 2, 3]);
+  assert_eq!(cpufreq::policy_max_limit(0).unwrap().unwrap().hz(), 1_416_000_000);
+  cpufreq::set_policy_min(4, Hertz::from_mhz(600.)).unwrap().unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy4/scaling_min_freq"), "600000");
+  assert!(cpufreq::policy_governor(2).is_err());
+  fixture
+    .write("sys/devices/system/cpu/cpu4/online", "0")
+    .write("sys/devices/system/cpu/cpufreq/policy4/affected_cpus", "5");
+  assert_eq!(cpufreq::policy_active(4).unwrap(), Some(true));
+  fixture
+    .write("sys/devices/system/cpu/cpu5/online", "0")
+    .write("sys/devices/system/cpu/cpufreq/policy4/affected_cpus", "");
+  assert_eq!(cpufreq::policy_active(4).unwrap(), Some(false));
+}
+
+#[test]
+fn policy_driver_and_limits() {
+  let fixture = Fixture::amd_desktop();
+  let _root = fixture.enter();
+  assert_eq!(cpufreq::policy_driver(3).unwrap().unwrap(), "acpi-cpufreq");
+  assert_eq!(cpufreq::policy_bios_limit(3).unwrap().unwrap().hz(), 3_700_000_000);
+  assert_eq!(cpufreq::policy_cpuinfo_cur(3).unwrap().unwrap().hz(), 2_200_000_000);
+  assert_eq!(cpufreq::policy_transition_latency(3).unwrap(), Some(0));
+  assert!(cpufreq::policy_base(3).unwrap().is_none());
+  drop(_root);
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert_eq!(cpufreq::policy_base(0).unwrap().unwrap().hz(), 1_600_000_000);
+  assert!(cpufreq::policy_bios_limit(0).unwrap().is_none());
+}
@@ -0,0 +1,47 @@
+mod support;
+
+use {
+  cpux::cpuidle,
+  support::Fixture,
+};
+
+#[test]
+fn governor() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert!(cpuidle::available());
+  assert_eq!(cpuidle::driver().unwrap().unwrap(), "intel_idle");
+  assert_eq!(cpuidle::governor().unwrap().unwrap(), "menu");
+  assert_eq!(cpuidle::governors().unwrap().unwrap(), vec!["ladder", "menu", "teo"]);
+  cpuidle::set_governor("teo").unwrap().unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpuidle/current_governor"), "teo");
+  fixture.remove("sys/devices/system/cpu/cpuidle/current_governor");
+  fixture.write("sys/devices/system/cpu/cpuidle/current_governor_ro", "menu");
+  assert_eq!(cpuidle::governor().unwrap().unwrap(), "menu");
+}
+
+#[test]
+fn states() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert_eq!(cpuidle::states(3).unwrap().unwrap(), vec![0, 1, 2, 3, 4]);
+  assert_eq!(cpuidle::name(3, 2).unwrap().unwrap(), "C6");
+  assert_eq!(cpuidle::desc(3, 2).unwrap().unwrap(), "MWAIT 0x20");
+  assert_eq!(cpuidle::latency(3, 2).unwrap().unwrap(), 170);
+  assert_eq!(cpuidle::residency(3, 2).unwrap().unwrap(), 600);
+  assert_eq!(cpuidle::usage(3, 2).unwrap().unwrap(), 3000);
+  assert_eq!(cpuidle::time(3, 2).unwrap().unwrap(), 300_000);
+  assert!(! cpuidle::disable(3, 2).unwrap().unwrap());
+  cpuidle::set_disable(3, 2, true).unwrap().unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpu3/cpuidle/state2/disable"), "1");
+  assert!(cpuidle::states(99).is_err());
+}
+
+#[test]
+fn no_cpuidle() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert!(! cpuidle::available());
+  assert!(cpuidle::governor().unwrap().is_none());
+  assert!(cpuidle::states(0).unwrap().is_none());
+}
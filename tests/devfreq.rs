@@ -0,0 +1,58 @@
+mod support;
+
+use {
+  cpux::{devfreq, units::Hertz},
+  support::Fixture,
+};
+
+#[test]
+fn devices_and_frequencies() {
+  let fixture = Fixture::rockchip();
+  let _root = fixture.enter();
+  assert!(devfreq::available());
+  assert_eq!(devfreq::devices().unwrap().unwrap(), vec!["dmc", "ff9a0000.gpu"]);
+  assert_eq!(devfreq::cur("ff9a0000.gpu").unwrap().unwrap().hz(), 200_000_000);
+  assert_eq!(devfreq::max("ff9a0000.gpu").unwrap().unwrap().hz(), 800_000_000);
+  let freqs = devfreq::available_frequencies("dmc").unwrap().unwrap();
+  assert_eq!(freqs.iter().map(|f| f.hz()).collect::<Vec<u64>>(), vec![328_000_000, 666_000_000, 928_000_000]);
+  assert_eq!(devfreq::governor("dmc").unwrap().unwrap(), "simple_ondemand");
+  assert_eq!(devfreq::available_governors("dmc").unwrap().unwrap().len(), 4);
+  assert!(devfreq::cur("nope").unwrap().is_none());
+}
+
+#[test]
+fn trans_stat() {
+  let fixture = Fixture::rockchip();
+  let _root = fixture.enter();
+  let stat = devfreq::trans_stat("ff9a0000.gpu").unwrap().unwrap();
+  assert_eq!(stat.states.len(), 5);
+  assert!(stat.states[0].current);
+  assert!(! stat.states[1].current);
+  assert_eq!(stat.states[1].freq.hz(), 300_000_000);
+  assert_eq!(stat.states[1].transitions, vec![0, 0, 1, 0, 0]);
+  assert_eq!(stat.states[4].time_ms, 5000);
+  assert_eq!(stat.total_transitions, Some(4));
+  let stat = devfreq::parse_trans_stat("Not Supported.\n");
+  assert!(stat.states.is_empty());
+  assert!(stat.total_transitions.is_none());
+}
+
+#[test]
+fn set_governor_and_limits() {
+  let fixture = Fixture::rockchip();
+  let _root = fixture.enter();
+  devfreq::set_governor("dmc", "performance").unwrap().unwrap();
+  devfreq::set_min("ff9a0000.gpu", Hertz::from_mhz(300.)).unwrap().unwrap();
+  devfreq::set_max("ff9a0000.gpu", Hertz::from_mhz(600.)).unwrap().unwrap();
+  assert_eq!(fixture.read("sys/class/devfreq/dmc/governor"), "performance");
+  assert_eq!(fixture.read("sys/class/devfreq/ff9a0000.gpu/min_freq"), "300000000");
+  assert_eq!(fixture.read("sys/class/devfreq/ff9a0000.gpu/max_freq"), "600000000");
+}
+
+#[test]
+fn no_devfreq() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert!(! devfreq::available());
+  assert!(devfreq::devices().unwrap().is_none());
+}
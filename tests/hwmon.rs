@@ -0,0 +1,108 @@
+mod support;
+
+use {
+  cpux::{hwmon, summary::Summary, units::Watts},
+  support::Fixture,
+};
+
+#[test]
+fn chips_and_sensors() {
+  let fixture = Fixture::amd_desktop();
+  let _root = fixture.enter();
+  assert!(hwmon::available());
+  assert_eq!(hwmon::chips().unwrap(), Some(vec![0, 1]));
+  assert_eq!(hwmon::name(1).unwrap().as_deref(), Some("nct6775"));
+  assert_eq!(hwmon::temps(0).unwrap(), Some(vec![1, 3]));
+  assert_eq!(hwmon::temp_label(0, 3).unwrap().as_deref(), Some("Tccd1"));
+  assert_eq!(hwmon::temp(0, 3).unwrap().unwrap().millicelsius(), 55_250);
+  assert_eq!(hwmon::temp_crit(0, 1).unwrap().unwrap().celsius(), 105.);
+  assert_eq!(hwmon::fans(1).unwrap(), Some(vec![1, 2]));
+  assert_eq!(hwmon::fan(1, 1).unwrap().unwrap().rpm(), 1100);
+  assert_eq!(hwmon::voltages(1).unwrap(), Some(vec![0, 1]));
+  assert_eq!(hwmon::voltage(1, 1).unwrap().unwrap().millivolts(), 3344);
+}
+
+#[test]
+fn missing_attributes_are_none() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert!(hwmon::temp_label(0, 1).unwrap().is_none());
+  assert!(hwmon::fans(0).unwrap().unwrap().is_empty());
+  assert!(hwmon::try_temp_label(0, 1).is_err());
+}
+
+// A directory in place of the file fails reads with EISDIR, like a sensor which returns EIO.
+#[test]
+fn unreadable_sensors_are_none() {
+  let fixture = Fixture::amd_desktop();
+  fixture.remove("sys/class/hwmon/hwmon1/fan1_input").mkdir("sys/class/hwmon/hwmon1/fan1_input");
+  let _root = fixture.enter();
+  assert!(hwmon::try_fan(1, 1).is_err());
+  assert!(hwmon::fan(1, 1).unwrap().is_none());
+  let chips = Summary::read().unwrap().hwmon.unwrap();
+  assert!(chips[1].fans[0].input.is_none());
+  assert_eq!(chips[1].fans[1].input.as_ref().unwrap().rpm(), 0);
+}
+
+#[test]
+fn no_hwmon_class() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert!(! hwmon::available());
+  assert_eq!(hwmon::chips().unwrap(), None);
+  assert!(hwmon::package_temp(0).unwrap().is_none());
+}
+
+#[test]
+fn coretemp_cpu_mapping() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert_eq!(hwmon::package_temp(7).unwrap().unwrap().millicelsius(), 52_000);
+  assert_eq!(hwmon::core_temp(2).unwrap().unwrap().millicelsius(), 47_000);
+  assert_eq!(hwmon::core_temp(8).unwrap().unwrap().millicelsius(), 47_000);
+}
+
+#[test]
+fn k10temp_cpu_mapping() {
+  let fixture = Fixture::amd_desktop();
+  let _root = fixture.enter();
+  assert_eq!(hwmon::package_temp(9).unwrap().unwrap().millicelsius(), 61_500);
+  assert!(hwmon::core_temp(9).unwrap().is_none());
+  drop(_root);
+  fixture.hwmon_temp(0, 2, Some("Tdie"), 51_500);
+  let _root = fixture.enter();
+  assert_eq!(hwmon::package_temp(9).unwrap().unwrap().millicelsius(), 51_500);
+}
+
+#[test]
+fn pwm_mode_and_restore() {
+  let fixture = Fixture::amd_desktop();
+  let _root = fixture.enter();
+  assert_eq!(hwmon::pwms(1).unwrap(), Some(vec![1, 2]));
+  assert_eq!(hwmon::pwm_mode(1, 1).unwrap(), Some(hwmon::PwmMode::Auto(5)));
+  assert_eq!("manual".parse::<hwmon::PwmMode>().unwrap(), hwmon::PwmMode::Manual);
+  assert!("quiet".parse::<hwmon::PwmMode>().is_err());
+  let state = hwmon::PwmState::save(1, 1).unwrap();
+  hwmon::set_pwm_mode(1, 1, hwmon::PwmMode::Manual).unwrap();
+  hwmon::set_pwm(1, 1, 200).unwrap();
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm1_enable"), "1");
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm1"), "200");
+  state.restore().unwrap();
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon1/pwm1_enable"), "5");
+  assert!(hwmon::pwms(0).unwrap().unwrap().is_empty());
+}
+
+#[test]
+fn gpu_power_cap() {
+  let fixture = Fixture::workstation();
+  let _root = fixture.enter();
+  assert_eq!(hwmon::power_cap(1).unwrap().unwrap().microwatts(), 150_000_000);
+  assert_eq!(hwmon::power_cap_min(1).unwrap().unwrap().microwatts(), 100_000_000);
+  assert_eq!(hwmon::power_cap_max(1).unwrap().unwrap().microwatts(), 186_000_000);
+  assert_eq!(hwmon::power_average(1).unwrap().unwrap().microwatts(), 35_000_000);
+  assert_eq!(hwmon::power_cap(0).unwrap().unwrap().microwatts(), 15_000_000);
+  assert!(hwmon::power_cap_max(0).unwrap().is_none());
+  assert!(hwmon::power_average(0).unwrap().is_none());
+  hwmon::set_power_cap(0, Watts::from_watts(12.)).unwrap();
+  assert_eq!(fixture.read("sys/class/hwmon/hwmon0/power1_max"), "12000000");
+}
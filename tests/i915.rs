@@ -0,0 +1,84 @@
+mod support;
+
+use {
+  cpux::{i915, units::Hertz},
+  support::Fixture,
+};
+
+#[test]
+fn cards_filters_by_driver() {
+  let fixture = Fixture::intel_laptop();
+  fixture.drm_card(1, "0000:01:00.0", "nouveau");
+  let _root = fixture.enter();
+  assert!(i915::available());
+  assert_eq!(i915::cards().unwrap(), Some(vec![0]));
+}
+
+#[test]
+fn no_cards() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert!(! i915::available());
+  assert_eq!(i915::cards().unwrap(), Some(vec![]));
+}
+
+#[test]
+fn frequencies() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert_eq!(i915::max_limit(0).unwrap().unwrap().hz(), 1_150_000_000);
+  assert_eq!(i915::min(0).unwrap().unwrap().hz(), 300_000_000);
+  assert_eq!(i915::actual(0).unwrap().unwrap().hz(), 300_000_000);
+}
+
+#[test]
+fn set_frequencies_writes_mhz() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  i915::set_max(0, Hertz::from_mhz(900.)).unwrap();
+  i915::set_boost(0, "1.1ghz".parse::<Hertz>().unwrap()).unwrap();
+  assert_eq!(fixture.read("sys/class/drm/card0/gt_max_freq_mhz"), "900");
+  assert_eq!(fixture.read("sys/class/drm/card0/gt_boost_freq_mhz"), "1100");
+}
+
+#[test]
+fn missing_files_are_none() {
+  let fixture = Fixture::intel_laptop();
+  fixture.remove("sys/class/drm/card0/gt_RP1_freq_mhz");
+  let _root = fixture.enter();
+  assert!(i915::optimum_limit(0).unwrap().is_none());
+  assert!(i915::try_optimum_limit(0).is_err());
+}
+
+#[test]
+fn per_gt_frequencies() {
+  let fixture = Fixture::meteor_lake();
+  let _root = fixture.enter();
+  assert_eq!(i915::gts(0).unwrap(), Some(vec![0, 1]));
+  assert_eq!(i915::gt_max_limit(0, 0).unwrap().unwrap().mhz(), 2250.);
+  assert_eq!(i915::gt_max_limit(0, 1).unwrap().unwrap().mhz(), 1000.);
+  assert_eq!(i915::gt_optimum_limit(0, 1).unwrap().unwrap().mhz(), 325.);
+  i915::set_gt_max(0, 1, Hertz::from_mhz(800.)).unwrap();
+  assert_eq!(fixture.read("sys/class/drm/card0/gt/gt1/rps_max_freq_mhz"), "800");
+  assert_eq!(fixture.read("sys/class/drm/card0/gt/gt0/rps_max_freq_mhz"), "2250");
+  drop(_root);
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert!(i915::gts(0).unwrap().is_none());
+  assert!(i915::gt_actual(0, 0).unwrap().is_none());
+}
+
+#[test]
+fn rc6_and_throttle_reasons() {
+  let fixture = Fixture::meteor_lake();
+  fixture.write("sys/class/drm/card0/gt/gt0/throttle_reason_pl1", "1");
+  fixture.write("sys/class/drm/card0/gt/gt0/throttle_reason_thermal", "1");
+  fixture.write("sys/class/drm/card0/gt/gt1/rc6_enable", "0");
+  let _root = fixture.enter();
+  assert_eq!(i915::gt_rc6_enable(0, 0).unwrap(), Some(true));
+  assert_eq!(i915::gt_rc6_enable(0, 1).unwrap(), Some(false));
+  assert_eq!(i915::gt_rc6_residency_ms(0, 0).unwrap(), Some(1_000_000));
+  assert_eq!(i915::gt_throttle_reasons(0, 0).unwrap().unwrap(), vec!["pl1", "thermal"]);
+  assert!(i915::gt_throttle_reasons(0, 1).unwrap().unwrap().is_empty());
+  assert!(i915::gt_throttle_reasons(0, 2).unwrap().is_none());
+}
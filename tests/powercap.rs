@@ -0,0 +1,71 @@
+mod support;
+
+use {
+  cpux::{
+    powercap::{self, ZoneId},
+    units::Watts,
+  },
+  support::Fixture,
+};
+
+#[test]
+fn zones_and_constraints() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert!(powercap::available());
+  let zones: Vec<String> = powercap::zones().unwrap().unwrap().iter().map(ZoneId::to_string).collect();
+  assert_eq!(zones, vec!["intel-rapl:0", "intel-rapl:0:0", "intel-rapl:0:1", "intel-rapl:1"]);
+  let package = ZoneId::new(0, None);
+  assert_eq!(powercap::name(package).unwrap().as_deref(), Some("package-0"));
+  assert_eq!(powercap::zones_named("uncore").unwrap(), vec![ZoneId::new(0, Some(1))]);
+  assert_eq!(powercap::enabled(package).unwrap(), Some(true));
+  assert_eq!(powercap::energy_uj(package).unwrap(), Some(71_234_567_890));
+  assert_eq!(powercap::max_energy_range_uj(package).unwrap(), Some(262_143_328_850));
+  assert_eq!(powercap::constraints(package).unwrap(), Some(vec![0, 1]));
+  assert_eq!(powercap::constraint_named(package, "short_term").unwrap(), Some(1));
+  assert_eq!(powercap::power_limit(package, 0).unwrap().unwrap().watts(), 15.);
+  assert_eq!(powercap::time_window_us(package, 1).unwrap(), Some(2_440));
+  assert!(powercap::max_power(ZoneId::new(0, Some(0)), 1).unwrap().is_none());
+}
+
+#[test]
+fn set_limit_and_enabled() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  let package = ZoneId::new(0, None);
+  powercap::set_power_limit(package, 0, "12.5W".parse::<Watts>().unwrap()).unwrap();
+  powercap::set_enabled(package, false).unwrap();
+  assert_eq!(fixture.read("sys/class/powercap/intel-rapl:0/constraint_0_power_limit_uw"), "12500000");
+  assert_eq!(fixture.read("sys/class/powercap/intel-rapl:0/enabled"), "0");
+}
+
+#[test]
+fn no_powercap_class() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert!(! powercap::available());
+  assert_eq!(powercap::zones().unwrap(), None);
+}
+
+#[test]
+fn parse_zone_ids_and_watts() {
+  assert_eq!("1".parse::<ZoneId>().unwrap(), ZoneId::new(1, None));
+  assert_eq!("0:2".parse::<ZoneId>().unwrap(), ZoneId::new(0, Some(2)));
+  assert_eq!("intel-rapl:0:2".parse::<ZoneId>().unwrap(), ZoneId::new(0, Some(2)));
+  assert!("package-0".parse::<ZoneId>().is_err());
+  assert_eq!("15w".parse::<Watts>().unwrap().microwatts(), 15_000_000);
+  assert_eq!("28.5W".parse::<Watts>().unwrap().microwatts(), 28_500_000);
+  assert_eq!("800mw".parse::<Watts>().unwrap().microwatts(), 800_000);
+  assert_eq!("9".parse::<Watts>().unwrap().microwatts(), 9_000_000);
+  assert!("fast".parse::<Watts>().is_err());
+  assert!("-5w".parse::<Watts>().is_err());
+  assert_eq!(Watts::from_watts(28.5).to_string(), "28.5 W");
+}
+
+#[test]
+fn energy_delta_wraps() {
+  assert_eq!(powercap::energy_delta_uj(1_000, 4_000, None), Some(3_000));
+  assert_eq!(powercap::energy_delta_uj(262_143_000_000, 1_000, Some(262_143_328_850)), Some(328_850 + 1_000));
+  assert_eq!(powercap::energy_delta_uj(5_000, 1_000, None), None);
+  assert_eq!(powercap::energy_delta_uj(5_000, 1_000, Some(4_000)), None);
+}
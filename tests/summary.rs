@@ -0,0 +1,149 @@
+mod support;
+
+use {
+  cpux::summary::Summary,
+  std::time::Duration,
+  support::Fixture,
+};
+
+#[test]
+fn intel_laptop() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  let summary = Summary::read().unwrap();
+  assert_eq!(summary.cpus.len(), 12);
+  assert_eq!(summary.cpus[3].max_limit.as_ref().unwrap().hz(), 4_700_000_000);
+  assert_eq!(summary.cpus[3].epb, Some(6));
+  assert_eq!(summary.intel_pstate.unwrap().status.as_deref(), Some("active"));
+  let cards = summary.i915.unwrap();
+  assert_eq!(cards.len(), 1);
+  assert_eq!(cards[0].boost.as_ref().unwrap().hz(), 1_150_000_000);
+}
+
+#[test]
+fn vm() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  let summary = Summary::read().unwrap();
+  assert_eq!(summary.cpus.len(), 4);
+  assert!(summary.cpus[0].online);
+  assert!(summary.cpus[0].cur.is_none());
+  assert!(summary.cpus[0].governors.is_none());
+  assert!(summary.intel_pstate.is_none());
+  assert!(summary.i915.is_none());
+}
+
+#[test]
+fn intel_pstate_off() {
+  let fixture = Fixture::intel_laptop();
+  fixture.write("sys/devices/system/cpu/intel_pstate/status", "off\n");
+  for attr in ["no_turbo", "turbo_pct", "num_pstates"] {
+    let path = format!("sys/devices/system/cpu/intel_pstate/{}", attr);
+    fixture.remove(&path).mkdir(&path);
+  }
+  let _root = fixture.enter();
+  let pstate = Summary::read().unwrap().intel_pstate.unwrap();
+  assert_eq!(pstate.status.as_deref(), Some("off"));
+  assert!(pstate.no_turbo.is_none());
+  assert!(pstate.turbo_pct.is_none());
+  assert!(pstate.num_pstates.is_none());
+  assert!(pstate.max_perf_pct.is_some());
+}
+
+#[test]
+fn inactive_policy() {
+  let fixture = Fixture::rockchip();
+  fixture
+    .write("sys/devices/system/cpu/cpu4/online", "0")
+    .write("sys/devices/system/cpu/cpu5/online", "0")
+    .write("sys/devices/system/cpu/cpufreq/policy4/affected_cpus", "");
+  // An inactive policy's other attributes fail with EBUSY, here a directory.
+  for attr in ["related_cpus", "scaling_governor"] {
+    let path = format!("sys/devices/system/cpu/cpufreq/policy4/{}", attr);
+    fixture.remove(&path).mkdir(&path);
+  }
+  let _root = fixture.enter();
+  let policies = Summary::read().unwrap().policies.unwrap();
+  assert!(policies[0].active);
+  assert!(policies[0].governor.is_some());
+  assert!(! policies[1].active);
+  assert!(policies[1].related_cpus.is_none());
+  assert!(policies[1].governor.is_none());
+}
+
+#[test]
+fn gpu_power() {
+  let fixture = Fixture::workstation();
+  let _root = fixture.enter();
+  let summary = Summary::read().unwrap();
+  let i915 = summary.i915.unwrap();
+  let power = i915[0].power.as_ref().unwrap();
+  assert_eq!(power.chip_id, 0);
+  assert_eq!(power.cap.as_ref().unwrap().microwatts(), 15_000_000);
+  let amdgpu = summary.amdgpu.unwrap();
+  let power = amdgpu[0].power.as_ref().unwrap();
+  assert_eq!(power.chip_id, 1);
+  assert_eq!(power.average.as_ref().unwrap().microwatts(), 35_000_000);
+}
+
+#[test]
+fn gpu_identification() {
+  let fixture = Fixture::workstation();
+  let _root = fixture.enter();
+  let gpus = Summary::read().unwrap().gpus.unwrap();
+  assert_eq!(gpus.len(), 2);
+  assert_eq!(gpus[0].slot.as_deref(), Some("0000:00:02.0"));
+  assert_eq!(gpus[0].vendor_id, Some(0x8086));
+  assert_eq!(gpus[0].device_id, Some(0x4680));
+  assert_eq!(gpus[0].boot_vga, Some(false));
+  assert_eq!(gpus[0].render_node.as_deref(), Some("renderD128"));
+  assert_eq!(gpus[0].device.as_deref(), Some("AlderLake-S GT1"));
+  assert_eq!(gpus[0].subsystem.as_deref(), Some("ROG STRIX Z690-A"));
+  assert_eq!(gpus[1].driver.as_deref(), Some("amdgpu"));
+  assert_eq!(gpus[1].boot_vga, Some(true));
+  assert_eq!(gpus[1].vendor.as_deref(), Some("Advanced Micro Devices, Inc. [AMD/ATI]"));
+  assert_eq!(gpus[1].device.as_deref(), Some("Navi 21 [Radeon RX 6800/6800 XT / 6900 XT]"));
+  fixture.remove("usr/share/hwdata/pci.ids");
+  let gpus = Summary::read().unwrap().gpus.unwrap();
+  assert_eq!(gpus[1].subsystem_vendor_id, Some(0x1da2));
+  assert!(gpus[1].vendor.is_none());
+  assert!(gpus[1].device.is_none());
+  fixture.mkdir("usr/share/misc");
+  std::fs::write(fixture.path("usr/share/misc/pci.ids"), b"# \xff\n8086  Intel Corporation\n").unwrap();
+  let gpus = Summary::read().unwrap().gpus.unwrap();
+  assert_eq!(gpus[0].vendor.as_deref(), Some("Intel Corporation"));
+  assert!(gpus[0].device.is_none());
+}
+
+#[test]
+fn rapl_power_from_energy() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  let prev = Summary::read().unwrap();
+  assert!(prev.rapl.as_ref().unwrap()[0].power.is_none());
+  fixture.write("sys/class/powercap/intel-rapl:0/energy_uj", "71246567890");
+  fixture.write("sys/class/powercap/intel-rapl:1/energy_uj", "500000");
+  let mut cur = Summary::read().unwrap();
+  cur.set_deltas(&prev, Duration::from_secs(2));
+  let zones = cur.rapl.unwrap();
+  assert_eq!(zones[0].power.as_ref().unwrap().watts(), 6.);
+  assert_eq!(zones[1].power.as_ref().unwrap().watts(), 0.);
+  // psys wrapped around at max_energy_range_uj
+  assert_eq!(zones[3].power.as_ref().unwrap().microwatts(), (262_143_328_850 - 120_000_000_000 + 500_000) / 2);
+}
+
+#[test]
+fn i915_rc6_from_residency() {
+  let fixture = Fixture::meteor_lake();
+  let _root = fixture.enter();
+  let prev = Summary::read().unwrap();
+  assert!(prev.i915.as_ref().unwrap()[0].gts[0].rc6.is_none());
+  fixture.write("sys/class/drm/card0/gt/gt0/rc6_residency_ms", "1001500");
+  fixture.write("sys/class/drm/card0/gt/gt1/rc6_residency_ms", "1003000");
+  let mut cur = Summary::read().unwrap();
+  cur.set_deltas(&prev, Duration::from_secs(2));
+  let gts = &cur.i915.unwrap()[0].gts;
+  assert_eq!(gts[0].rc6, Some(75.));
+  // residency counters can run slightly ahead of the wall clock
+  assert_eq!(gts[1].rc6, Some(100.));
+}
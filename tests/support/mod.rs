@@ -0,0 +1,597 @@
+#![allow(dead_code)]
+
+use {
+  std::{
+    fs,
+    os::unix::fs::symlink,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    sync::{Mutex, MutexGuard},
+  },
+  tempfile::TempDir,
+};
+
+// cpux keeps its sysfs root in a global, so tests which call library functions
+// in-process take turns.
+static ROOT_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct Entered(MutexGuard<'static, ()>);
+
+impl Drop for Entered {
+  fn drop(&mut self) { cpux::set_root("/"); }
+}
+
+pub struct Fixture {
+  dir: TempDir,
+}
+
+impl Fixture {
+
+  pub fn empty() -> Self {
+    let fixture = Self { dir: tempfile::tempdir().expect("tempdir") };
+    fixture.mkdir("sys/devices/system/cpu");
+    fixture.mkdir("sys/class/drm");
+    fixture.mkdir("sys/module");
+    fixture
+  }
+
+  pub fn root(&self) -> &Path { self.dir.path() }
+
+  pub fn path<P: AsRef<Path>>(&self, path: P) -> PathBuf { self.root().join(path) }
+
+  pub fn mkdir<P: AsRef<Path>>(&self, path: P) -> &Self {
+    fs::create_dir_all(self.path(path)).expect("mkdir");
+    self
+  }
+
+  pub fn write<P: AsRef<Path>>(&self, path: P, val: &str) -> &Self {
+    let path = self.path(path);
+    fs::create_dir_all(path.parent().unwrap()).expect("mkdir");
+    fs::write(&path, format!("{}\n", val)).expect("write");
+    self
+  }
+
+  pub fn symlink<P: AsRef<Path>, T: AsRef<Path>>(&self, path: P, target: T) -> &Self {
+    let path = self.path(path);
+    fs::create_dir_all(path.parent().unwrap()).expect("mkdir");
+    symlink(target, path).expect("symlink");
+    self
+  }
+
+  pub fn remove<P: AsRef<Path>>(&self, path: P) -> &Self {
+    let path = self.path(path);
+    if path.is_dir() && ! path.is_symlink() { fs::remove_dir_all(path).expect("remove"); }
+    else { fs::remove_file(path).expect("remove"); }
+    self
+  }
+
+  pub fn read<P: AsRef<Path>>(&self, path: P) -> String {
+    fs::read_to_string(self.path(path)).expect("read").trim_end().to_string()
+  }
+
+  // Lock the global root and point cpux at this fixture until the guard drops.
+  pub fn enter(&self) -> Entered {
+    let lock = ROOT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    cpux::set_root(self.root());
+    Entered(lock)
+  }
+
+  // Run the cpux binary against this fixture.
+  pub fn cpux(&self, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cpux"))
+      .arg("--root")
+      .arg(self.root())
+      .args(args)
+      .env_remove("CPUX_LOG_LEVEL")
+      .output()
+      .expect("run cpux")
+  }
+
+  pub fn cpux_ok(&self, args: &[&str]) -> String {
+    let out = self.cpux(args);
+    assert!(out.status.success(), "cpux {:?} failed: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8(out.stdout).expect("utf8")
+  }
+
+  pub fn cpu_dir(cpu_id: u64) -> String { format!("sys/devices/system/cpu/cpu{}", cpu_id) }
+
+  pub fn policy_dir(policy_id: u64) -> String { format!("sys/devices/system/cpu/cpufreq/policy{}", policy_id) }
+
+  pub fn card_dir(card_id: u64) -> String { format!("sys/class/drm/card{}", card_id) }
+
+  pub fn hwmon_dir(chip_id: u64) -> String { format!("sys/class/hwmon/hwmon{}", chip_id) }
+
+  // `count` CPUs, all online. cpu0 has no `online` file, as on most x86 systems.
+  pub fn cpus(&self, count: u64) -> &Self {
+    let last = count - 1;
+    self.write("sys/devices/system/cpu/present", &format!("0-{}", last));
+    self.write("sys/devices/system/cpu/possible", &format!("0-{}", last));
+    self.write("sys/devices/system/cpu/online", &format!("0-{}", last));
+    for cpu_id in 0..count {
+      self.mkdir(Self::cpu_dir(cpu_id));
+      if cpu_id > 0 { self.write(format!("{}/online", Self::cpu_dir(cpu_id)), "1"); }
+    }
+    self
+  }
+
+  // Core ids for `count` CPUs in one package, with `threads` SMT siblings numbered like Linux does
+  // on x86, e.g. cpu0 and cpu6 share core 0 on a 6-core 12-thread part.
+  pub fn topology(&self, count: u64, threads: u64) -> &Self {
+    for cpu_id in 0..count {
+      self.write(format!("{}/topology/core_id", Self::cpu_dir(cpu_id)), &(cpu_id % (count / threads)).to_string());
+      self.write(format!("{}/topology/physical_package_id", Self::cpu_dir(cpu_id)), "0");
+    }
+    self
+  }
+
+  // One cpufreq policy per CPU, linked from cpuN/cpufreq as the kernel does.
+  pub fn cpufreq(&self, count: u64, driver: &str, governors: &[&str], min_khz: u64, max_khz: u64) -> &Self {
+    for cpu_id in 0..count {
+      self.cpufreq_policy(cpu_id, &[cpu_id], driver, governors, min_khz, max_khz);
+    }
+    self
+  }
+
+  // A cpufreq policy shared by `cpus`, named after the first of them.
+  pub fn cpufreq_policy(&self, policy_id: u64, cpus: &[u64], driver: &str, governors: &[&str], min_khz: u64, max_khz: u64) -> &Self {
+    let policy = Self::policy_dir(policy_id);
+    let cpu_list = cpus.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ");
+    self.write(format!("{}/affected_cpus", policy), &cpu_list);
+    self.write(format!("{}/related_cpus", policy), &cpu_list);
+    self.write(format!("{}/scaling_driver", policy), driver);
+    self.write(format!("{}/scaling_governor", policy), governors[0]);
+    self.write(format!("{}/scaling_available_governors", policy), &governors.join(" "));
+    self.write(format!("{}/scaling_cur_freq", policy), &min_khz.to_string());
+    self.write(format!("{}/scaling_min_freq", policy), &min_khz.to_string());
+    self.write(format!("{}/scaling_max_freq", policy), &max_khz.to_string());
+    self.write(format!("{}/cpuinfo_min_freq", policy), &min_khz.to_string());
+    self.write(format!("{}/cpuinfo_max_freq", policy), &max_khz.to_string());
+    self.write(format!("{}/cpuinfo_transition_latency", policy), "0");
+    for cpu_id in cpus {
+      self.symlink(format!("{}/cpufreq", Self::cpu_dir(*cpu_id)), format!("../cpufreq/policy{}", policy_id));
+    }
+    self
+  }
+
+  // cpuidle states (name, desc, latency µs, residency µs) of `count` cpus, all enabled.
+  pub fn cpuidle(&self, count: u64, driver: &str, states: &[(&str, &str, u64, u64)]) -> &Self {
+    self.write("sys/devices/system/cpu/cpuidle/current_driver", driver);
+    self.write("sys/devices/system/cpu/cpuidle/current_governor", "menu");
+    self.write("sys/devices/system/cpu/cpuidle/available_governors", "ladder menu teo");
+    for cpu_id in 0..count {
+      for (state_id, (name, desc, latency, residency)) in states.iter().enumerate() {
+        let state = format!("{}/cpuidle/state{}", Self::cpu_dir(cpu_id), state_id);
+        self.write(format!("{}/name", state), name);
+        self.write(format!("{}/desc", state), desc);
+        self.write(format!("{}/latency", state), &latency.to_string());
+        self.write(format!("{}/residency", state), &residency.to_string());
+        self.write(format!("{}/usage", state), &(1000 * (state_id + 1)).to_string());
+        self.write(format!("{}/time", state), &(100_000 * (state_id + 1)).to_string());
+        self.write(format!("{}/disable", state), "0");
+      }
+    }
+    self
+  }
+
+  pub fn intel_pstate(&self, count: u64) -> &Self {
+    let epps = "default performance balance_performance balance_power power";
+    self.write("sys/devices/system/cpu/intel_pstate/status", "active");
+    self.write("sys/devices/system/cpu/intel_pstate/no_turbo", "0");
+    self.write("sys/devices/system/cpu/intel_pstate/max_perf_pct", "100");
+    self.write("sys/devices/system/cpu/intel_pstate/min_perf_pct", "9");
+    self.write("sys/devices/system/cpu/intel_pstate/turbo_pct", "33");
+    self.write("sys/devices/system/cpu/intel_pstate/num_pstates", "44");
+    self.write("sys/devices/system/cpu/intel_pstate/hwp_dynamic_boost", "0");
+    for cpu_id in 0..count {
+      let policy = Self::policy_dir(cpu_id);
+      self.write(format!("{}/energy_performance_preference", policy), "balance_performance");
+      self.write(format!("{}/energy_performance_available_preferences", policy), epps);
+      self.write(format!("{}/base_frequency", policy), "1600000");
+      self.write(format!("{}/power/energy_perf_bias", Self::cpu_dir(cpu_id)), "6");
+    }
+    self
+  }
+
+  // amd-pstate-epp in active mode, with preferred core rankings alternating between the CCDs.
+  pub fn amd_pstate(&self, count: u64) -> &Self {
+    let epps = "default performance balance_performance balance_power power";
+    self.write("sys/devices/system/cpu/amd_pstate/status", "active");
+    self.write("sys/devices/system/cpu/amd_pstate/prefcore", "enabled");
+    for cpu_id in 0..count {
+      let policy = Self::policy_dir(cpu_id);
+      self.write(format!("{}/energy_performance_preference", policy), "balance_performance");
+      self.write(format!("{}/energy_performance_available_preferences", policy), epps);
+      self.write(format!("{}/boost", policy), "1");
+      self.write(format!("{}/amd_pstate_highest_perf", policy), "166");
+      self.write(format!("{}/amd_pstate_lowest_nonlinear_freq", policy), "1800000");
+      self.write(format!("{}/amd_pstate_max_freq", policy), "5137000");
+      self.write(format!("{}/amd_pstate_prefcore_ranking", policy), &(236 - (cpu_id % 8) * 5).to_string());
+    }
+    self
+  }
+
+  // A PCI device at `slot` bound to `driver`, with its DRM card linked from /sys/class/drm.
+  pub fn drm_card(&self, card_id: u64, slot: &str, driver: &str) -> &Self {
+    let device = format!("sys/devices/pci0000:00/{}", slot);
+    self.mkdir(format!("sys/bus/pci/drivers/{}", driver));
+    self.mkdir(format!("sys/module/{}", driver));
+    self.symlink(format!("{}/driver", device), format!("../../../bus/pci/drivers/{}", driver));
+    self.mkdir(format!("{}/drm/card{}", device, card_id));
+    self.symlink(format!("{}/drm/card{}/device", device, card_id), "../../../".to_string() + slot);
+    self.symlink(Self::card_dir(card_id), format!("../../devices/pci0000:00/{}/drm/card{}", slot, card_id));
+    self
+  }
+
+  // PCI ids (vendor, device, subsystem vendor, subsystem device) and render node of a card.
+  pub fn drm_pci(&self, card_id: u64, slot: &str, ids: [u16; 4], boot_vga: bool) -> &Self {
+    let device = format!("sys/devices/pci0000:00/{}", slot);
+    for (name, id) in ["vendor", "device", "subsystem_vendor", "subsystem_device"].iter().zip(ids.iter()) {
+      self.write(format!("{}/{}", device, name), &format!("0x{:04x}", id));
+    }
+    self.write(format!("{}/boot_vga", device), if boot_vga { "1" } else { "0" });
+    self.mkdir(format!("{}/drm/renderD{}", device, 128 + card_id))
+  }
+
+  // An excerpt of pci.ids with the devices of the presets.
+  pub fn pci_ids(&self) -> &Self {
+    self.write("usr/share/hwdata/pci.ids", &[
+      "# List of PCI ID's",
+      "1002  Advanced Micro Devices, Inc. [AMD/ATI]",
+      "\t73bf  Navi 21 [Radeon RX 6800/6800 XT / 6900 XT]",
+      "\t\t1da2 e438  Radeon RX 6800 XT Nitro+",
+      "8086  Intel Corporation",
+      "\t4680  AlderLake-S GT1",
+      "\t\t1043 8694  ROG STRIX Z690-A",
+      "\t7d55  Meteor Lake-P [Intel Arc Graphics]",
+      "C 03  Display controller",
+      "\t00  VGA compatible controller",
+    ].join("\n"))
+  }
+
+  pub fn i915(&self, card_id: u64) -> &Self {
+    let card = Self::card_dir(card_id);
+    for (name, mhz) in &[
+      ("gt_RP0_freq_mhz", 1150),
+      ("gt_RP1_freq_mhz", 300),
+      ("gt_RPn_freq_mhz", 300),
+      ("gt_act_freq_mhz", 300),
+      ("gt_boost_freq_mhz", 1150),
+      ("gt_cur_freq_mhz", 300),
+      ("gt_max_freq_mhz", 1150),
+      ("gt_min_freq_mhz", 300),
+    ] {
+      self.write(format!("{}/{}", card, name), &mhz.to_string());
+    }
+    self
+  }
+
+  // An i915 GT under `cardN/gt/gt<gt>`, frequencies in MHz.
+  pub fn i915_gt(&self, card_id: u64, gt_id: u64, rpn: u64, rp0: u64) -> &Self {
+    let gt = format!("{}/gt/gt{}", Self::card_dir(card_id), gt_id);
+    for (name, mhz) in &[
+      ("rps_RP0_freq_mhz", rp0),
+      ("rps_RP1_freq_mhz", rpn + (rp0 - rpn) / 4),
+      ("rps_RPn_freq_mhz", rpn),
+      ("rps_act_freq_mhz", rpn),
+      ("rps_boost_freq_mhz", rp0),
+      ("rps_cur_freq_mhz", rpn),
+      ("rps_max_freq_mhz", rp0),
+      ("rps_min_freq_mhz", rpn),
+    ] {
+      self.write(format!("{}/{}", gt, name), &mhz.to_string());
+    }
+    self.write(format!("{}/rc6_enable", gt), "1");
+    self.write(format!("{}/rc6_residency_ms", gt), "1000000");
+    for reason in &["status", "pl1", "pl2", "thermal", "prochot", "ratl", "vr_tdc", "vr_thermalert"] {
+      self.write(format!("{}/throttle_reason_{}", gt, reason), "0");
+    }
+    self
+  }
+
+  // An xe GT under `device/tile<tile>/gt<gt>/freq0`, frequencies in MHz.
+  pub fn xe_gt(&self, card_id: u64, tile_id: u64, gt_id: u64, rpn: u64, rp0: u64) -> &Self {
+    let freq = format!("{}/device/tile{}/gt{}/freq0", Self::card_dir(card_id), tile_id, gt_id);
+    for (name, mhz) in &[
+      ("act_freq", rpn),
+      ("cur_freq", rpn),
+      ("min_freq", rpn),
+      ("max_freq", rp0),
+      ("rp0_freq", rp0),
+      ("rpe_freq", rpn + (rp0 - rpn) / 4),
+      ("rpn_freq", rpn),
+    ] {
+      self.write(format!("{}/{}", freq, name), &mhz.to_string());
+    }
+    self
+  }
+
+  // RDNA2-like amdgpu power management files, in auto with the 3D_FULL_SCREEN profile.
+  pub fn amdgpu(&self, card_id: u64) -> &Self {
+    let device = format!("{}/device", Self::card_dir(card_id));
+    let profiles = [
+      "PROFILE_INDEX(NAME) CLOCK_TYPE(NAME) FPS MinActiveFreqType MinActiveFreq BoosterFreqType BoosterFreq",
+      " 0 BOOTUP_DEFAULT :",
+      "                    0(       GFXCLK)       0       5       1       0       4     800",
+      "                    1(       SOCCLK)       0       5       1       0       4     800",
+      " 1 3D_FULL_SCREEN*:",
+      "                    0(       GFXCLK)       1       5       1       0       4     650",
+      "                    1(       SOCCLK)       1       5       1       0       4     650",
+      " 2   POWER_SAVING :",
+      "                    0(       GFXCLK)       0       5       1       0       4     800",
+      " 3          VIDEO :",
+      " 4             VR :",
+      " 5        COMPUTE :",
+      " 6         CUSTOM :",
+    ];
+    self.write(format!("{}/power_dpm_force_performance_level", device), "auto");
+    self.write(format!("{}/pp_power_profile_mode", device), &profiles.join("\n"));
+    self.write(format!("{}/pp_dpm_sclk", device), "0: 500Mhz\n1: 1200Mhz *\n2: 2615Mhz");
+    self.write(format!("{}/pp_dpm_mclk", device), "0: 96Mhz\n1: 456Mhz\n2: 673Mhz\n3: 1000Mhz *");
+    self.write(format!("{}/pp_od_clk_voltage", device), &[
+      "OD_SCLK:",
+      "0: 500Mhz",
+      "1: 2615Mhz",
+      "OD_MCLK:",
+      "0: 97Mhz",
+      "1: 1000MHz",
+      "OD_VDDGFX_OFFSET:",
+      "0mV",
+      "OD_RANGE:",
+      "SCLK:     500Mhz       3150Mhz",
+      "MCLK:     674Mhz       1200Mhz",
+    ].join("\n"));
+    self
+  }
+
+  // An hwmon chip named `name` under `sys/devices/<device>`, linked from /sys/class/hwmon.
+  pub fn hwmon(&self, chip_id: u64, name: &str, device: &str) -> &Self {
+    let dir = format!("devices/{}/hwmon/hwmon{}", device, chip_id);
+    self.write(format!("sys/{}/name", dir), name);
+    self.symlink(Self::hwmon_dir(chip_id), format!("../../{}", dir));
+    self
+  }
+
+  // Power cap files of a GPU hwmon chip in µW, as exposed by amdgpu.
+  pub fn hwmon_power_cap(&self, chip_id: u64, cap: u64, min: u64, max: u64, average: u64) -> &Self {
+    let chip = Self::hwmon_dir(chip_id);
+    self.write(format!("{}/power1_cap", chip), &cap.to_string());
+    self.write(format!("{}/power1_cap_min", chip), &min.to_string());
+    self.write(format!("{}/power1_cap_max", chip), &max.to_string());
+    self.write(format!("{}/power1_average", chip), &average.to_string());
+    self
+  }
+
+  // Power limit files of a GPU hwmon chip in µW, as exposed by i915 and xe.
+  pub fn hwmon_power_max(&self, chip_id: u64, max: u64, rated_max: u64) -> &Self {
+    let chip = Self::hwmon_dir(chip_id);
+    self.write(format!("{}/power1_max", chip), &max.to_string());
+    self.write(format!("{}/power1_rated_max", chip), &rated_max.to_string());
+    self
+  }
+
+  pub fn hwmon_temp(&self, chip_id: u64, temp_id: u64, label: Option<&str>, millicelsius: i64) -> &Self {
+    let chip = Self::hwmon_dir(chip_id);
+    self.write(format!("{}/temp{}_input", chip, temp_id), &millicelsius.to_string());
+    self.write(format!("{}/temp{}_max", chip, temp_id), "100000");
+    self.write(format!("{}/temp{}_crit", chip, temp_id), "105000");
+    if let Some(label) = label { self.write(format!("{}/temp{}_label", chip, temp_id), label); }
+    self
+  }
+
+  pub fn hwmon_fan(&self, chip_id: u64, fan_id: u64, rpm: u64) -> &Self {
+    self.write(format!("{}/fan{}_input", Self::hwmon_dir(chip_id), fan_id), &rpm.to_string())
+  }
+
+  pub fn hwmon_in(&self, chip_id: u64, in_id: u64, millivolts: i64) -> &Self {
+    self.write(format!("{}/in{}_input", Self::hwmon_dir(chip_id), in_id), &millivolts.to_string())
+  }
+
+  pub fn hwmon_pwm(&self, chip_id: u64, pwm_id: u64, value: u64, enable: u64) -> &Self {
+    let chip = Self::hwmon_dir(chip_id);
+    self.write(format!("{}/pwm{}", chip, pwm_id), &value.to_string());
+    self.write(format!("{}/pwm{}_enable", chip, pwm_id), &enable.to_string())
+  }
+
+  pub fn devfreq_dir(name: &str) -> String { format!("sys/class/devfreq/{}", name) }
+
+  // A devfreq device of a platform device, running at its lowest frequency. Each frequency
+  // has had one transition to the next, and spent 1s per step in the table.
+  pub fn devfreq(&self, name: &str, governor: &str, governors: &[&str], freqs_hz: &[u64]) -> &Self {
+    let dir = format!("sys/devices/platform/{}/devfreq/{}", name, name);
+    let freqs: Vec<String> = freqs_hz.iter().map(|f| f.to_string()).collect();
+    self.write(format!("{}/cur_freq", dir), &freqs[0]);
+    self.write(format!("{}/min_freq", dir), &freqs[0]);
+    self.write(format!("{}/max_freq", dir), &freqs[freqs.len() - 1]);
+    self.write(format!("{}/available_frequencies", dir), &freqs.join(" "));
+    self.write(format!("{}/governor", dir), governor);
+    self.write(format!("{}/available_governors", dir), &governors.join(" "));
+    let mut trans_stat = vec!["     From  :   To".to_string()];
+    trans_stat.push(format!("           :{}   time(ms)", freqs.iter().map(|f| format!("{:>10}", f)).collect::<String>()));
+    for (i, freq) in freqs.iter().enumerate() {
+      let transitions: String = (0..freqs.len()).map(|j| format!("{:>10}", (j == i + 1) as u64)).collect();
+      trans_stat.push(format!("{}{:>10}:{}{:>10}", if i == 0 { "*" } else { " " }, freq, transitions, 1000 * (i + 1)));
+    }
+    trans_stat.push(format!("Total transition : {}", freqs.len() - 1));
+    self.write(format!("{}/trans_stat", dir), &trans_stat.join("\n"));
+    self.symlink(Self::devfreq_dir(name), format!("../../devices/platform/{}/devfreq/{}", name, name));
+    self
+  }
+
+  pub fn rapl_dir(zone: &str) -> String { format!("sys/class/powercap/intel-rapl:{}", zone) }
+
+  // RAPL zone `intel-rapl:<zone>`, a subzone if `zone` is e.g. `0:1`.
+  pub fn rapl_zone(&self, zone: &str, name: &str, energy_uj: u64) -> &Self {
+    let mut dir = "sys/devices/virtual/powercap/intel-rapl".to_string();
+    if let Some((parent, _)) = zone.split_once(':') { dir.push_str(&format!("/intel-rapl:{}", parent)); }
+    dir.push_str(&format!("/intel-rapl:{}", zone));
+    self.write(format!("{}/name", dir), name);
+    self.write(format!("{}/enabled", dir), "1");
+    self.write(format!("{}/energy_uj", dir), &energy_uj.to_string());
+    self.write(format!("{}/max_energy_range_uj", dir), "262143328850");
+    self.symlink(Self::rapl_dir(zone), format!("../../{}", dir.trim_start_matches("sys/")));
+    self
+  }
+
+  pub fn rapl_constraint(&self, zone: &str, id: u64, name: &str, limit_uw: u64, window_us: u64, max_uw: Option<u64>) -> &Self {
+    let dir = Self::rapl_dir(zone);
+    self.write(format!("{}/constraint_{}_name", dir, id), name);
+    self.write(format!("{}/constraint_{}_power_limit_uw", dir, id), &limit_uw.to_string());
+    self.write(format!("{}/constraint_{}_time_window_us", dir, id), &window_us.to_string());
+    if let Some(max_uw) = max_uw { self.write(format!("{}/constraint_{}_max_power_uw", dir, id), &max_uw.to_string()); }
+    self
+  }
+
+  // 12-thread Intel laptop with intel_pstate, an i915 iGPU, coretemp and a fan.
+  pub fn intel_laptop() -> Self {
+    let fixture = Self::empty();
+    fixture
+      .cpus(12)
+      .topology(12, 2)
+      .cpufreq(12, "intel_pstate", &["powersave", "performance"], 400_000, 4_700_000)
+      .intel_pstate(12)
+      .cpuidle(12, "intel_idle", &[
+        ("POLL", "CPUIDLE CORE POLL IDLE", 0, 0),
+        ("C1E", "MWAIT 0x01", 1, 1),
+        ("C6", "MWAIT 0x20", 170, 600),
+        ("C8", "MWAIT 0x40", 200, 800),
+        ("C10", "MWAIT 0x60", 230, 770),
+      ])
+      .drm_card(0, "0000:00:02.0", "i915")
+      .i915(0)
+      .mkdir("sys/devices/pci0000:00/0000:00:02.0/drm/card0/card0-eDP-1")
+      .symlink("sys/class/drm/card0-eDP-1", "../../devices/pci0000:00/0000:00:02.0/drm/card0/card0-eDP-1")
+      .hwmon(0, "acpitz", "virtual/thermal/thermal_zone0")
+      .hwmon_temp(0, 1, None, 48_000)
+      .hwmon(1, "coretemp", "platform/coretemp.0")
+      .hwmon_temp(1, 1, Some("Package id 0"), 52_000)
+      .hwmon(2, "thinkpad", "platform/thinkpad_hwmon")
+      .hwmon_fan(2, 1, 2900)
+      .hwmon_pwm(2, 1, 128, 2)
+      .rapl_zone("0", "package-0", 71_234_567_890)
+      .rapl_constraint("0", 0, "long_term", 15_000_000, 27_983_872, Some(15_000_000))
+      .rapl_constraint("0", 1, "short_term", 25_000_000, 2_440, Some(0))
+      .rapl_zone("0:0", "core", 31_000_000_000)
+      .rapl_constraint("0:0", 0, "long_term", 0, 976, Some(0))
+      .rapl_zone("0:1", "uncore", 1_200_000_000)
+      .rapl_constraint("0:1", 0, "long_term", 0, 976, Some(0))
+      .rapl_zone("1", "psys", 120_000_000_000)
+      .rapl_constraint("1", 0, "long_term", 0, 27_983_872, Some(0))
+      .rapl_constraint("1", 1, "short_term", 0, 976, Some(0));
+    for core_id in 0..6 {
+      fixture.hwmon_temp(1, core_id + 2, Some(&format!("Core {}", core_id)), 45_000 + 1_000 * core_id as i64);
+    }
+    fixture
+  }
+
+  // 8-thread Intel desktop with intel_pstate, an i915 iGPU and an amdgpu dGPU.
+  pub fn workstation() -> Self {
+    let fixture = Self::empty();
+    fixture
+      .cpus(8)
+      .topology(8, 2)
+      .cpufreq(8, "intel_pstate", &["powersave", "performance"], 800_000, 4_900_000)
+      .intel_pstate(8)
+      .drm_card(0, "0000:00:02.0", "i915")
+      .drm_pci(0, "0000:00:02.0", [0x8086, 0x4680, 0x1043, 0x8694], false)
+      .i915(0)
+      .drm_card(1, "0000:03:00.0", "amdgpu")
+      .drm_pci(1, "0000:03:00.0", [0x1002, 0x73bf, 0x1da2, 0xe438], true)
+      .amdgpu(1)
+      .pci_ids()
+      .hwmon(0, "i915", "pci0000:00/0000:00:02.0")
+      .hwmon_power_max(0, 15_000_000, 15_000_000)
+      .hwmon(1, "amdgpu", "pci0000:00/0000:03:00.0")
+      .hwmon_power_cap(1, 150_000_000, 100_000_000, 186_000_000, 35_000_000);
+    fixture
+  }
+
+  // 8-thread Meteor Lake laptop whose i915 iGPU has a render and a media GT.
+  pub fn meteor_lake() -> Self {
+    let fixture = Self::empty();
+    fixture
+      .cpus(8)
+      .topology(8, 2)
+      .cpufreq(8, "intel_pstate", &["powersave", "performance"], 400_000, 4_800_000)
+      .intel_pstate(8)
+      .drm_card(0, "0000:00:02.0", "i915")
+      .i915(0)
+      .i915_gt(0, 0, 100, 2250)
+      .i915_gt(0, 1, 100, 1000);
+    fixture
+  }
+
+  // 8-thread Intel laptop whose iGPU binds to xe, with a render and a media GT.
+  pub fn xe_laptop() -> Self {
+    let fixture = Self::empty();
+    fixture
+      .cpus(8)
+      .topology(8, 2)
+      .cpufreq(8, "intel_pstate", &["powersave", "performance"], 400_000, 5_100_000)
+      .intel_pstate(8)
+      .drm_card(0, "0000:00:02.0", "xe")
+      .xe_gt(0, 0, 0, 400, 2050)
+      .xe_gt(0, 0, 1, 100, 1000);
+    fixture
+  }
+
+  // 16-thread AMD laptop with amd-pstate-epp.
+  pub fn amd_laptop() -> Self {
+    let fixture = Self::empty();
+    fixture
+      .cpus(16)
+      .topology(16, 2)
+      .cpufreq(16, "amd-pstate-epp", &["powersave", "performance"], 400_000, 5_137_000)
+      .amd_pstate(16)
+      .hwmon(0, "k10temp", "pci0000:00/0000:00:18.3")
+      .hwmon_temp(0, 1, Some("Tctl"), 58_000);
+    fixture
+  }
+
+  // 16-thread AMD desktop with acpi-cpufreq, global boost control, BIOS limits, k10temp and a
+  // Super I/O chip.
+  pub fn amd_desktop() -> Self {
+    let fixture = Self::empty();
+    let governors = ["schedutil", "conservative", "ondemand", "userspace", "powersave", "performance"];
+    fixture
+      .cpus(16)
+      .topology(16, 2)
+      .cpufreq(16, "acpi-cpufreq", &governors, 2_200_000, 3_700_000)
+      .write("sys/devices/system/cpu/cpufreq/boost", "1")
+      .hwmon(0, "k10temp", "pci0000:00/0000:00:18.3")
+      .hwmon_temp(0, 1, Some("Tctl"), 61_500)
+      .hwmon_temp(0, 3, Some("Tccd1"), 55_250)
+      .hwmon(1, "nct6775", "platform/nct6775.656")
+      .hwmon_fan(1, 1, 1100)
+      .hwmon_fan(1, 2, 0)
+      .hwmon_in(1, 0, 1016)
+      .hwmon_in(1, 1, 3344)
+      .hwmon_pwm(1, 1, 96, 5)
+      .hwmon_pwm(1, 2, 255, 5);
+    for policy_id in 0..16 {
+      fixture.write(format!("{}/bios_limit", Self::policy_dir(policy_id)), "3700000");
+      fixture.write(format!("{}/cpuinfo_cur_freq", Self::policy_dir(policy_id)), "2200000");
+    }
+    fixture
+  }
+
+  // 6-core Rockchip RK3399 board with little and big cpufreq clusters, and devfreq scaling of
+  // its Mali GPU and memory controller.
+  pub fn rockchip() -> Self {
+    let fixture = Self::empty();
+    let governors = ["userspace", "powersave", "performance", "simple_ondemand"];
+    fixture
+      .cpus(6)
+      .cpufreq_policy(0, &[0, 1, 2, 3], "cpufreq-dt", &["schedutil", "performance"], 408_000, 1_416_000)
+      .cpufreq_policy(4, &[4, 5], "cpufreq-dt", &["schedutil", "performance"], 408_000, 1_800_000)
+      .devfreq("ff9a0000.gpu", "simple_ondemand", &governors, &[200_000_000, 300_000_000, 400_000_000, 600_000_000, 800_000_000])
+      .devfreq("dmc", "simple_ondemand", &governors, &[328_000_000, 666_000_000, 928_000_000]);
+    fixture
+  }
+
+  // 4-vCPU virtual machine without cpufreq or GPUs.
+  pub fn vm() -> Self {
+    let fixture = Self::empty();
+    fixture.cpus(4);
+    fixture
+  }
+}
@@ -0,0 +1,57 @@
+mod support;
+
+use {
+  cpux::turbo,
+  support::Fixture,
+};
+
+#[test]
+fn intel_pstate_no_turbo() {
+  let fixture = Fixture::intel_laptop();
+  let _root = fixture.enter();
+  assert_eq!(turbo::turbo(0).unwrap(), Some(true));
+  turbo::set_turbo(false).unwrap().unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/no_turbo"), "1");
+  assert_eq!(turbo::turbo(5).unwrap(), Some(false));
+}
+
+#[test]
+fn intel_pstate_off() {
+  let fixture = Fixture::intel_laptop();
+  fixture
+    .write("sys/devices/system/cpu/intel_pstate/status", "off\n")
+    .write("sys/devices/system/cpu/cpufreq/boost", "1\n");
+  let _root = fixture.enter();
+  assert_eq!(turbo::turbo(0).unwrap(), Some(true));
+  turbo::set_turbo(false).unwrap().unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/boost"), "0");
+  assert_eq!(fixture.read("sys/devices/system/cpu/intel_pstate/no_turbo"), "0");
+  assert_eq!(turbo::turbo(0).unwrap(), Some(false));
+}
+
+#[test]
+fn cpufreq_boost() {
+  let fixture = Fixture::amd_desktop();
+  let _root = fixture.enter();
+  turbo::set_turbo(false).unwrap().unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/boost"), "0");
+  assert_eq!(turbo::turbo(15).unwrap(), Some(false));
+}
+
+#[test]
+fn policy_boost() {
+  let fixture = Fixture::amd_laptop();
+  let _root = fixture.enter();
+  turbo::set_turbo(false).unwrap().unwrap();
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy0/boost"), "0");
+  assert_eq!(fixture.read("sys/devices/system/cpu/cpufreq/policy15/boost"), "0");
+  assert_eq!(turbo::turbo(7).unwrap(), Some(false));
+}
+
+#[test]
+fn not_available() {
+  let fixture = Fixture::vm();
+  let _root = fixture.enter();
+  assert!(turbo::turbo(0).unwrap().is_none());
+  assert!(turbo::set_turbo(true).unwrap().is_none());
+}
@@ -0,0 +1,38 @@
+mod support;
+
+use {
+  cpux::{units::Hertz, xe},
+  support::Fixture,
+};
+
+#[test]
+fn cards_tiles_gts() {
+  let fixture = Fixture::xe_laptop();
+  let _root = fixture.enter();
+  assert!(xe::available());
+  assert_eq!(xe::cards().unwrap(), Some(vec![0]));
+  assert_eq!(xe::tiles(0).unwrap(), Some(vec![0]));
+  assert_eq!(xe::gts(0, 0).unwrap(), Some(vec![0, 1]));
+  assert!(xe::gts(0, 1).unwrap().is_none());
+}
+
+#[test]
+fn frequencies() {
+  let fixture = Fixture::xe_laptop();
+  let _root = fixture.enter();
+  assert_eq!(xe::actual(0, 0, 0).unwrap().unwrap().mhz(), 400.);
+  assert_eq!(xe::max_limit(0, 0, 0).unwrap().unwrap().mhz(), 2050.);
+  assert_eq!(xe::min_limit(0, 0, 1).unwrap().unwrap().mhz(), 100.);
+  assert_eq!(xe::optimum_limit(0, 0, 1).unwrap().unwrap().mhz(), 325.);
+  xe::set_max(0, 0, 1, Hertz::from_mhz(800.)).unwrap();
+  assert_eq!(fixture.read("sys/class/drm/card0/device/tile0/gt1/freq0/max_freq"), "800");
+  assert_eq!(xe::max(0, 0, 0).unwrap().unwrap().mhz(), 2050.);
+}
+
+#[test]
+fn no_xe() {
+  let fixture = Fixture::workstation();
+  let _root = fixture.enter();
+  assert!(! xe::available());
+  assert_eq!(xe::cards().unwrap(), Some(vec![]));
+}